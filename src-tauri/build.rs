@@ -1,3 +1,36 @@
+use std::path::Path;
+use std::process::Command;
+
+/// Trimmed stdout of `git <args>`, or `None` outside a git checkout.
+fn git(args: &[&str]) -> Option<String> {
+    Command::new("git")
+        .args(args)
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+        .filter(|out| !out.is_empty())
+}
+
 fn main() {
+    // Build metadata for `app_version_info`; both are left unset outside a
+    // git checkout (e.g. a source tarball).
+    if let Some(commit) = git(&["rev-parse", "--short", "HEAD"]) {
+        println!("cargo:rustc-env=UPDOWN_GIT_COMMIT={}", commit);
+    }
+    if let Some(date) = git(&["log", "-1", "--format=%cs"]) {
+        println!("cargo:rustc-env=UPDOWN_COMMIT_DATE={}", date);
+    }
+    // HEAD only changes on checkout; new commits move the branch's ref,
+    // which lives loose or in packed-refs. Missing files are skipped since
+    // Cargo would rerun this script on every build for them.
+    let mut watched = vec!["HEAD".to_string(), "packed-refs".to_string()];
+    watched.extend(git(&["symbolic-ref", "-q", "HEAD"]));
+    for name in watched {
+        if let Some(path) = git(&["rev-parse", "--git-path", &name]).filter(|p| Path::new(p).exists()) {
+            println!("cargo:rerun-if-changed={}", path);
+        }
+    }
+
     tauri_build::build()
 }
@@ -0,0 +1,71 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+
+use crate::lock::LockExt;
+
+/// Whether any app window has focus. Starts active so background work runs
+/// before the first focus event arrives.
+static ACTIVE: AtomicBool = AtomicBool::new(true);
+
+/// Labels of the windows that currently have focus.
+#[derive(Default)]
+pub struct FocusedWindows(pub Mutex<HashSet<String>>);
+
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Record a focus change for `label`. Returns `Some(active)` when the app as
+/// a whole moved between active (some window focused) and inactive.
+pub fn focus_transition(focused: &mut HashSet<String>, label: &str, has_focus: bool) -> Option<bool> {
+    let was_active = !focused.is_empty();
+    if has_focus {
+        focused.insert(label.to_string());
+    } else {
+        focused.remove(label);
+    }
+    let active = !focused.is_empty();
+    (active != was_active).then_some(active)
+}
+
+/// Called from the window event handler on focus changes and window
+/// destruction. Emits `app-active` / `app-inactive`, and on reactivation
+/// re-checks the recent list for files removed while we were in the
+/// background.
+pub fn window_focus_changed(app: &tauri::AppHandle, label: &str, has_focus: bool) {
+    let transition = {
+        let state = app.state::<FocusedWindows>();
+        let mut focused = state.0.lock_or_recover();
+        focus_transition(&mut focused, label, has_focus)
+    };
+    let Some(active) = transition else { return };
+    ACTIVE.store(active, Ordering::Relaxed);
+    let _ = app.emit(if active { "app-active" } else { "app-inactive" }, ());
+    if active {
+        crate::prune_missing_recent(app);
+    }
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn is_app_active() -> bool {
+    is_active()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transitions_only_when_the_last_window_loses_focus() {
+        let mut focused = HashSet::new();
+        assert_eq!(focus_transition(&mut focused, "main", true), Some(true));
+        assert_eq!(focus_transition(&mut focused, "doc-1", true), None);
+        assert_eq!(focus_transition(&mut focused, "main", false), None);
+        assert_eq!(focus_transition(&mut focused, "doc-1", false), Some(false));
+        assert_eq!(focus_transition(&mut focused, "doc-1", false), None);
+    }
+}
@@ -0,0 +1,756 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Front-matter fields. Scalars are strings; lists are arrays of strings.
+pub type FrontMatter = BTreeMap<String, Value>;
+
+/// An ATX heading. `line` is 1-based and counts from the top of the file,
+/// front matter included.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Heading {
+    pub level: u8,
+    pub text: String,
+    pub line: usize,
+}
+
+/// Written as `generator` in every sidecar, so a re-export knows the file
+/// is ours to replace.
+const SIDECAR_GENERATOR: &str = "UpDown";
+
+/// What `export_metadata` writes to the sidecar.
+#[derive(Debug, Clone, Serialize)]
+pub struct DocumentSummary {
+    /// Always `SIDECAR_GENERATOR`.
+    pub generator: &'static str,
+    pub title: Option<String>,
+    pub headings: Vec<Heading>,
+    pub word_count: usize,
+    pub tags: Vec<String>,
+    pub links: Vec<String>,
+    /// Seconds since the Unix epoch.
+    pub mtime: Option<u64>,
+}
+
+/// A document split into front matter and body, for `read_file_with_meta`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DocumentMeta {
+    pub front_matter: Option<FrontMatter>,
+    /// Front-matter `title`, which the window title prefers to the file name.
+    pub title: Option<String>,
+    pub body: String,
+}
+
+/// Indentation style inferred from a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Indentation {
+    pub uses_tabs: bool,
+    /// Spaces per level (for tabs, the width a tab should display as).
+    pub size: u8,
+}
+
+/// Used when a document has no indented lines to learn from.
+pub const DEFAULT_INDENTATION: Indentation = Indentation { uses_tabs: false, size: 2 };
+
+/// Display width reported for tab-indented documents.
+const TAB_DISPLAY_WIDTH: u8 = 4;
+
+/// Reading speed used when the caller doesn't pass one.
+pub const DEFAULT_WORDS_PER_MINUTE: u32 = 200;
+
+/// Live counts for the status bar, over prose only (no front matter, code
+/// blocks, link targets or images).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DocumentStats {
+    pub words: usize,
+    pub characters: usize,
+    pub characters_no_spaces: usize,
+    /// Rounded up, so any text takes at least a minute.
+    pub reading_time_minutes: usize,
+}
+
+// ── Front matter ──────────────────────────────────────────────────────────────
+
+/// Split a leading block opened by the line `fence` and closed by any of
+/// `closers`, as `split_front_matter` describes.
+fn split_fenced<'a>(text: &'a str, fence: &str, closers: &[&str]) -> (Option<&'a str>, &'a str, usize) {
+    let rest = text
+        .strip_prefix(fence)
+        .and_then(|r| r.strip_prefix('\n').or_else(|| r.strip_prefix("\r\n")));
+    let Some(rest) = rest else {
+        return (None, text, 0);
+    };
+    let mut offset = 0;
+    for (i, line) in rest.split_inclusive('\n').enumerate() {
+        if closers.contains(&line.trim_end()) {
+            let body = &rest[offset + line.len()..];
+            return (Some(&rest[..offset]), body, i + 2);
+        }
+        offset += line.len();
+    }
+    (None, text, 0)
+}
+
+/// Split a leading `---` YAML block from the document. Returns the raw block
+/// (without fences), the body, and how many lines the block occupied.
+pub fn split_front_matter(text: &str) -> (Option<&str>, &str, usize) {
+    split_fenced(text, "---", &["---", "..."])
+}
+
+fn unquote(s: &str) -> String {
+    let s = s.trim();
+    let quoted = s.len() >= 2
+        && ((s.starts_with('"') && s.ends_with('"')) || (s.starts_with('\'') && s.ends_with('\'')));
+    if quoted {
+        s[1..s.len() - 1].to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+/// Parse the simple YAML most notes use: `key: value`, inline lists
+/// `key: [a, b]`, and block lists of `- item` lines.
+pub fn parse_front_matter(raw: &str) -> FrontMatter {
+    let mut out = FrontMatter::new();
+    let mut list_key: Option<String> = None;
+    for line in raw.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if let (Some(key), Some(item)) = (&list_key, trimmed.strip_prefix("- ")) {
+            if let Some(Value::Array(items)) = out.get_mut(key) {
+                items.push(Value::String(unquote(item)));
+            }
+            continue;
+        }
+        list_key = None;
+        if line.starts_with([' ', '\t']) {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let key = key.trim().to_string();
+        let value = value.trim();
+        if value.is_empty() {
+            out.insert(key.clone(), Value::Array(vec![]));
+            list_key = Some(key);
+        } else if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            let items = inner
+                .split(',')
+                .map(unquote)
+                .filter(|s| !s.is_empty())
+                .map(Value::String)
+                .collect();
+            out.insert(key, Value::Array(items));
+        } else {
+            out.insert(key, Value::String(unquote(value)));
+        }
+    }
+    out
+}
+
+/// Whether every top-level line of a YAML block is a `key:` field, a list
+/// item or a comment; anything else means we'd misread it.
+fn yaml_well_formed(raw: &str) -> bool {
+    raw.lines().all(|line| {
+        let t = line.trim();
+        t.is_empty()
+            || t.starts_with('#')
+            || line.starts_with([' ', '\t', '-'])
+            || line.split_once(':').is_some_and(|(key, _)| !key.trim().is_empty())
+    })
+}
+
+/// Parse the flat TOML Hugo-style notes use: `key = value` with quoted or
+/// bare scalars and inline arrays. Keys under a `[table]` header are
+/// prefixed with it (`params.key`). `None` if a line doesn't fit.
+pub fn parse_toml_front_matter(raw: &str) -> Option<FrontMatter> {
+    let mut out = FrontMatter::new();
+    let mut table = String::new();
+    for line in raw.lines() {
+        let t = line.trim();
+        if t.is_empty() || t.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = t.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+            table = format!("{}.", name.trim());
+            continue;
+        }
+        let (key, value) = t.split_once('=')?;
+        let key = unquote(key);
+        if key.is_empty() {
+            return None;
+        }
+        let value = value.trim();
+        let value = match value.strip_prefix('[') {
+            Some(inner) => {
+                let items = inner.strip_suffix(']')?.split(',').map(unquote).filter(|s| !s.is_empty());
+                Value::Array(items.map(Value::String).collect())
+            }
+            None if value.is_empty() => return None,
+            None => Value::String(unquote(value)),
+        };
+        out.insert(format!("{}{}", table, key), value);
+    }
+    Some(out)
+}
+
+/// Front matter in `---` YAML or `+++` TOML fences, and the body after it.
+/// Unclosed or malformed front matter yields `None` and the whole text.
+pub fn extract_front_matter(content: &str) -> (Option<FrontMatter>, &str) {
+    if let (Some(raw), body, _) = split_front_matter(content) {
+        if yaml_well_formed(raw) {
+            return (Some(parse_front_matter(raw)), body);
+        }
+    } else if let (Some(raw), body, _) = split_fenced(content, "+++", &["+++"]) {
+        if let Some(fm) = parse_toml_front_matter(raw) {
+            return (Some(fm), body);
+        }
+    }
+    (None, content)
+}
+
+/// Add `key` as the first front-matter field: `key: value` in a YAML
+/// block, `key = "value"` in a `+++` TOML block, creating a YAML block if
+/// the document has neither. Keeps the document's line endings.
+pub fn insert_front_matter_field(text: &str, key: &str, value: &str) -> String {
+    let nl = if text.contains("\r\n") { "\r\n" } else { "\n" };
+    let (fence, field) = if split_front_matter(text).0.is_some() {
+        ("---", format!("{}: {}{}", key, value, nl))
+    } else if split_fenced(text, "+++", &["+++"]).0.is_some() {
+        let quoted = serde_json::to_string(value).unwrap_or_default();
+        ("+++", format!("{} = {}{}", key, quoted, nl))
+    } else {
+        return format!("---{nl}{key}: {value}{nl}---{nl}{text}");
+    };
+    let open = fence.len() + if text[fence.len()..].starts_with("\r\n") { 2 } else { 1 };
+    format!("{}{}{}", &text[..open], field, &text[open..])
+}
+
+/// The document's front-matter `id`, generating one and saving it through
+/// `write` on first use so the id survives renames and moves.
+pub fn ensure_document_id(path: &Path, write: impl FnOnce(&str) -> Result<(), String>) -> Result<String, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    if let (Some(fm), _) = extract_front_matter(&text) {
+        if let Some(Value::String(id)) = fm.get("id") {
+            if !id.trim().is_empty() {
+                return Ok(id.trim().to_string());
+            }
+        }
+    }
+    let id = uuid::Uuid::new_v4().to_string();
+    write(&insert_front_matter_field(&text, "id", &id))?;
+    Ok(id)
+}
+
+// ── Body scanning ─────────────────────────────────────────────────────────────
+
+/// Lines outside fenced code blocks, as `(0-based line index, line)`.
+pub fn prose_lines(body: &str) -> Vec<(usize, &str)> {
+    let mut out = Vec::new();
+    let mut fence: Option<&str> = None;
+    for (i, line) in body.lines().enumerate() {
+        let t = line.trim_start();
+        let marker = if t.starts_with("```") {
+            Some("```")
+        } else if t.starts_with("~~~") {
+            Some("~~~")
+        } else {
+            None
+        };
+        match (fence, marker) {
+            (Some(open), Some(m)) if open == m => fence = None,
+            (Some(_), _) => {}
+            (None, Some(m)) => fence = Some(m),
+            (None, None) => out.push((i, line)),
+        }
+    }
+    out
+}
+
+fn atx_heading(line: &str) -> Option<(u8, String)> {
+    let t = line.trim_start();
+    if line.len() - t.len() > 3 {
+        return None;
+    }
+    let level = t.chars().take_while(|&c| c == '#').count();
+    let rest = &t[level..];
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+    let text = rest.trim().trim_end_matches('#').trim_end().to_string();
+    Some((level as u8, text))
+}
+
+/// ATX headings outside code blocks. `line_offset` is added to each line
+/// number so callers passing a body can report file line numbers.
+pub fn headings(body: &str, line_offset: usize) -> Vec<Heading> {
+    prose_lines(body)
+        .into_iter()
+        .filter_map(|(i, line)| {
+            atx_heading(line).map(|(level, text)| Heading { level, text, line: i + 1 + line_offset })
+        })
+        .collect()
+}
+
+/// Words of prose, ignoring code blocks and markup-only tokens.
+pub fn word_count(body: &str) -> usize {
+    prose_lines(body)
+        .into_iter()
+        .flat_map(|(_, line)| line.split_whitespace())
+        .filter(|w| w.chars().any(char::is_alphanumeric))
+        .count()
+}
+
+/// The text a reader sees on one prose line: block markers, emphasis, link
+/// targets and images removed. A single forward pass, so it stays linear
+/// on long lines.
+fn visible_text(line: &str) -> String {
+    let mut t = line.trim_start().trim_start_matches(['#', '>', ' ']);
+    let digits = t.chars().take_while(char::is_ascii_digit).count();
+    if let Some(rest) = ["- ", "* ", "+ "].iter().find_map(|m| t.strip_prefix(m)) {
+        t = rest;
+    } else if let Some(rest) = t[digits..].strip_prefix(". ").or_else(|| t[digits..].strip_prefix(") ")).filter(|_| digits > 0) {
+        t = rest;
+    }
+    for task in ["[ ] ", "[x] ", "[X] "] {
+        t = t.strip_prefix(task).unwrap_or(t);
+    }
+
+    let chars: Vec<char> = t.chars().collect();
+    let mut out = String::with_capacity(t.len());
+    let skip_past = |from: usize, close: char| chars[from..].iter().position(|&c| c == close).map(|k| from + k + 1);
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let word_char = |k: Option<usize>| k.and_then(|k| chars.get(k)).is_some_and(|c| c.is_alphanumeric());
+        match c {
+            '!' if next == Some('[') => {
+                let after_alt = skip_past(i + 2, ']').unwrap_or(chars.len());
+                i = match chars.get(after_alt) {
+                    Some('(') => skip_past(after_alt + 1, ')').unwrap_or(chars.len()),
+                    _ => after_alt,
+                };
+            }
+            ']' if next == Some('(') => i = skip_past(i + 2, ')').unwrap_or(chars.len()),
+            '<' => match skip_past(i + 1, '>') {
+                Some(end) if !chars[i + 1..end - 1].iter().any(|c| c.is_whitespace()) => i = end,
+                _ => {
+                    out.push(c);
+                    i += 1;
+                }
+            },
+            '[' | ']' | '*' | '`' | '~' => i += 1,
+            '_' if !(word_char(i.checked_sub(1)) && word_char(Some(i + 1))) => i += 1,
+            _ => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Word, character and reading-time counts for `markdown`.
+pub fn stats(markdown: &str, words_per_minute: u32) -> DocumentStats {
+    let (_, body, _) = split_front_matter(markdown);
+    let mut stats = DocumentStats { words: 0, characters: 0, characters_no_spaces: 0, reading_time_minutes: 0 };
+    for (_, line) in prose_lines(body) {
+        let text = visible_text(line);
+        stats.words += text.split_whitespace().filter(|w| w.chars().any(char::is_alphanumeric)).count();
+        stats.characters += text.trim().chars().count();
+        stats.characters_no_spaces += text.chars().filter(|c| !c.is_whitespace()).count();
+    }
+    stats.reading_time_minutes = stats.words.div_ceil(words_per_minute.max(1) as usize);
+    stats
+}
+
+/// Inline `#tags` (a `#` at a word start followed by a letter).
+fn inline_tags(body: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    for (_, line) in prose_lines(body) {
+        let chars: Vec<char> = line.chars().collect();
+        for i in 0..chars.len() {
+            let at_word_start = i == 0 || chars[i - 1].is_whitespace();
+            if chars[i] != '#' || !at_word_start || !chars.get(i + 1).is_some_and(|c| c.is_alphabetic()) {
+                continue;
+            }
+            let tag: String = chars[i + 1..]
+                .iter()
+                .take_while(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '/'))
+                .collect();
+            out.push(tag);
+        }
+    }
+    out
+}
+
+/// Tags from front matter (`tags:`) and inline `#tags`, deduplicated.
+pub fn tags(front_matter: &FrontMatter, body: &str) -> Vec<String> {
+    let mut out: Vec<String> = match front_matter.get("tags") {
+        Some(Value::Array(items)) => items.iter().filter_map(|v| v.as_str().map(String::from)).collect(),
+        Some(Value::String(s)) => s.split([',', ' ']).filter(|t| !t.is_empty()).map(String::from).collect(),
+        _ => vec![],
+    };
+    for tag in inline_tags(body) {
+        if !out.contains(&tag) {
+            out.push(tag);
+        }
+    }
+    out
+}
+
+/// Link targets in document order: `[text](target)` destinations (images
+/// included) and `[[wikilink]]` names, without any `|alias`.
+pub fn links(body: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    for (_, line) in prose_lines(body) {
+        let mut rest = line;
+        while let Some(start) = rest.find("[[") {
+            let Some(end) = rest[start + 2..].find("]]") else { break };
+            let inner = &rest[start + 2..start + 2 + end];
+            let target = inner.split('|').next().unwrap_or(inner).trim();
+            if !target.is_empty() {
+                out.push(target.to_string());
+            }
+            rest = &rest[start + 2 + end + 2..];
+        }
+        let mut rest = line;
+        while let Some(start) = rest.find("](") {
+            let Some(end) = rest[start + 2..].find(')') else { break };
+            let target = rest[start + 2..start + 2 + end].trim();
+            // Drop an optional title: [x](url "title").
+            let target = target.split_once(' ').map(|(t, _)| t).unwrap_or(target);
+            if !target.is_empty() {
+                out.push(target.trim_matches(['<', '>']).to_string());
+            }
+            rest = &rest[start + 2 + end + 1..];
+        }
+    }
+    out
+}
+
+/// Front-matter `title`, else the first level-1 heading.
+pub fn title(front_matter: &FrontMatter, headings: &[Heading]) -> Option<String> {
+    front_matter
+        .get("title")
+        .and_then(|v| v.as_str())
+        .map(String::from)
+        .or_else(|| headings.iter().find(|h| h.level == 1).map(|h| h.text.clone()))
+}
+
+pub fn summarize(text: &str, mtime: Option<u64>) -> DocumentSummary {
+    let (raw, body, fm_lines) = split_front_matter(text);
+    let fm = raw.map(parse_front_matter).unwrap_or_default();
+    let headings = headings(body, fm_lines);
+    DocumentSummary {
+        generator: SIDECAR_GENERATOR,
+        title: title(&fm, &headings),
+        word_count: word_count(body),
+        tags: tags(&fm, body),
+        links: links(body),
+        headings,
+        mtime,
+    }
+}
+
+/// How far through the body `scroll_line` (1-based, counting front matter)
+/// is: 0.0 at the first body line, 1.0 at the last. Only counts newlines, so
+/// it's cheap enough to call on every scroll.
+pub fn progress_at(text: &str, scroll_line: usize) -> f32 {
+    let (_, body, fm_lines) = split_front_matter(text);
+    let body_lines = body.lines().count();
+    if body_lines <= 1 {
+        return if scroll_line > fm_lines + 1 { 1.0 } else { 0.0 };
+    }
+    let above = scroll_line.saturating_sub(fm_lines + 1).min(body_lines - 1);
+    above as f32 / (body_lines - 1) as f32
+}
+
+/// Guess the indentation style by majority vote: tab- vs space-indented
+/// lines decide `uses_tabs`, and the most common change in indent width
+/// between consecutive lines decides `size`.
+pub fn detect_indent(text: &str) -> Indentation {
+    let (mut tab_lines, mut space_lines) = (0usize, 0usize);
+    let mut deltas = [0usize; 9];
+    let mut prev_width = 0usize;
+    for line in text.lines().filter(|l| !l.trim().is_empty()) {
+        let lead: &str = &line[..line.len() - line.trim_start().len()];
+        if lead.starts_with('\t') {
+            tab_lines += 1;
+        } else if !lead.is_empty() {
+            space_lines += 1;
+        }
+        let width = if lead.contains('\t') { prev_width } else { lead.len() };
+        let delta = width.abs_diff(prev_width);
+        if (1..deltas.len()).contains(&delta) {
+            deltas[delta] += 1;
+        }
+        prev_width = width;
+    }
+    if tab_lines == 0 && space_lines == 0 {
+        return DEFAULT_INDENTATION;
+    }
+    // Ties go to the smaller width, so nested 2/4 indents read as 2.
+    let size = (1..deltas.len())
+        .max_by_key(|&d| (deltas[d], std::cmp::Reverse(d)))
+        .filter(|&d| deltas[d] > 0)
+        .map(|d| d as u8)
+        .unwrap_or(DEFAULT_INDENTATION.size);
+    if tab_lines > space_lines {
+        Indentation { uses_tabs: true, size: TAB_DISPLAY_WIDTH }
+    } else {
+        Indentation { uses_tabs: false, size }
+    }
+}
+
+fn mtime_secs(path: &Path) -> Option<u64> {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// `notes.md.json` for `notes.md`: the whole file name plus `.json`, so the
+/// sidecar never lands on an unrelated file such as `package.json`.
+fn default_sidecar(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".json");
+    path.with_file_name(name)
+}
+
+/// Whether `path` holds a sidecar we wrote earlier.
+fn is_our_sidecar(path: &Path) -> bool {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok())
+        .is_some_and(|json| json["generator"] == SIDECAR_GENERATOR)
+}
+
+/// Write a JSON sidecar describing the document (by default `<name>.json`
+/// next to it, e.g. `notes.md.json`) and return the sidecar's path. The
+/// default sidecar only replaces an earlier export; any other file there is
+/// left alone unless `out_path` names it explicitly.
+#[tauri::command]
+pub fn export_metadata(path: String, out_path: Option<String>) -> Result<String, String> {
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let summary = summarize(&text, mtime_secs(Path::new(&path)));
+    let out = match out_path {
+        Some(out) => PathBuf::from(out),
+        None => {
+            let out = default_sidecar(Path::new(&path));
+            if out.exists() && !is_our_sidecar(&out) {
+                return Err(format!("{} already exists and was not written by UpDown", out.display()));
+            }
+            out
+        }
+    };
+    let json = serde_json::to_string_pretty(&summary).map_err(|e| e.to_string())?;
+    crate::files::write_atomic(&out, json.as_bytes())
+        .map_err(|e| crate::files::describe_write_error(&out.to_string_lossy(), &e))?;
+    Ok(out.to_string_lossy().to_string())
+}
+
+/// Fraction of the document above `scroll_line`, excluding front matter.
+#[tauri::command]
+pub fn reading_progress(text: String, scroll_line: usize) -> f32 {
+    progress_at(&text, scroll_line)
+}
+
+/// Live word, character and reading-time counts, at `words_per_minute`
+/// (default 200).
+#[tauri::command]
+pub fn document_stats(markdown: String, words_per_minute: Option<u32>) -> DocumentStats {
+    stats(&markdown, words_per_minute.unwrap_or(DEFAULT_WORDS_PER_MINUTE))
+}
+
+/// Indentation style of `text`, for configuring the editor on open.
+#[tauri::command]
+pub fn detect_indentation(text: String) -> Indentation {
+    detect_indent(&text)
+}
+
+/// The document at `path` with its front matter parsed out.
+#[tauri::command]
+pub fn read_file_with_meta(path: String) -> Result<DocumentMeta, String> {
+    let bytes = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let text = crate::encoding::decode(&bytes).content;
+    let (front_matter, body) = extract_front_matter(&text);
+    let title = front_matter
+        .as_ref()
+        .and_then(|fm| fm.get("title"))
+        .and_then(|v| v.as_str())
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty());
+    Ok(DocumentMeta { title, body: body.to_string(), front_matter })
+}
+
+/// Stable id for the document at `path`, stored as front-matter `id` so
+/// positions, tags and favorites can follow the file across renames. The
+/// note is saved like any other write, so read-only files are refused.
+#[tauri::command]
+pub fn document_id(app: tauri::AppHandle, path: String) -> Result<String, String> {
+    ensure_document_id(Path::new(&path), |text| crate::write_document(&app, &path, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "---\ntitle: Weekly Notes\ntags: [work, planning]\n---\n# Monday\n\nMet with #design about [the spec](spec.md).\n\n```\n# not a heading\n```\n\n## Follow-up\n\nSee [[Roadmap|the roadmap]].\n";
+
+    #[test]
+    fn sidecar_contains_expected_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("weekly.md");
+        std::fs::write(&path, SAMPLE).unwrap();
+
+        let out = export_metadata(path.to_string_lossy().to_string(), None).unwrap();
+        assert_eq!(Path::new(&out), dir.path().join("weekly.md.json"));
+        // A second export replaces our own sidecar.
+        assert_eq!(export_metadata(path.to_string_lossy().to_string(), None).unwrap(), out);
+
+        let json: Value = serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+        assert_eq!(json["title"], "Weekly Notes");
+        assert_eq!(json["headings"][0]["text"], "Monday");
+        assert_eq!(json["headings"][0]["line"], 5);
+        assert_eq!(json["headings"][1]["level"], 2);
+        assert_eq!(json["headings"].as_array().unwrap().len(), 2);
+        assert_eq!(json["tags"], serde_json::json!(["work", "planning", "design"]));
+        assert_eq!(json["links"], serde_json::json!(["spec.md", "Roadmap"]));
+        assert_eq!(json["word_count"], 11);
+        assert!(json["mtime"].as_u64().is_some());
+    }
+
+    #[test]
+    fn sidecar_never_replaces_a_file_it_did_not_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("package.md");
+        std::fs::write(&path, SAMPLE).unwrap();
+        std::fs::write(dir.path().join("package.json"), "{}").unwrap();
+        let path = path.to_string_lossy().to_string();
+
+        let foreign = dir.path().join("package.md.json");
+        std::fs::write(&foreign, r#"{"name": "mine"}"#).unwrap();
+        assert!(export_metadata(path.clone(), None).is_err());
+        assert_eq!(std::fs::read_to_string(&foreign).unwrap(), r#"{"name": "mine"}"#);
+
+        let out = export_metadata(path, Some(foreign.to_string_lossy().to_string())).unwrap();
+        assert!(is_our_sidecar(Path::new(&out)));
+        assert_eq!(std::fs::read_to_string(dir.path().join("package.json")).unwrap(), "{}");
+    }
+
+    #[test]
+    fn progress_at_start_middle_and_end() {
+        let text = "a\nb\nc\nd\ne\n";
+        assert_eq!(progress_at(text, 1), 0.0);
+        assert_eq!(progress_at(text, 3), 0.5);
+        assert_eq!(progress_at(text, 5), 1.0);
+        assert_eq!(progress_at(text, 99), 1.0);
+    }
+
+    #[test]
+    fn progress_ignores_front_matter() {
+        let text = "---\ntitle: x\n---\na\nb\nc\n";
+        assert_eq!(progress_at(text, 2), 0.0);
+        assert_eq!(progress_at(text, 4), 0.0);
+        assert_eq!(progress_at(text, 5), 0.5);
+        assert_eq!(progress_at(text, 6), 1.0);
+    }
+
+    #[test]
+    fn detects_two_space_indentation() {
+        let text = "- a\n  - b\n    - c\n  - d\n- e\n";
+        assert_eq!(detect_indent(text), Indentation { uses_tabs: false, size: 2 });
+    }
+
+    #[test]
+    fn detects_tabs() {
+        let text = "- a\n\t- b\n\t\t- c\n- d\n";
+        assert!(detect_indent(text).uses_tabs);
+    }
+
+    #[test]
+    fn mixed_document_follows_the_majority() {
+        let text = "1. a\n    code\n    more\n\tstray tab\n2. b\n    x\n";
+        assert_eq!(detect_indent(text), Indentation { uses_tabs: false, size: 4 });
+        assert_eq!(detect_indent("# Title\n\nflat text\n"), DEFAULT_INDENTATION);
+    }
+
+    #[test]
+    fn malformed_front_matter_is_left_in_body() {
+        let (raw, body, lines) = split_front_matter("---\ntitle: x\nno closing fence");
+        assert_eq!((raw, lines), (None, 0));
+        assert!(body.starts_with("---"));
+    }
+
+    #[test]
+    fn stats_count_prose_not_markup() {
+        let md = "---\ntitle: Skip me\n---\n# Hello *world*\n\n- [x] See [the docs](https://example.com/a/very/long/url) now\n\n![a diagram](img.png)\n\n```\nlet code = 1;\n```\n\n1. snake_case stays one word\n";
+        let s = stats(md, DEFAULT_WORDS_PER_MINUTE);
+        assert_eq!(s.words, 10);
+        assert_eq!(s.characters, "Hello world".len() + "See the docs now".len() + "snake_case stays one word".len());
+        assert_eq!(s.characters_no_spaces, "Helloworld".len() + "Seethedocsnow".len() + "snake_casestaysoneword".len());
+        assert_eq!(s.reading_time_minutes, 1);
+        assert_eq!(stats(&"word ".repeat(450), 200).reading_time_minutes, 3);
+        assert_eq!(stats("", 200).reading_time_minutes, 0);
+    }
+
+    #[test]
+    fn yaml_and_toml_front_matter_are_extracted() {
+        let (fm, body) = extract_front_matter(SAMPLE);
+        let fm = fm.unwrap();
+        assert_eq!(fm["title"], "Weekly Notes");
+        assert!(body.starts_with("# Monday"));
+
+        let toml = "+++\ntitle = \"Release notes\"\ndate = 2024-05-01\ntags = [\"a\", 'b']\n[params]\ndraft = true\n+++\nBody\n";
+        let (fm, body) = extract_front_matter(toml);
+        let fm = fm.unwrap();
+        assert_eq!(fm["title"], "Release notes");
+        assert_eq!(fm["date"], "2024-05-01");
+        assert_eq!(fm["tags"], serde_json::json!(["a", "b"]));
+        assert_eq!(fm["params.draft"], "true");
+        assert_eq!(body, "Body\n");
+
+        for malformed in ["+++\nnot toml at all\n+++\nBody\n", "---\njust prose here\n---\nBody\n", "+++\ntitle = \"x\"\n"] {
+            assert_eq!(extract_front_matter(malformed), (None, malformed));
+        }
+    }
+
+    #[test]
+    fn document_id_is_written_once_and_stable() {
+        let dir = tempfile::tempdir().unwrap();
+        let bare = dir.path().join("bare.md");
+        let with_fm = dir.path().join("weekly.md");
+        std::fs::write(&bare, "# Bare\n").unwrap();
+        std::fs::write(&with_fm, SAMPLE).unwrap();
+
+        let ensure = |path: &Path| {
+            ensure_document_id(path, |text| crate::files::write_atomic(path, text.as_bytes()).map_err(|e| e.to_string()))
+        };
+
+        let id = ensure(&bare).unwrap();
+        assert_eq!(id.len(), 36);
+        assert_eq!(std::fs::read_to_string(&bare).unwrap(), format!("---\nid: {}\n---\n# Bare\n", id));
+        assert_eq!(ensure(&bare).unwrap(), id);
+
+        let id = ensure(&with_fm).unwrap();
+        let text = std::fs::read_to_string(&with_fm).unwrap();
+        assert!(text.starts_with(&format!("---\nid: {}\ntitle: Weekly Notes\n", id)));
+        assert_eq!(summarize(&text, None).title.as_deref(), Some("Weekly Notes"));
+        assert_eq!(ensure(&with_fm).unwrap(), id);
+
+        let hugo = dir.path().join("post.md");
+        std::fs::write(&hugo, "+++\r\ntitle = \"Post\"\r\n+++\r\nBody\r\n").unwrap();
+        let id = ensure(&hugo).unwrap();
+        let text = std::fs::read_to_string(&hugo).unwrap();
+        assert_eq!(text, format!("+++\r\nid = \"{}\"\r\ntitle = \"Post\"\r\n+++\r\nBody\r\n", id));
+        assert_eq!(ensure(&hugo).unwrap(), id);
+        assert!(ensure_document_id(&dir.path().join("new.md"), |_| Err("read-only".into())).is_err());
+    }
+}
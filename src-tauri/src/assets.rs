@@ -0,0 +1,108 @@
+use std::io::Write;
+use std::path::Path;
+
+/// Folder beside the note that pasted images go into.
+const ASSETS_DIR: &str = "assets";
+
+/// Larger pastes are refused rather than copied into the notes folder.
+const MAX_IMAGE_BYTES: usize = 50 * 1024 * 1024;
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// The image type of `bytes` from its signature, as the canonical file
+/// extension, or `None` if it isn't an image we recognise.
+pub fn sniff_image(bytes: &[u8]) -> Option<&'static str> {
+    let ext = match bytes {
+        [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n', ..] => "png",
+        [0xff, 0xd8, 0xff, ..] => "jpg",
+        [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => "gif",
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => "webp",
+        [b'B', b'M', ..] => "bmp",
+        [b'I', b'I', b'*', 0, ..] | [b'M', b'M', 0, b'*', ..] => "tiff",
+        [0, 0, 1, 0, ..] => "ico",
+        [_, _, _, _, b'f', b't', b'y', b'p', b'a', b'v', b'i', b'f', ..] => "avif",
+        _ => {
+            let head = String::from_utf8_lossy(&bytes[..bytes.len().min(1024)]);
+            let head = head.trim_start_matches('\u{feff}').trim_start();
+            let svg = head.starts_with("<svg") || (head.starts_with("<?xml") && head.contains("<svg"));
+            return svg.then_some("svg");
+        }
+    };
+    Some(ext)
+}
+
+/// The extension to save with: the suggested spelling when it names the
+/// detected type (`jpeg` for a JPEG), the canonical one otherwise.
+fn extension_for(detected: &'static str, suggested: &str) -> String {
+    let suggested = suggested.trim_start_matches('.').to_ascii_lowercase();
+    let alias = matches!((detected, suggested.as_str()), ("jpg", "jpeg") | ("tiff", "tif"));
+    if alias {
+        suggested
+    } else {
+        detected.to_string()
+    }
+}
+
+/// `image-<timestamp>.<ext>`, then `image-<timestamp>-2.<ext>`, … for
+/// attempt `n`.
+fn image_name(stamp: &str, ext: &str, n: usize) -> String {
+    if n == 1 {
+        format!("image-{}.{}", stamp, ext)
+    } else {
+        format!("image-{}-{}.{}", stamp, n, ext)
+    }
+}
+
+/// Write `bytes` to a new file in the `assets` folder beside `note` and
+/// return its path relative to the note's folder, `/`-separated.
+pub fn save_image_beside(note: &Path, bytes: &[u8], suggested_ext: &str, stamp: &str) -> Result<String, String> {
+    if bytes.len() > MAX_IMAGE_BYTES {
+        return Err(format!("Image is larger than {} MB", MAX_IMAGE_BYTES / (1024 * 1024)));
+    }
+    let detected = sniff_image(bytes).ok_or("The pasted data is not a supported image")?;
+    let ext = extension_for(detected, suggested_ext);
+    let folder = note.parent().ok_or_else(|| format!("{} has no parent folder", note.display()))?;
+    let dir = folder.join(ASSETS_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let name = crate::files::first_free_name(|n| {
+        let name = image_name(stamp, &ext, n);
+        crate::files::create_new(&dir.join(&name), |f| f.write_all(bytes)).map(|r| r.map(|()| name))
+    })?;
+    Ok(format!("{}/{}", ASSETS_DIR, name))
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// Store a pasted or inserted image beside `note_path` and return the
+/// relative path to link it with. The type comes from the bytes;
+/// `suggested_ext` only picks between spellings of it.
+#[tauri::command]
+pub fn save_pasted_image(note_path: String, image_bytes: Vec<u8>, suggested_ext: String) -> Result<String, String> {
+    if note_path.trim().is_empty() {
+        return Err("Save the note before adding images".to_string());
+    }
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    save_image_beside(Path::new(&note_path), &image_bytes, &suggested_ext, &stamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+    #[test]
+    fn images_land_in_assets_with_unique_names_and_sniffed_types() {
+        let dir = tempfile::tempdir().unwrap();
+        let note = dir.path().join("note.md");
+
+        assert_eq!(save_image_beside(&note, PNG, "jpg", "20240309-140500").unwrap(), "assets/image-20240309-140500.png");
+        assert_eq!(save_image_beside(&note, PNG, "png", "20240309-140500").unwrap(), "assets/image-20240309-140500-2.png");
+        assert_eq!(std::fs::read(dir.path().join("assets/image-20240309-140500.png")).unwrap(), PNG);
+
+        let jpeg = [0xff, 0xd8, 0xff, 0xe0, 0, 0x10];
+        assert_eq!(save_image_beside(&note, &jpeg, ".JPEG", "x").unwrap(), "assets/image-x.jpeg");
+        assert_eq!(sniff_image(b"<?xml version=\"1.0\"?>\n<svg xmlns=\"http://www.w3.org/2000/svg\"/>"), Some("svg"));
+        assert!(save_image_beside(&note, b"#!/bin/sh\nrm -rf ~", "png", "y").is_err());
+    }
+}
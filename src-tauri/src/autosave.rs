@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+use crate::lock::LockExt;
+use crate::watch::{snapshot, Snapshot};
+
+/// Bounds `interval_ms` is clamped to.
+pub const INTERVAL_LIMITS_MS: (u64, u64) = (250, 60_000);
+
+/// The `auto_save` setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoSave {
+    pub enabled: bool,
+    /// Quiet time after the last edit before the buffer is written.
+    pub interval_ms: u64,
+}
+
+impl Default for AutoSave {
+    fn default() -> Self {
+        AutoSave { enabled: true, interval_ms: 1500 }
+    }
+}
+
+impl AutoSave {
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms.clamp(INTERVAL_LIMITS_MS.0, INTERVAL_LIMITS_MS.1))
+    }
+}
+
+/// A buffer waiting for its timer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pending {
+    /// Bumped on every edit; only the newest timer writes.
+    generation: u64,
+    content: String,
+    /// The file as the frontend loaded or last saved it. If it differs at
+    /// write time, someone else changed the file and the save is held back.
+    expected: Snapshot,
+}
+
+/// Buffers waiting to be auto-saved, keyed by path.
+#[derive(Default)]
+pub struct AutoSaves(pub Mutex<HashMap<String, Pending>>);
+
+/// What a timer that fires should do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Due {
+    Write(String),
+    /// The file changed on disk since the frontend loaded or saved it.
+    Conflict,
+}
+
+/// Sent as `auto-saved` after a write.
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoSaved {
+    pub path: String,
+    /// Unix time in milliseconds.
+    pub timestamp: i64,
+    /// The file's new stamp, to pass back with the next edit.
+    pub disk: Snapshot,
+}
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// Queue `content` for `path`, expected to still be as `expected` on disk.
+/// Returns the generation the new timer must match.
+pub fn schedule(pending: &mut HashMap<String, Pending>, path: &str, content: String, expected: Snapshot) -> u64 {
+    let entry = pending.entry(path.to_string()).or_insert(Pending { generation: 0, content: String::new(), expected });
+    entry.generation += 1;
+    entry.content = content;
+    entry.expected = expected;
+    entry.generation
+}
+
+/// What to do once timer `generation` fires: nothing if a later edit has
+/// superseded it, otherwise write the content unless the file on disk is
+/// no longer as the frontend last saw it.
+pub fn take_due(pending: &mut HashMap<String, Pending>, path: &str, generation: u64) -> Option<Due> {
+    if pending.get(path)?.generation != generation {
+        return None;
+    }
+    let entry = pending.remove(path)?;
+    Some(if snapshot(Path::new(path)) == entry.expected { Due::Write(entry.content) } else { Due::Conflict })
+}
+
+fn load_auto_save(app: &tauri::AppHandle) -> AutoSave {
+    crate::settings::settings_path(app)
+        .map(|p| crate::settings::load_settings(&p).auto_save)
+        .unwrap_or_default()
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// Write `content` to `path` once edits have paused for the configured
+/// interval, then emit `auto-saved`. `disk` is the file's stamp as the
+/// frontend loaded or last saved it; if the file no longer matches,
+/// `auto-save-conflict` is emitted with the path instead of writing. Each
+/// call restarts the timer. Returns false when auto-save is off or the
+/// buffer is untitled (empty `path`).
+#[tauri::command]
+pub fn schedule_autosave(app: tauri::AppHandle, path: String, content: String, disk: Snapshot) -> bool {
+    let config = load_auto_save(&app);
+    if !config.enabled || path.is_empty() {
+        return false;
+    }
+    let generation = schedule(&mut app.state::<AutoSaves>().0.lock_or_recover(), &path, content, disk);
+    std::thread::spawn(move || {
+        std::thread::sleep(config.interval());
+        let due = take_due(&mut app.state::<AutoSaves>().0.lock_or_recover(), &path, generation);
+        match due {
+            None => {}
+            Some(Due::Conflict) => {
+                let _ = app.emit("auto-save-conflict", path);
+            }
+            Some(Due::Write(content)) => match crate::write_document(&app, &path, &content) {
+                Ok(()) => {
+                    let timestamp = chrono::Utc::now().timestamp_millis();
+                    let disk = snapshot(Path::new(&path));
+                    let _ = app.emit("auto-saved", AutoSaved { path, timestamp, disk });
+                }
+                Err(e) => log::error!("Auto-save failed: {}", e),
+            },
+        }
+    });
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_latest_edit_is_written() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("note.md");
+        std::fs::write(&file, "one").unwrap();
+        let path = file.to_string_lossy().to_string();
+        let loaded = snapshot(&file);
+        let mut pending = HashMap::new();
+
+        let first = schedule(&mut pending, &path, "two".into(), loaded);
+        let second = schedule(&mut pending, &path, "three".into(), loaded);
+        assert_eq!(take_due(&mut pending, &path, first), None);
+        assert_eq!(take_due(&mut pending, &path, second), Some(Due::Write("three".to_string())));
+        assert!(pending.is_empty());
+
+        assert_eq!(AutoSave { enabled: true, interval_ms: 5 }.interval(), Duration::from_millis(250));
+    }
+
+    #[test]
+    fn external_edits_before_typing_or_after_a_conflict_are_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("note.md");
+        std::fs::write(&file, "one").unwrap();
+        let path = file.to_string_lossy().to_string();
+        let loaded = snapshot(&file);
+        let mut pending = HashMap::new();
+
+        // Changed on disk after the frontend loaded it, before any typing.
+        std::fs::write(&file, "changed elsewhere").unwrap();
+        let generation = schedule(&mut pending, &path, "two".into(), loaded);
+        assert_eq!(take_due(&mut pending, &path, generation), Some(Due::Conflict));
+
+        // The next burst still compares against what the frontend loaded.
+        let generation = schedule(&mut pending, &path, "three".into(), loaded);
+        assert_eq!(take_due(&mut pending, &path, generation), Some(Due::Conflict));
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "changed elsewhere");
+
+        // Once the frontend reloads, auto-save resumes.
+        let generation = schedule(&mut pending, &path, "four".into(), snapshot(&file));
+        assert_eq!(take_due(&mut pending, &path, generation), Some(Due::Write("four".to_string())));
+    }
+}
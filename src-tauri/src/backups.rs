@@ -0,0 +1,177 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+/// Backups kept per file; older ones are pruned after each save.
+pub const MAX_BACKUPS_PER_FILE: usize = 20;
+
+/// Cap on the whole backup directory; the oldest backups (of any file) go
+/// first once it is exceeded.
+pub const MAX_BACKUP_BYTES: u64 = 200 * 1024 * 1024;
+
+/// Backup ids are UTC timestamps in this format, so they sort by age.
+const ID_FORMAT: &str = "%Y%m%dT%H%M%S%3fZ";
+
+/// One saved version of a file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BackupEntry {
+    /// Pass back to `restore_backup`.
+    pub id: String,
+    /// Unix time in milliseconds.
+    pub created: i64,
+    pub size: u64,
+}
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// FNV-1a of the path, as 16 hex digits: a stable, filesystem-safe
+/// directory name for the file's backups.
+fn path_hash(path: &str) -> String {
+    let hash = path.bytes().fold(0xcbf2_9ce4_8422_2325u64, |h, b| (h ^ b as u64).wrapping_mul(0x0100_0000_01b3));
+    format!("{:016x}", hash)
+}
+
+fn backups_root(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|d| d.join("backups"))
+        .map_err(|e| format!("Cannot locate the app data directory: {}", e))
+}
+
+/// Backup files in `dir`, oldest first.
+fn backup_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).filter(|p| p.is_file()).collect())
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+fn entry_for(file: &Path) -> Option<BackupEntry> {
+    let id = file.file_stem()?.to_string_lossy().to_string();
+    let created = chrono::NaiveDateTime::parse_from_str(&id, ID_FORMAT).ok()?.and_utc().timestamp_millis();
+    let size = std::fs::metadata(file).ok()?.len();
+    Some(BackupEntry { id, created, size })
+}
+
+/// Copy the current contents of `path` into `root` before it is
+/// overwritten, then prune. A file that doesn't exist yet has nothing to
+/// back up.
+pub fn back_up_in(root: &Path, path: &str, now: chrono::DateTime<chrono::Utc>) -> Result<(), String> {
+    if !Path::new(path).is_file() {
+        return Ok(());
+    }
+    let dir = root.join(path_hash(path));
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let dest = dir.join(format!("{}.md", now.format(ID_FORMAT)));
+    std::fs::copy(path, &dest).map_err(|e| format!("Failed to back up {}: {}", path, e))?;
+    prune(root, &dir);
+    Ok(())
+}
+
+/// Keep `MAX_BACKUPS_PER_FILE` in `dir`, then trim the oldest backups
+/// under `root` until it fits in `MAX_BACKUP_BYTES`.
+fn prune(root: &Path, dir: &Path) {
+    let files = backup_files(dir);
+    for old in &files[..files.len().saturating_sub(MAX_BACKUPS_PER_FILE)] {
+        let _ = std::fs::remove_file(old);
+    }
+
+    let mut all: Vec<(String, PathBuf, u64)> = std::fs::read_dir(root)
+        .map(|dirs| dirs.flatten().flat_map(|d| backup_files(&d.path())).collect::<Vec<_>>())
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|p| {
+            let name = p.file_name()?.to_string_lossy().to_string();
+            let size = std::fs::metadata(&p).ok()?.len();
+            Some((name, p, size))
+        })
+        .collect();
+    let mut total: u64 = all.iter().map(|(_, _, size)| size).sum();
+    all.sort();
+    for (_, path, size) in all {
+        if total <= MAX_BACKUP_BYTES {
+            break;
+        }
+        if std::fs::remove_file(&path).is_ok() {
+            total -= size;
+        }
+    }
+}
+
+/// Backups of `path` under `root`, newest first.
+pub fn list_in(root: &Path, path: &str) -> Vec<BackupEntry> {
+    let mut entries: Vec<BackupEntry> =
+        backup_files(&root.join(path_hash(path))).iter().filter_map(|f| entry_for(f)).collect();
+    entries.reverse();
+    entries
+}
+
+/// The stored contents of backup `id` of `path`.
+pub fn read_in(root: &Path, path: &str, id: &str) -> Result<String, String> {
+    if id.is_empty() || id.contains(['/', '\\', '.']) {
+        return Err(format!("Invalid backup id: {}", id));
+    }
+    let file = root.join(path_hash(path)).join(format!("{}.md", id));
+    std::fs::read_to_string(&file).map_err(|e| format!("Failed to read backup {}: {}", id, e))
+}
+
+/// Back up `path` before a save if backups are enabled. Failures are
+/// logged rather than blocking the save.
+pub fn before_save(app: &tauri::AppHandle, path: &str) {
+    let enabled = crate::settings::settings_path(app)
+        .map(|p| crate::settings::load_settings(&p).enable_backups)
+        .unwrap_or(false);
+    if !enabled {
+        return;
+    }
+    if let Err(e) = backups_root(app).and_then(|root| back_up_in(&root, path, chrono::Utc::now())) {
+        log::warn!("{}", e);
+    }
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// Saved versions of `path`, newest first.
+#[tauri::command]
+pub fn list_backups(app: tauri::AppHandle, path: String) -> Result<Vec<BackupEntry>, String> {
+    Ok(list_in(&backups_root(&app)?, &path))
+}
+
+/// Overwrite `path` with backup `backup_id`. The current contents are backed
+/// up first (when backups are on), so a restore can itself be undone.
+#[tauri::command]
+pub fn restore_backup(app: tauri::AppHandle, path: String, backup_id: String) -> Result<(), String> {
+    let content = read_in(&backups_root(&app)?, &path, &backup_id)?;
+    before_save(&app, &path);
+    crate::write_document(&app, &path, &content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backups_are_listed_newest_first_and_capped() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("backups");
+        let file = dir.path().join("note.md");
+        let path = file.to_string_lossy().to_string();
+        let start = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+
+        back_up_in(&root, &path, start).unwrap();
+        assert!(list_in(&root, &path).is_empty());
+
+        for i in 0..(MAX_BACKUPS_PER_FILE + 3) {
+            std::fs::write(&file, format!("version {}", i)).unwrap();
+            back_up_in(&root, &path, start + chrono::Duration::seconds(i as i64)).unwrap();
+        }
+        let entries = list_in(&root, &path);
+        assert_eq!(entries.len(), MAX_BACKUPS_PER_FILE);
+        assert_eq!(entries[0].created, (start.timestamp() + MAX_BACKUPS_PER_FILE as i64 + 2) * 1000);
+        assert_eq!(read_in(&root, &path, &entries[0].id).unwrap(), format!("version {}", MAX_BACKUPS_PER_FILE + 2));
+        assert_eq!(read_in(&root, &path, &entries.last().unwrap().id).unwrap(), "version 3");
+        assert!(read_in(&root, &path, "../settings").is_err());
+        assert_eq!(path_hash(&path).len(), 16);
+    }
+}
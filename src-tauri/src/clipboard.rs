@@ -0,0 +1,123 @@
+use serde::Serialize;
+use tauri::image::Image;
+use tauri::{Emitter, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::documents::Documents;
+use crate::lock::LockExt;
+
+/// Widest image the preview may be rendered at, in CSS pixels.
+pub const MAX_IMAGE_WIDTH: u32 = 4096;
+
+/// Device-pixel ratio bounds for image renders.
+pub const MIN_IMAGE_SCALE: f32 = 0.5;
+pub const MAX_IMAGE_SCALE: f32 = 4.0;
+
+/// Sent to a window as `render-image`: the frontend renders its preview at
+/// `width` CSS pixels and `scale`, then hands the PNG to
+/// `write_clipboard_image`. `pixel_width` is the resulting bitmap width.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageRender {
+    pub width: u32,
+    pub scale: f32,
+    pub pixel_width: u32,
+}
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// Validate the requested render size and compute the bitmap width.
+pub fn image_render(width: u32, scale: f32) -> Result<ImageRender, String> {
+    if width == 0 || width > MAX_IMAGE_WIDTH {
+        return Err(format!("Image width must be between 1 and {} pixels", MAX_IMAGE_WIDTH));
+    }
+    if !(MIN_IMAGE_SCALE..=MAX_IMAGE_SCALE).contains(&scale) {
+        return Err(format!("Image scale must be between {} and {}", MIN_IMAGE_SCALE, MAX_IMAGE_SCALE));
+    }
+    let pixel_width = (width as f32 * scale).round() as u32;
+    Ok(ImageRender { width, scale, pixel_width })
+}
+
+/// Decode the PNG the preview rendered, for the clipboard.
+fn decode_png(png: &[u8]) -> Result<Image<'static>, String> {
+    if !png.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Err("Clipboard image is not a PNG".to_string());
+    }
+    Image::from_bytes(png).map_err(|e| format!("Failed to read the rendered image: {}", e))
+}
+
+/// `path` as text for the clipboard: absolute, or relative to `root` when
+/// one is given (`/`-separated on every platform).
+pub fn path_text(path: &str, root: Option<&str>) -> Result<String, String> {
+    if path.is_empty() {
+        return Err("No file is open".to_string());
+    }
+    let Some(root) = root else { return Ok(path.to_string()) };
+    std::path::Path::new(path)
+        .strip_prefix(root)
+        .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+        .map_err(|_| format!("{} is not inside {}", path, root))
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// Copy the calling window's rendered preview to the clipboard as a PNG.
+/// The window renders the image itself (see `ImageRender`).
+#[tauri::command]
+pub fn copy_as_image(window: tauri::WebviewWindow, width: u32, scale: f32) -> Result<(), String> {
+    let render = image_render(width, scale)?;
+    let app = window.app_handle();
+    let has_document = app
+        .state::<Documents>()
+        .0
+        .lock_or_recover()
+        .get(window.label())
+        .is_some_and(|d| d.path.is_some() || d.dirty);
+    if !has_document {
+        return Err("No document is open".to_string());
+    }
+    window
+        .emit_to(window.label(), "render-image", render)
+        .map_err(|e| format!("Failed to request image render: {}", e))
+}
+
+/// Copy the open file's path, absolute or relative to `relative_to` (the
+/// folder-panel root).
+#[tauri::command]
+pub fn copy_file_path(app: tauri::AppHandle, path: String, relative_to: Option<String>) -> Result<(), String> {
+    app.clipboard()
+        .write_text(path_text(&path, relative_to.as_deref())?)
+        .map_err(|e| format!("Failed to copy text: {}", e))
+}
+
+/// Receives the PNG rendered in response to `render-image`.
+#[tauri::command]
+pub fn write_clipboard_image(app: tauri::AppHandle, png: Vec<u8>) -> Result<(), String> {
+    app.clipboard()
+        .write_image(&decode_png(&png)?)
+        .map_err(|e| format!("Failed to copy image: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_dimensions_follow_scale_and_are_bounded() {
+        assert_eq!(image_render(800, 2.0).unwrap().pixel_width, 1600);
+        assert_eq!(image_render(801, 1.5).unwrap().pixel_width, 1202);
+        assert!(image_render(0, 1.0).is_err());
+        assert!(image_render(MAX_IMAGE_WIDTH + 1, 1.0).is_err());
+        assert!(image_render(800, 8.0).is_err());
+        assert!(decode_png(b"not a png").is_err());
+        assert!(decode_png(b"\x89PNG\r\n\x1a\ntruncated").is_err());
+    }
+
+    #[test]
+    fn path_text_is_absolute_or_relative_to_the_root() {
+        assert_eq!(path_text("/notes/a/b.md", None).unwrap(), "/notes/a/b.md");
+        assert_eq!(path_text("/notes/a/b.md", Some("/notes")).unwrap(), "a/b.md");
+        assert!(path_text("/other/b.md", Some("/notes")).is_err());
+        assert!(path_text("", None).is_err());
+    }
+}
@@ -0,0 +1,257 @@
+/// ProgID UpDown registers for Markdown files on Windows.
+#[cfg(any(target_os = "windows", test))]
+const PROG_ID: &str = "UpDown.Markdown";
+
+/// MIME types xdg-mime associates with the desktop entry.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const MIME_TYPES: &[&str] = &["text/markdown", "text/x-markdown"];
+
+/// Uniform type identifier of `.md` and `.markdown` files.
+#[cfg(target_os = "macos")]
+const MARKDOWN_UTI: &str = "net.daringfireball.markdown";
+
+/// Result message when the association was written.
+const DONE: &str = "UpDown is now the default app for Markdown files.";
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// Whether a handler name reported by the OS is ours. Bundle ids and
+/// ProgIDs are case-insensitive.
+fn is_ours(reported: &str, ours: &str) -> bool {
+    reported.trim().eq_ignore_ascii_case(ours)
+}
+
+/// The data of the first `REG_SZ` value in `reg query` output.
+#[cfg(any(target_os = "windows", test))]
+fn reg_value(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let (_, data) = line.split_once("REG_SZ")?;
+        Some(data.trim().to_string())
+    })
+}
+
+#[cfg(not(target_os = "macos"))]
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!("{} failed: {}", program, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// macOS: Launch Services, keyed by our bundle id.
+#[cfg(target_os = "macos")]
+mod launch_services {
+    use std::ffi::{c_char, c_void};
+
+    const UTF8: u32 = 0x0800_0100;
+    const ROLES_ALL: u32 = 0xFFFF_FFFF;
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFStringCreateWithBytes(
+            alloc: *const c_void,
+            bytes: *const u8,
+            len: isize,
+            encoding: u32,
+            external: u8,
+        ) -> *const c_void;
+        fn CFStringGetCString(string: *const c_void, buffer: *mut c_char, size: isize, encoding: u32) -> u8;
+        fn CFRelease(cf: *const c_void);
+    }
+
+    #[link(name = "CoreServices", kind = "framework")]
+    extern "C" {
+        fn LSSetDefaultRoleHandlerForContentType(content_type: *const c_void, role: u32, handler: *const c_void) -> i32;
+        fn LSCopyDefaultRoleHandlerForContentType(content_type: *const c_void, role: u32) -> *const c_void;
+    }
+
+    /// An owned `CFStringRef`.
+    struct CfString(*const c_void);
+
+    impl CfString {
+        fn new(s: &str) -> CfString {
+            CfString(unsafe { CFStringCreateWithBytes(std::ptr::null(), s.as_ptr(), s.len() as isize, UTF8, 0) })
+        }
+    }
+
+    impl Drop for CfString {
+        fn drop(&mut self) {
+            if !self.0.is_null() {
+                unsafe { CFRelease(self.0) };
+            }
+        }
+    }
+
+    pub fn set_default(uti: &str, bundle_id: &str) -> Result<(), String> {
+        let (uti, bundle_id) = (CfString::new(uti), CfString::new(bundle_id));
+        match unsafe { LSSetDefaultRoleHandlerForContentType(uti.0, ROLES_ALL, bundle_id.0) } {
+            0 => Ok(()),
+            status => Err(format!("Launch Services refused the change (error {})", status)),
+        }
+    }
+
+    pub fn default_handler(uti: &str) -> Option<String> {
+        let uti = CfString::new(uti);
+        let handler = CfString(unsafe { LSCopyDefaultRoleHandlerForContentType(uti.0, ROLES_ALL) });
+        if handler.0.is_null() {
+            return None;
+        }
+        let mut buffer = [0 as c_char; 256];
+        let ok = unsafe { CFStringGetCString(handler.0, buffer.as_mut_ptr(), buffer.len() as isize, UTF8) };
+        (ok != 0).then(|| unsafe { std::ffi::CStr::from_ptr(buffer.as_ptr()) }.to_string_lossy().into_owned())
+    }
+}
+
+/// Windows: per-user associations under `HKCU\Software\Classes`, which need
+/// no elevation.
+#[cfg(target_os = "windows")]
+mod registry {
+    use super::{reg_value, run, PROG_ID};
+
+    #[link(name = "shell32")]
+    extern "system" {
+        fn SHChangeNotify(event: i32, flags: u32, item1: *const std::ffi::c_void, item2: *const std::ffi::c_void);
+    }
+
+    const SHCNE_ASSOCCHANGED: i32 = 0x0800_0000;
+
+    fn set(key: &str, value: &str) -> Result<(), String> {
+        run("reg", &["add", &format!("HKCU\\Software\\Classes\\{}", key), "/ve", "/d", value, "/f"]).map(|_| ())
+    }
+
+    pub fn set_default(exe: &str) -> Result<(), String> {
+        set(PROG_ID, "Markdown Document")?;
+        set(&format!("{}\\shell\\open\\command", PROG_ID), &format!("\"{}\" \"%1\"", exe))?;
+        for ext in [".md", ".markdown"] {
+            set(ext, PROG_ID)?;
+        }
+        unsafe { SHChangeNotify(SHCNE_ASSOCCHANGED, 0, std::ptr::null(), std::ptr::null()) };
+        Ok(())
+    }
+
+    /// The ProgID `.md` opens with: the user's choice in Explorer if they
+    /// made one, otherwise the class default.
+    pub fn default_handler() -> Option<String> {
+        let user_choice = "HKCU\\Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\FileExts\\.md\\UserChoice";
+        run("reg", &["query", user_choice, "/v", "ProgId"])
+            .ok()
+            .and_then(|out| reg_value(&out))
+            .or_else(|| run("reg", &["query", "HKCU\\Software\\Classes\\.md", "/ve"]).ok().and_then(|out| reg_value(&out)))
+    }
+
+    /// Whether the user has picked an app in Explorer, which overrides
+    /// what we write and can only be changed through Settings.
+    pub fn has_user_choice() -> bool {
+        let key = "HKCU\\Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\FileExts\\.md\\UserChoice";
+        run("reg", &["query", key]).is_ok()
+    }
+}
+
+/// Linux: the desktop entry the bundle installs, `<productName>.desktop`.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn desktop_file(app: &tauri::AppHandle) -> String {
+    format!("{}.desktop", app.package_info().name)
+}
+
+/// Whether `name` is installed in one of the XDG `applications` folders.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn desktop_file_installed(name: &str) -> bool {
+    let home = std::env::var_os("XDG_DATA_HOME")
+        .filter(|d| !d.is_empty())
+        .map(std::path::PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| std::path::PathBuf::from(h).join(".local/share")));
+    let system = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|d| !d.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    home.into_iter()
+        .chain(system.split(':').map(std::path::PathBuf::from))
+        .any(|dir| dir.join("applications").join(name).is_file())
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// Make UpDown the default app for `.md` and `.markdown` files for the
+/// current user, returning a message to show.
+#[tauri::command]
+pub fn set_as_default_markdown_handler(app: tauri::AppHandle) -> Result<String, String> {
+    #[cfg(target_os = "macos")]
+    {
+        launch_services::set_default(MARKDOWN_UTI, &app.config().identifier)?;
+        Ok(DONE.to_string())
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let exe = std::env::current_exe().map_err(|e| format!("Cannot locate UpDown's executable: {}", e))?;
+        registry::set_default(&exe.to_string_lossy())?;
+        if registry::has_user_choice() && !is_default_markdown_handler(app)? {
+            return Ok(
+                "UpDown is registered for Markdown files. Windows keeps your earlier choice until you pick \
+                 UpDown under Settings › Apps › Default apps."
+                    .to_string(),
+            );
+        }
+        Ok(DONE.to_string())
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let desktop = desktop_file(&app);
+        if !desktop_file_installed(&desktop) {
+            return Err(format!(
+                "Not supported for this install: there is no {} desktop entry to associate (install UpDown from \
+                 its .deb or .rpm package)",
+                desktop
+            ));
+        }
+        for mime in MIME_TYPES {
+            run("xdg-mime", &["default", &desktop, mime])?;
+        }
+        Ok(DONE.to_string())
+    }
+}
+
+/// Whether `.md` files currently open in UpDown.
+#[tauri::command]
+pub fn is_default_markdown_handler(app: tauri::AppHandle) -> Result<bool, String> {
+    #[cfg(target_os = "macos")]
+    let current = launch_services::default_handler(MARKDOWN_UTI);
+    #[cfg(target_os = "macos")]
+    let ours = app.config().identifier.clone();
+
+    #[cfg(target_os = "windows")]
+    let current = registry::default_handler();
+    #[cfg(target_os = "windows")]
+    let ours = {
+        let _ = &app;
+        PROG_ID.to_string()
+    };
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let current = run("xdg-mime", &["query", "default", MIME_TYPES[0]]).ok();
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let ours = desktop_file(&app);
+
+    Ok(current.is_some_and(|handler| is_ours(&handler, &ours)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reported_handlers_are_compared_loosely() {
+        assert!(is_ours("UpDown.desktop\n", "UpDown.desktop"));
+        assert!(is_ours("com.noam.UpDown", "com.noam.updown"));
+        assert!(!is_ours("typora.desktop", "UpDown.desktop"));
+
+        let query = "\r\nHKEY_CURRENT_USER\\Software\\Classes\\.md\r\n    (Default)    REG_SZ    UpDown.Markdown\r\n\r\n";
+        assert_eq!(reg_value(query).as_deref(), Some(PROG_ID));
+        assert_eq!(reg_value("ERROR: The system was unable to find the specified registry key"), None);
+    }
+}
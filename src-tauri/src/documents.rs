@@ -0,0 +1,250 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+
+use crate::lock::LockExt;
+
+/// Shown in the quit prompt for a dirty buffer that has never been saved.
+const UNTITLED: &str = "Untitled";
+
+/// How many closed files "Reopen Last Closed File" remembers.
+const MAX_CLOSED: usize = 20;
+
+/// The document shown in one window.
+#[derive(Debug, Clone, Default)]
+pub struct DocState {
+    pub path: Option<String>,
+    pub dirty: bool,
+}
+
+/// Per-window document state, keyed by window label.
+#[derive(Default)]
+pub struct Documents(pub Mutex<BTreeMap<String, DocState>>);
+
+/// The file each window has open read-only, keyed by window label; writes
+/// to it are refused while it stays open there.
+#[derive(Default)]
+pub struct ReadOnlyFiles(pub Mutex<HashMap<String, String>>);
+
+/// What the open `confirm-quit` prompt will close if the user goes ahead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CloseScope {
+    /// One window's close button.
+    Window(String),
+    /// Quitting the whole app.
+    App,
+}
+
+/// The close or quit waiting on `confirm_quit`, if any.
+#[derive(Default)]
+pub struct PendingClose(pub Mutex<Option<CloseScope>>);
+
+/// Recently closed files, most recent last.
+#[derive(Default)]
+pub struct ClosedFiles(pub Mutex<Vec<String>>);
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// The first window (by label) holding unsaved changes; it hosts the quit
+/// prompt so only one dialog appears.
+pub fn first_dirty_window(docs: &BTreeMap<String, DocState>) -> Option<String> {
+    docs.iter().find(|(_, d)| d.dirty).map(|(label, _)| label.clone())
+}
+
+/// Hold an app exit while documents are dirty and ask the user first.
+/// Returns whether the exit must be prevented.
+pub fn exit_requested(app: &tauri::AppHandle) -> bool {
+    let (dirty, host) = {
+        let state = app.state::<Documents>();
+        let docs = state.0.lock_or_recover();
+        (dirty_paths(&docs), first_dirty_window(&docs))
+    };
+    let Some(host) = host else { return false };
+    *app.state::<PendingClose>().0.lock_or_recover() = Some(CloseScope::App);
+    let _ = app.emit_to(host.as_str(), "confirm-quit", &dirty);
+    true
+}
+
+/// Hold a window's close button while its document is dirty. Returns
+/// whether the close must be prevented.
+pub fn close_requested(window: &tauri::Window) -> bool {
+    let app = window.app_handle();
+    let doc = app.state::<Documents>().0.lock_or_recover().get(window.label()).cloned();
+    let Some(doc) = doc.filter(|d| d.dirty) else { return false };
+    *app.state::<PendingClose>().0.lock_or_recover() = Some(CloseScope::Window(window.label().to_string()));
+    let name = doc.path.unwrap_or_else(|| UNTITLED.to_string());
+    let _ = window.emit_to(window.label(), "confirm-quit", vec![name]);
+    true
+}
+
+/// Paths of every dirty document, in window-label order and without
+/// duplicates (the same file may be open in two windows).
+pub fn dirty_paths(docs: &BTreeMap<String, DocState>) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for doc in docs.values().filter(|d| d.dirty) {
+        let name = doc.path.clone().unwrap_or_else(|| UNTITLED.to_string());
+        if !out.contains(&name) {
+            out.push(name);
+        }
+    }
+    out
+}
+
+/// Window `label` now shows `path`: its read-only flag goes unless it is
+/// still the same file.
+pub fn keep_read_only(flags: &mut HashMap<String, String>, label: &str, path: Option<&str>) {
+    if flags.get(label).map(String::as_str) != path {
+        flags.remove(label);
+    }
+}
+
+/// Record how window `label` is about to show `file`, replacing whatever
+/// it had open before.
+pub fn note_opening(app: &tauri::AppHandle, label: &str, file: &crate::files::OpenFilePayload) {
+    let state = app.state::<ReadOnlyFiles>();
+    let mut flags = state.0.lock_or_recover();
+    if file.read_only {
+        flags.insert(label.to_string(), file.path.clone());
+    } else {
+        flags.remove(label);
+    }
+}
+
+/// Whether some window has `path` open read-only.
+pub fn is_read_only(app: &tauri::AppHandle, path: &str) -> bool {
+    app.state::<ReadOnlyFiles>().0.lock_or_recover().values().any(|p| p == path)
+}
+
+/// Lift the read-only flag on `path` in every window, once the user has
+/// chosen to overwrite it anyway.
+pub fn clear_read_only(app: &tauri::AppHandle, path: &str) {
+    app.state::<ReadOnlyFiles>().0.lock_or_recover().retain(|_, p| p != path);
+}
+
+/// Put `path` on top of the closed stack, moving it up if already there.
+pub fn push_closed(stack: &mut Vec<String>, path: String) {
+    stack.retain(|p| p != &path);
+    stack.push(path);
+    if stack.len() > MAX_CLOSED {
+        stack.remove(0);
+    }
+}
+
+/// Record a closed file and enable the reopen item.
+pub fn note_closed_path(app: &tauri::AppHandle, path: String) {
+    if path.trim().is_empty() {
+        return;
+    }
+    push_closed(&mut app.state::<ClosedFiles>().0.lock_or_recover(), path);
+    crate::menu::set_items_enabled(app, &[crate::menu::REOPEN_CLOSED_ID], true);
+}
+
+/// Pop the most recently closed file and open it in the main window. The
+/// reopened path leaves the stack, so reopening never feeds back into it.
+pub fn reopen_last_closed(app: &tauri::AppHandle) {
+    let (path, remaining) = {
+        let state = app.state::<ClosedFiles>();
+        let mut stack = state.0.lock_or_recover();
+        (stack.pop(), !stack.is_empty())
+    };
+    crate::menu::set_items_enabled(app, &[crate::menu::REOPEN_CLOSED_ID], remaining);
+    if let Some(path) = path {
+        crate::open_file_in_running_app(app, &path, true);
+    }
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// Called by the frontend whenever a window's document gains or loses
+/// unsaved changes.
+#[tauri::command]
+pub fn set_dirty(window: tauri::Window, path: Option<String>, dirty: bool) {
+    let app = window.app_handle();
+    let state = app.state::<Documents>();
+    keep_read_only(&mut app.state::<ReadOnlyFiles>().0.lock_or_recover(), window.label(), path.as_deref());
+    let mut docs = state.0.lock_or_recover();
+    docs.insert(window.label().to_string(), DocState { path, dirty });
+}
+
+/// Called by the frontend when a window stops showing `path`, i.e. it
+/// switched to another file or started a new one.
+#[tauri::command]
+pub fn note_closed(app: tauri::AppHandle, path: String) {
+    note_closed_path(&app, path);
+}
+
+/// Paths of all documents with unsaved changes, across every window.
+#[tauri::command]
+pub fn dirty_documents(state: tauri::State<Documents>) -> Vec<String> {
+    dirty_paths(&state.0.lock_or_recover())
+}
+
+/// The user's answer to a `confirm-quit` prompt. With `proceed`, the
+/// pending close or quit goes ahead, discarding whatever is still unsaved;
+/// otherwise it is dropped.
+#[tauri::command]
+pub fn confirm_quit(app: tauri::AppHandle, proceed: bool) {
+    let Some(scope) = app.state::<PendingClose>().0.lock_or_recover().take() else { return };
+    if !proceed {
+        return;
+    }
+    let state = app.state::<Documents>();
+    match scope {
+        CloseScope::Window(label) => {
+            state.0.lock_or_recover().remove(&label);
+            if let Some(window) = app.get_webview_window(&label) {
+                let _ = window.destroy();
+            }
+        }
+        CloseScope::App => {
+            state.0.lock_or_recover().values_mut().for_each(|d| d.dirty = false);
+            app.exit(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(path: Option<&str>, dirty: bool) -> DocState {
+        DocState { path: path.map(String::from), dirty }
+    }
+
+    #[test]
+    fn aggregates_dirty_documents_across_windows() {
+        let mut docs = BTreeMap::new();
+        docs.insert("main".to_string(), doc(Some("/notes/a.md"), true));
+        docs.insert("doc-1".to_string(), doc(Some("/notes/b.md"), false));
+        docs.insert("doc-2".to_string(), doc(None, true));
+        docs.insert("doc-3".to_string(), doc(Some("/notes/a.md"), true));
+
+        assert_eq!(dirty_paths(&docs), vec!["Untitled", "/notes/a.md"]);
+        assert_eq!(first_dirty_window(&docs).as_deref(), Some("doc-2"));
+    }
+
+    #[test]
+    fn read_only_flag_lasts_until_the_window_shows_another_file() {
+        let mut flags = HashMap::from([("main".to_string(), "/notes/a.md".to_string())]);
+        keep_read_only(&mut flags, "main", Some("/notes/a.md"));
+        keep_read_only(&mut flags, "doc-1", None);
+        assert_eq!(flags.get("main").map(String::as_str), Some("/notes/a.md"));
+        keep_read_only(&mut flags, "main", Some("/notes/b.md"));
+        assert!(flags.is_empty());
+    }
+
+    #[test]
+    fn closed_stack_moves_repeats_to_the_top_and_is_bounded() {
+        let mut stack = Vec::new();
+        push_closed(&mut stack, "/a.md".into());
+        push_closed(&mut stack, "/b.md".into());
+        push_closed(&mut stack, "/a.md".into());
+        assert_eq!(stack, vec!["/b.md", "/a.md"]);
+
+        for i in 0..MAX_CLOSED {
+            push_closed(&mut stack, format!("/{i}.md"));
+        }
+        assert_eq!(stack.len(), MAX_CLOSED);
+        assert_eq!(stack.first().map(String::as_str), Some("/0.md"));
+    }
+}
@@ -0,0 +1,100 @@
+use serde::Serialize;
+
+/// Windows-1252 characters for bytes 0x80–0x9F; `None` marks the five bytes
+/// the code page leaves undefined.
+const CP1252_HIGH: [Option<char>; 32] = [
+    Some('€'), None, Some('‚'), Some('ƒ'), Some('„'), Some('…'), Some('†'), Some('‡'),
+    Some('ˆ'), Some('‰'), Some('Š'), Some('‹'), Some('Œ'), None, Some('Ž'), None,
+    None, Some('‘'), Some('’'), Some('“'), Some('”'), Some('•'), Some('–'), Some('—'),
+    Some('˜'), Some('™'), Some('š'), Some('›'), Some('œ'), None, Some('ž'), Some('Ÿ'),
+];
+
+/// A file's text and the encoding it was decoded from (WHATWG label).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DecodedText {
+    pub content: String,
+    pub encoding: &'static str,
+}
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+fn decode_utf16(bytes: &[u8], little_endian: bool) -> String {
+    let units = bytes.chunks_exact(2).map(|pair| {
+        let pair = [pair[0], pair[1]];
+        if little_endian { u16::from_le_bytes(pair) } else { u16::from_be_bytes(pair) }
+    });
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+/// Decode Windows-1252, or `None` if a byte is undefined in it.
+fn decode_cp1252(bytes: &[u8]) -> Option<String> {
+    bytes
+        .iter()
+        .map(|&b| match b {
+            0x80..=0x9F => CP1252_HIGH[(b - 0x80) as usize],
+            _ => Some(b as char),
+        })
+        .collect()
+}
+
+/// BOM-less UTF-16 shows up as a NUL in every other byte of ASCII text.
+fn guess_utf16(bytes: &[u8]) -> Option<bool> {
+    let sample = &bytes[..bytes.len().min(512) & !1];
+    if sample.len() < 4 {
+        return None;
+    }
+    let zeros = |offset: usize| sample.iter().skip(offset).step_by(2).filter(|&&b| b == 0).count();
+    let half = sample.len() / 2;
+    if zeros(1) * 10 >= half * 9 && zeros(0) == 0 {
+        Some(true)
+    } else if zeros(0) * 10 >= half * 9 && zeros(1) == 0 {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Decode file bytes: a BOM wins, then BOM-less UTF-16, then strict UTF-8,
+/// then Windows-1252. Anything else is decoded as lossy UTF-8.
+pub fn decode(bytes: &[u8]) -> DecodedText {
+    let (content, encoding) = if let Some(rest) = bytes.strip_prefix(b"\xEF\xBB\xBF") {
+        (String::from_utf8_lossy(rest).into_owned(), "UTF-8")
+    } else if let Some(rest) = bytes.strip_prefix(b"\xFF\xFE") {
+        (decode_utf16(rest, true), "UTF-16LE")
+    } else if let Some(rest) = bytes.strip_prefix(b"\xFE\xFF") {
+        (decode_utf16(rest, false), "UTF-16BE")
+    } else if let Some(le) = guess_utf16(bytes) {
+        (decode_utf16(bytes, le), if le { "UTF-16LE" } else { "UTF-16BE" })
+    } else if let Ok(text) = std::str::from_utf8(bytes) {
+        (text.to_string(), "UTF-8")
+    } else if let Some(text) = decode_cp1252(bytes) {
+        (text, "windows-1252")
+    } else {
+        (String::from_utf8_lossy(bytes).into_owned(), "UTF-8 (lossy)")
+    };
+    DecodedText { content, encoding }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_boms_utf8_and_windows_1252() {
+        assert_eq!(decode("# Café".as_bytes()), DecodedText { content: "# Café".into(), encoding: "UTF-8" });
+        assert_eq!(decode(b"\xEF\xBB\xBF# Hi").content, "# Hi");
+
+        let utf16le: Vec<u8> = b"\xFF\xFE".iter().copied().chain("# Hé".encode_utf16().flat_map(u16::to_le_bytes)).collect();
+        assert_eq!(decode(&utf16le), DecodedText { content: "# Hé".into(), encoding: "UTF-16LE" });
+        let bare_be: Vec<u8> = "# Notes".encode_utf16().flat_map(u16::to_be_bytes).collect();
+        assert_eq!(decode(&bare_be), DecodedText { content: "# Notes".into(), encoding: "UTF-16BE" });
+
+        assert_eq!(
+            decode(b"\x93Caf\xE9\x94 \x80"),
+            DecodedText { content: "“Café” €".into(), encoding: "windows-1252" }
+        );
+        assert_eq!(decode(b"bad \x81 byte").encoding, "UTF-8 (lossy)");
+    }
+}
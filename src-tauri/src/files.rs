@@ -0,0 +1,548 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+
+use crate::encoding::{self, DecodedText};
+use crate::lock::LockExt;
+use crate::settings;
+
+/// Files above this size (in bytes) are not loaded straight into the editor.
+pub const DEFAULT_LARGE_FILE_THRESHOLD: u64 = 5 * 1024 * 1024;
+
+/// Files above `threshold * STREAM_FACTOR` skip the prompt and go straight to
+/// a read-only preview.
+const STREAM_FACTOR: u64 = 10;
+
+/// Bytes returned by `peek_file` when the caller doesn't ask for a size.
+const DEFAULT_PEEK_BYTES: usize = 64 * 1024;
+
+/// How the frontend should open a file, decided by its size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum OpenAction {
+    /// Load normally into the editor.
+    Normal,
+    /// Big enough that the user should confirm a full load.
+    Prompt,
+    /// Too big to edit; show a `peek_file` preview read-only.
+    ReadOnly,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenPlan {
+    pub action: OpenAction,
+    pub size_bytes: u64,
+    pub threshold_bytes: u64,
+}
+
+/// Result of `read_file`: the decoded text, plus whether saving it back
+/// can work and the file's stamp as read, for auto-save to compare against.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReadFile {
+    #[serde(flatten)]
+    pub text: DecodedText,
+    pub writable: bool,
+    pub disk: crate::watch::Snapshot,
+}
+
+/// Payload of the `open-file` event.
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenFilePayload {
+    pub path: String,
+    pub read_only: bool,
+}
+
+/// Size, times and permissions of a file, for the status bar.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileMeta {
+    pub size_bytes: u64,
+    /// UTC epoch seconds; `None` where the platform doesn't record it.
+    pub modified_unix: Option<i64>,
+    pub created_unix: Option<i64>,
+    pub readonly: bool,
+}
+
+/// Payload of the `file-chunk` event sent by `read_file_chunked`.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileChunk {
+    pub id: String,
+    pub seq: u64,
+    pub data: String,
+    pub eof: bool,
+}
+
+/// Ids of chunked reads still running; `cancel_read` removes one to stop it.
+#[derive(Default)]
+pub struct ChunkedReads(pub Mutex<HashSet<String>>);
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// A user-facing message for a failed save of `path`.
+pub fn describe_write_error(path: &str, e: &std::io::Error) -> String {
+    match e.kind() {
+        std::io::ErrorKind::PermissionDenied => format!("Permission denied writing {}", path),
+        std::io::ErrorKind::StorageFull => format!("Not enough disk space to save {}", path),
+        std::io::ErrorKind::ReadOnlyFilesystem => format!("{} is on a read-only volume", path),
+        _ => format!("Failed to write {}: {}", path, e),
+    }
+}
+
+/// Write `contents` to `<path>.tmp` and rename it over `path`, so a crash
+/// mid-write leaves the previous file intact.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = std::path::PathBuf::from(tmp);
+    // The rename would replace a read-only file, so refuse it up front and
+    // carry the existing permissions over to the replacement.
+    let existing = std::fs::metadata(path).ok().map(|m| m.permissions());
+    if existing.as_ref().is_some_and(|p| p.readonly()) {
+        return Err(std::io::ErrorKind::PermissionDenied.into());
+    }
+    let written = std::fs::write(&tmp, contents).and_then(|_| match existing {
+        Some(perms) => std::fs::set_permissions(&tmp, perms),
+        None => Ok(()),
+    });
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e);
+    }
+    std::fs::rename(&tmp, path)
+}
+
+/// False when the file is missing, its permissions forbid writing, it is
+/// locked (macOS `uchg`/`schg`), or its folder is read-only so a save
+/// couldn't create the temporary file beside it.
+pub fn is_writable(path: &str) -> bool {
+    let Ok(meta) = std::fs::metadata(path) else { return false };
+    if meta.permissions().readonly() || is_locked(&meta) {
+        return false;
+    }
+    Path::new(path)
+        .parent()
+        .and_then(|dir| std::fs::metadata(if dir.as_os_str().is_empty() { Path::new(".") } else { dir }).ok())
+        .is_some_and(|dir| !dir.permissions().readonly())
+}
+
+/// The Finder "Locked" checkbox or the system immutable flag.
+#[cfg(target_os = "macos")]
+fn is_locked(meta: &std::fs::Metadata) -> bool {
+    use std::os::macos::fs::MetadataExt;
+    const UF_IMMUTABLE: u32 = 0x0000_0002;
+    const SF_IMMUTABLE: u32 = 0x0002_0000;
+    meta.st_flags() & (UF_IMMUTABLE | SF_IMMUTABLE) != 0
+}
+
+#[cfg(not(target_os = "macos"))]
+fn is_locked(_meta: &std::fs::Metadata) -> bool {
+    false
+}
+
+/// `foo copy.md`, then `foo copy 2.md`, … for copy number `n` of `path`,
+/// keeping only the last extension as the extension (`notes.draft.md`
+/// becomes `notes.draft copy.md`).
+pub fn copy_name(path: &Path, n: usize) -> Option<std::path::PathBuf> {
+    let stem = path.file_stem()?.to_string_lossy();
+    let suffix = if n == 1 { " copy".to_string() } else { format!(" copy {}", n) };
+    let name = match path.extension() {
+        Some(ext) => format!("{}{}.{}", stem, suffix, ext.to_string_lossy()),
+        None => format!("{}{}", stem, suffix),
+    };
+    Some(path.with_file_name(name))
+}
+
+/// The first of `candidate(1)`, `candidate(2)`, … that isn't taken;
+/// `candidate` returns `None` for a taken name.
+pub fn first_free_name<T>(candidate: impl FnMut(usize) -> Option<T>) -> T {
+    (1..).find_map(candidate).expect("unbounded range always yields a free name")
+}
+
+/// Create `dest` exclusively and fill it with `write`, for use with
+/// `first_free_name`: `None` if something is already there. A partly
+/// written file is removed again.
+pub fn create_new(dest: &Path, write: impl FnOnce(&mut std::fs::File) -> std::io::Result<()>) -> Option<Result<(), String>> {
+    let mut file = match std::fs::OpenOptions::new().write(true).create_new(true).open(dest) {
+        Ok(f) => f,
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => return None,
+        Err(e) => return Some(Err(describe_write_error(&dest.to_string_lossy(), &e))),
+    };
+    if let Err(e) = write(&mut file) {
+        drop(file);
+        let _ = std::fs::remove_file(dest);
+        return Some(Err(describe_write_error(&dest.to_string_lossy(), &e)));
+    }
+    Some(Ok(()))
+}
+
+fn unix_seconds(time: std::io::Result<std::time::SystemTime>) -> Option<i64> {
+    let secs = time.ok()?.duration_since(std::time::UNIX_EPOCH).ok()?.as_secs();
+    i64::try_from(secs).ok()
+}
+
+fn file_metadata_for_open(path: &str) -> Result<std::fs::Metadata, String> {
+    let meta = std::fs::metadata(path).map_err(|e| format!("Cannot open {}: {}", path, e))?;
+    if meta.is_dir() {
+        return Err(format!("{} is a directory", path));
+    }
+    Ok(meta)
+}
+
+/// Refuse to open missing files, directories and files without an
+/// openable extension.
+pub fn check_openable(path: &str) -> Result<(), String> {
+    file_metadata_for_open(path)?;
+    validate_markdown_path(path)
+}
+
+/// Build the `open-file` payload. Files we can't write are always opened
+/// read-only, whatever the caller asked for.
+pub fn open_payload(path: String, read_only: bool) -> OpenFilePayload {
+    let read_only = read_only || !is_writable(&path);
+    OpenFilePayload { path, read_only }
+}
+
+/// Refuse paths whose extension isn't in `extensions`.
+pub fn validate_markdown_path_in(path: &Path, extensions: &[String]) -> Result<(), String> {
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    if settings::extension_allowed(extensions, ext) {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} is not a markdown file (openable extensions: {})",
+            path.display(),
+            extensions.join(", ")
+        ))
+    }
+}
+
+/// `validate_markdown_path_in` against the user's openable extensions.
+pub fn validate_markdown_path(path: &str) -> Result<(), String> {
+    validate_markdown_path_in(Path::new(path), &settings::openable_extensions())
+}
+
+pub fn plan_for_size(size_bytes: u64, threshold_bytes: u64) -> OpenPlan {
+    let action = if size_bytes <= threshold_bytes {
+        OpenAction::Normal
+    } else if size_bytes <= threshold_bytes.saturating_mul(STREAM_FACTOR) {
+        OpenAction::Prompt
+    } else {
+        OpenAction::ReadOnly
+    };
+    OpenPlan { action, size_bytes, threshold_bytes }
+}
+
+/// Length of the longest prefix of `bytes` (at most `max` long) that does not
+/// end in the middle of a UTF-8 sequence.
+pub fn utf8_boundary(bytes: &[u8], max: usize) -> usize {
+    if bytes.len() <= max {
+        return bytes.len();
+    }
+    let mut end = max;
+    // Continuation bytes look like 0b10xxxxxx; back up to the start of the char.
+    while end > 0 && (bytes[end] & 0b1100_0000) == 0b1000_0000 {
+        end -= 1;
+    }
+    end
+}
+
+/// Length of `bytes` without a trailing incomplete UTF-8 sequence.
+fn complete_utf8_len(bytes: &[u8]) -> usize {
+    let len = bytes.len();
+    let lead = (len.saturating_sub(4)..len).rev().find(|&i| (bytes[i] & 0b1100_0000) != 0b1000_0000);
+    let Some(start) = lead else { return len };
+    let width = match bytes[start] {
+        b if b >= 0xF0 => 4,
+        b if b >= 0xE0 => 3,
+        b if b >= 0xC0 => 2,
+        _ => 1,
+    };
+    if start + width > len {
+        start
+    } else {
+        len
+    }
+}
+
+/// Read `reader` in pieces of about `chunk_bytes`, never splitting a UTF-8
+/// character, passing each piece and an end-of-file flag to `emit`. Stops
+/// early when `emit` returns false.
+pub fn read_chunks<R: Read>(
+    mut reader: R,
+    chunk_bytes: usize,
+    mut emit: impl FnMut(String, bool) -> bool,
+) -> std::io::Result<()> {
+    let chunk_bytes = chunk_bytes.max(4);
+    let mut buf = vec![0; chunk_bytes];
+    let mut pending: Vec<u8> = Vec::with_capacity(chunk_bytes + 4);
+    loop {
+        let mut filled = 0;
+        while filled < chunk_bytes {
+            match reader.read(&mut buf[filled..])? {
+                0 => break,
+                n => filled += n,
+            }
+        }
+        let eof = filled < chunk_bytes;
+        pending.extend_from_slice(&buf[..filled]);
+        // Carry a split character over to the next chunk.
+        let end = if eof { pending.len() } else { complete_utf8_len(&pending) };
+        let data = String::from_utf8_lossy(&pending[..end]).into_owned();
+        pending.drain(..end);
+        if !emit(data, eof) || eof {
+            return Ok(());
+        }
+    }
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+fn plan_open(path: &str, threshold_bytes: u64) -> Result<OpenPlan, String> {
+    Ok(plan_for_size(file_metadata_for_open(path)?.len(), threshold_bytes))
+}
+
+/// Decide how to open `path` before reading it, so a huge log doesn't hang
+/// the UI. The limit is the `large_file_threshold` setting.
+#[tauri::command]
+pub fn precheck_open(app: tauri::AppHandle, path: String) -> Result<OpenPlan, String> {
+    let threshold = settings::settings_path(&app)
+        .map(|p| settings::load_settings(&p).large_file_threshold)
+        .unwrap_or(DEFAULT_LARGE_FILE_THRESHOLD);
+    plan_open(&path, threshold)
+}
+
+#[tauri::command]
+pub fn file_metadata(path: String) -> Result<FileMeta, String> {
+    let meta = std::fs::metadata(&path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => format!("{} does not exist", path),
+        _ => format!("Failed to read metadata of {}: {}", path, e),
+    })?;
+    Ok(FileMeta {
+        size_bytes: meta.len(),
+        modified_unix: unix_seconds(meta.modified()),
+        created_unix: unix_seconds(meta.created()),
+        readonly: meta.permissions().readonly(),
+    })
+}
+
+/// Show `path` in Finder / Explorer with the file selected. Linux file
+/// managers have no common "select" flag, so the parent folder is opened.
+#[tauri::command]
+pub fn reveal_in_file_manager(path: String) -> Result<(), String> {
+    if path.is_empty() {
+        return Err("No file is open".to_string());
+    }
+    let target = Path::new(&path);
+    if !target.exists() {
+        return Err(format!("{} no longer exists", path));
+    }
+    #[cfg(target_os = "macos")]
+    let child = std::process::Command::new("open").arg("-R").arg(target).spawn();
+    #[cfg(target_os = "windows")]
+    let child = std::process::Command::new("explorer").arg(format!("/select,{}", path)).spawn();
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let child = std::process::Command::new("xdg-open")
+        .arg(target.parent().unwrap_or(Path::new("/")))
+        .spawn();
+    child
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open the file manager: {}", e))
+}
+
+/// Copy `path` to the first free `… copy N` name beside it and return the
+/// new path. The copy is created exclusively, so nothing is overwritten.
+#[tauri::command]
+pub fn duplicate_file(path: String) -> Result<String, String> {
+    let source = Path::new(&path);
+    let mut input = std::fs::File::open(source).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    let dest = first_free_name(|n| match copy_name(source, n) {
+        Some(dest) => create_new(&dest, |f| std::io::copy(&mut input, f).map(drop)).map(|r| r.map(|()| dest)),
+        None => Some(Err(format!("{} has no file name", path))),
+    })?;
+    Ok(dest.to_string_lossy().to_string())
+}
+
+/// Open `path` in the application the OS associates with its type.
+#[tauri::command]
+pub fn open_in_default_app(path: String) -> Result<(), String> {
+    if path.is_empty() {
+        return Err("No file is open".to_string());
+    }
+    if !Path::new(&path).exists() {
+        return Err(format!("{} no longer exists", path));
+    }
+    tauri_plugin_opener::open_path(&path, None::<&str>)
+        .map_err(|e| format!("Failed to open {} in the default app: {}", path, e))
+}
+
+/// Read a whole file, detecting its encoding (see `encoding::decode`), and
+/// report whether it can be saved in place.
+#[tauri::command]
+pub fn read_file(path: String) -> Result<ReadFile, String> {
+    // Stamped before reading: a write in between then looks like a change.
+    let disk = crate::watch::snapshot(Path::new(&path));
+    let bytes = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    Ok(ReadFile { text: encoding::decode(&bytes), writable: is_writable(&path), disk })
+}
+
+/// Return the first `max_bytes` of a file (cut at a character boundary) as a
+/// cheap preview for files too large to load.
+#[tauri::command]
+pub fn peek_file(path: String, max_bytes: Option<usize>) -> Result<String, String> {
+    let max = max_bytes.unwrap_or(DEFAULT_PEEK_BYTES);
+    let file = std::fs::File::open(&path).map_err(|e| format!("Cannot open {}: {}", path, e))?;
+    // Read a few extra bytes so a multibyte char straddling `max` can be dropped cleanly.
+    let mut buf = Vec::with_capacity(max + 4);
+    file.take(max as u64 + 4)
+        .read_to_end(&mut buf)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let end = utf8_boundary(&buf, max);
+    Ok(String::from_utf8_lossy(&buf[..end]).into_owned())
+}
+
+/// Stream `path` to the frontend as `file-chunk` events tagged with `id`,
+/// so huge documents don't travel as one IPC payload. Returns immediately;
+/// a read error ends the stream with a `file-chunk-error` event.
+#[tauri::command]
+pub fn read_file_chunked(app: tauri::AppHandle, path: String, chunk_bytes: usize, id: String) -> Result<(), String> {
+    let file = std::fs::File::open(&path).map_err(|e| format!("Cannot open {}: {}", path, e))?;
+    app.state::<ChunkedReads>().0.lock_or_recover().insert(id.clone());
+    std::thread::spawn(move || {
+        let mut seq = 0;
+        let result = read_chunks(file, chunk_bytes, |data, eof| {
+            let active = app.state::<ChunkedReads>().0.lock_or_recover().contains(&id);
+            if active {
+                let _ = app.emit("file-chunk", FileChunk { id: id.clone(), seq, data, eof });
+                seq += 1;
+            }
+            active
+        });
+        if let Err(e) = result {
+            let error = format!("Failed to read {}: {}", path, e);
+            let _ = app.emit("file-chunk-error", serde_json::json!({ "id": id, "error": error }));
+        }
+        app.state::<ChunkedReads>().0.lock_or_recover().remove(&id);
+    });
+    Ok(())
+}
+
+/// Stop a `read_file_chunked` stream; no more chunks are sent for `id`.
+#[tauri::command]
+pub fn cancel_read(state: tauri::State<ChunkedReads>, id: String) {
+    state.0.lock_or_recover().remove(&id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn under_threshold_opens_normally() {
+        assert_eq!(plan_for_size(1_000, 5_000).action, OpenAction::Normal);
+        assert_eq!(plan_for_size(5_000, 5_000).action, OpenAction::Normal);
+    }
+
+    #[test]
+    fn over_threshold_prompts_then_goes_read_only() {
+        assert_eq!(plan_for_size(5_001, 5_000).action, OpenAction::Prompt);
+        assert_eq!(plan_for_size(50_001, 5_000).action, OpenAction::ReadOnly);
+    }
+
+    #[test]
+    fn precheck_and_peek_a_large_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("big.md");
+        std::fs::write(&path, "é".repeat(1_000)).unwrap();
+        let path = path.to_string_lossy().to_string();
+
+        let plan = plan_open(&path, 100).unwrap();
+        assert_eq!(plan.action, OpenAction::ReadOnly);
+        assert_eq!(plan.size_bytes, 2_000);
+
+        // 11 bytes would split the sixth "é"; the peek stops before it.
+        assert_eq!(peek_file(path, Some(11)).unwrap(), "é".repeat(5));
+    }
+
+    #[test]
+    fn non_writable_file_opens_read_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("locked.md");
+        std::fs::write(&path, "# Locked").unwrap();
+        let path_str = path.to_string_lossy().to_string();
+        assert!(!open_payload(path_str.clone(), false).read_only);
+
+        let mut perms = std::fs::metadata(&path).unwrap().permissions();
+        perms.set_readonly(true);
+        std::fs::set_permissions(&path, perms).unwrap();
+        assert!(open_payload(path_str.clone(), false).read_only);
+        assert!(!read_file(path_str).unwrap().writable);
+    }
+
+    #[test]
+    fn metadata_reports_size_and_times() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("note.md");
+        std::fs::write(&path, "12345").unwrap();
+        let meta = file_metadata(path.to_string_lossy().to_string()).unwrap();
+        assert_eq!(meta.size_bytes, 5);
+        assert!(!meta.readonly);
+        assert!(meta.modified_unix.is_some_and(|t| t > 1_600_000_000));
+
+        let missing = dir.path().join("gone.md").to_string_lossy().to_string();
+        assert_eq!(file_metadata(missing.clone()), Err(format!("{} does not exist", missing)));
+    }
+
+    #[test]
+    fn duplicates_get_free_copy_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.draft.md");
+        std::fs::write(&path, "# Draft").unwrap();
+        let original = path.to_string_lossy().to_string();
+
+        let first = duplicate_file(original.clone()).unwrap();
+        let second = duplicate_file(original).unwrap();
+        assert_eq!(Path::new(&first).file_name().unwrap(), "notes.draft copy.md");
+        assert_eq!(Path::new(&second).file_name().unwrap(), "notes.draft copy 2.md");
+        assert_eq!(std::fs::read_to_string(&second).unwrap(), "# Draft");
+        assert_eq!(copy_name(Path::new("/n/README"), 3).unwrap(), Path::new("/n/README copy 3"));
+    }
+
+    #[test]
+    fn chunks_reassemble_without_splitting_characters() {
+        let text = "héllo wörld — ünïcödé 🎉 text ".repeat(20);
+        for size in [1, 5, 7, 64] {
+            let mut chunks = Vec::new();
+            read_chunks(std::io::Cursor::new(text.as_bytes()), size, |data, eof| {
+                chunks.push((data, eof));
+                true
+            })
+            .unwrap();
+            assert!(chunks.iter().all(|(c, _)| !c.contains('\u{FFFD}')));
+            assert_eq!(chunks.iter().filter(|(_, eof)| *eof).count(), 1);
+            assert!(chunks.last().unwrap().1);
+            assert_eq!(chunks.into_iter().map(|(c, _)| c).collect::<String>(), text);
+        }
+    }
+
+    #[test]
+    fn failed_atomic_write_keeps_the_old_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recent-files.json");
+        write_atomic(&path, br#"["/a.md"]"#).unwrap();
+        assert!(!dir.path().join("recent-files.json.tmp").exists());
+
+        // A directory squatting on the temp path makes the write fail part-way.
+        std::fs::create_dir(dir.path().join("recent-files.json.tmp")).unwrap();
+        assert!(write_atomic(&path, br#"["/b.md"]"#).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), r#"["/a.md"]"#);
+    }
+
+    #[test]
+    fn write_errors_name_the_cause() {
+        let denied = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+        assert_eq!(describe_write_error("/a.md", &denied), "Permission denied writing /a.md");
+        let full = std::io::Error::from(std::io::ErrorKind::StorageFull);
+        assert_eq!(describe_write_error("/a.md", &full), "Not enough disk space to save /a.md");
+    }
+}
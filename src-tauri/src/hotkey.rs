@@ -0,0 +1,201 @@
+use std::sync::Mutex;
+use tauri::Manager;
+use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
+
+use crate::lock::LockExt;
+use crate::settings;
+
+/// A key a global shortcut can end in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    /// `A`–`Z` or `0`–`9`, uppercase.
+    Char(char),
+    /// `F1`–`F12`.
+    F(u8),
+    Space,
+}
+
+/// A parsed accelerator such as `CmdOrCtrl+Shift+Space`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hotkey {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    /// Cmd on macOS, the Windows/Super key elsewhere.
+    pub meta: bool,
+    pub key: Key,
+}
+
+/// The registered quick-capture shortcut.
+#[derive(Default)]
+pub struct QuickCapture(pub Mutex<Option<Shortcut>>);
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// Parse an accelerator in the menu's syntax. At least one of Ctrl, Alt and
+/// Cmd/Super is required so the shortcut can't swallow ordinary typing.
+pub fn parse_accelerator(accelerator: &str) -> Result<Hotkey, String> {
+    let invalid = || format!("Invalid shortcut: {}", accelerator);
+    let parts: Vec<String> = accelerator.split('+').map(|p| p.trim().to_lowercase()).collect();
+    let (key, modifiers) = parts.split_last().ok_or_else(invalid)?;
+    let key = match key.as_str() {
+        "space" => Key::Space,
+        k if k.len() == 1 && k.chars().all(|c| c.is_ascii_alphanumeric()) => {
+            Key::Char(k.chars().next().unwrap_or_default().to_ascii_uppercase())
+        }
+        k => match k.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+            Some(n @ 1..=12) => Key::F(n),
+            _ => return Err(invalid()),
+        },
+    };
+    let mut hotkey = Hotkey { ctrl: false, alt: false, shift: false, meta: false, key };
+    for modifier in modifiers {
+        match modifier.as_str() {
+            "cmdorctrl" | "commandorcontrol" if cfg!(target_os = "macos") => hotkey.meta = true,
+            "cmdorctrl" | "commandorcontrol" | "ctrl" | "control" => hotkey.ctrl = true,
+            "cmd" | "command" | "super" | "meta" => hotkey.meta = true,
+            "alt" | "option" => hotkey.alt = true,
+            "shift" => hotkey.shift = true,
+            _ => return Err(invalid()),
+        }
+    }
+    if !(hotkey.ctrl || hotkey.alt || hotkey.meta) {
+        return Err(format!("{} needs Ctrl, Alt or Cmd as well", accelerator));
+    }
+    Ok(hotkey)
+}
+
+/// The global-shortcut plugin's form of `hotkey`.
+fn shortcut(hotkey: Hotkey) -> Result<Shortcut, String> {
+    let mut modifiers = Modifiers::empty();
+    modifiers.set(Modifiers::CONTROL, hotkey.ctrl);
+    modifiers.set(Modifiers::ALT, hotkey.alt);
+    modifiers.set(Modifiers::SHIFT, hotkey.shift);
+    modifiers.set(Modifiers::SUPER, hotkey.meta);
+    let code = match hotkey.key {
+        Key::Char(c) if c.is_ascii_digit() => format!("Digit{}", c),
+        Key::Char(c) => format!("Key{}", c),
+        Key::F(n) => format!("F{}", n),
+        Key::Space => "Space".to_string(),
+    };
+    let code: Code = code.parse().map_err(|_| format!("Unsupported shortcut key: {}", code))?;
+    Ok(Shortcut::new(Some(modifiers), code))
+}
+
+/// Hide the main window if it's in front, otherwise bring it forward.
+fn toggle_main_window(app: &tauri::AppHandle) {
+    let Some(window) = app.get_webview_window(crate::windows::MAIN_WINDOW) else { return };
+    if window.is_visible().unwrap_or(false) && window.is_focused().unwrap_or(false) {
+        let _ = window.hide();
+    } else {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// Bind `shortcut` to showing or hiding the main window.
+fn bind(app: &tauri::AppHandle, shortcut: Shortcut) -> Result<(), String> {
+    app.global_shortcut()
+        .on_shortcut(shortcut, |app, _, event| {
+            if event.state == ShortcutState::Pressed {
+                toggle_main_window(app);
+            }
+        })
+        .map_err(|e| format!("Failed to register the shortcut: {}", e))
+}
+
+/// Unbind the current quick-capture shortcut, if any.
+fn unregister(app: &tauri::AppHandle) {
+    if let Some(old) = app.state::<QuickCapture>().0.lock_or_recover().take() {
+        let _ = app.global_shortcut().unregister(old);
+    }
+}
+
+/// Register `accelerator` as the quick-capture shortcut, replacing any
+/// current one. If the new one can't be registered the old one is kept.
+fn register(app: &tauri::AppHandle, accelerator: &str) -> Result<(), String> {
+    let shortcut = shortcut(parse_accelerator(accelerator)?)?;
+    let state = app.state::<QuickCapture>();
+    let mut current = state.0.lock_or_recover();
+    if *current == Some(shortcut) {
+        return Ok(());
+    }
+    let previous = current.take();
+    if let Some(old) = previous {
+        let _ = app.global_shortcut().unregister(old);
+    }
+    match bind(app, shortcut) {
+        Ok(()) => {
+            *current = Some(shortcut);
+            Ok(())
+        }
+        Err(e) => {
+            *current = previous.filter(|&old| bind(app, old).is_ok());
+            Err(e)
+        }
+    }
+}
+
+/// Make `accelerator` the quick-capture shortcut, or remove the shortcut
+/// for `None` or a blank one. Errors (an invalid or taken combination)
+/// leave the previous shortcut in place.
+pub fn apply(app: &tauri::AppHandle, accelerator: Option<&str>) -> Result<(), String> {
+    match accelerator.map(str::trim).filter(|a| !a.is_empty()) {
+        Some(accelerator) => register(app, accelerator),
+        None => {
+            unregister(app);
+            Ok(())
+        }
+    }
+}
+
+/// Register the saved shortcut, if any. Called from `setup`.
+pub fn register_saved(app: &tauri::AppHandle) {
+    let saved = settings::settings_path(app).ok().and_then(|p| settings::load_settings(&p).quick_capture_shortcut);
+    if let Some(accelerator) = saved {
+        if let Err(e) = register(app, &accelerator) {
+            log::warn!("Failed to register the quick-capture shortcut: {}", e);
+        }
+    }
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// Set the global shortcut that shows or hides the main window from any
+/// app, and save it. An empty accelerator removes the shortcut. Errors
+/// (e.g. the combination is taken) leave the previous shortcut in place.
+#[tauri::command]
+pub fn set_quick_capture_shortcut(app: tauri::AppHandle, accelerator: String) -> Result<(), String> {
+    let accelerator = accelerator.trim().to_string();
+    apply(&app, Some(&accelerator))?;
+    let path = settings::settings_path(&app)?;
+    let mut saved = settings::load_settings(&path);
+    saved.quick_capture_shortcut = Some(accelerator).filter(|a| !a.is_empty());
+    settings::save_settings(&path, &saved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accelerators_parse_and_need_a_real_modifier() {
+        let hotkey = parse_accelerator("CmdOrCtrl+Shift+space").unwrap();
+        assert_eq!(hotkey.key, Key::Space);
+        assert!(hotkey.shift);
+        assert_eq!((hotkey.meta, hotkey.ctrl), (cfg!(target_os = "macos"), !cfg!(target_os = "macos")));
+
+        assert_eq!(parse_accelerator("Alt+n").unwrap().key, Key::Char('N'));
+        assert_eq!(parse_accelerator("Super+F12").unwrap().key, Key::F(12));
+        assert!(parse_accelerator("Shift+A").is_err());
+        assert!(parse_accelerator("Ctrl+F13").is_err());
+        assert!(parse_accelerator("Ctrl+Hyper+A").is_err());
+        assert!(parse_accelerator("").is_err());
+
+        let to_shortcut = |accelerator| shortcut(parse_accelerator(accelerator).unwrap()).unwrap();
+        assert_eq!(to_shortcut("Ctrl+Shift+7"), Shortcut::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::Digit7));
+        assert_eq!(to_shortcut("Super+Alt+q"), Shortcut::new(Some(Modifiers::SUPER | Modifiers::ALT), Code::KeyQ));
+        assert_eq!(to_shortcut("Ctrl+F12"), Shortcut::new(Some(Modifiers::CONTROL), Code::F12));
+    }
+}
@@ -1,35 +1,140 @@
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Mutex;
-use tauri::menu::{MenuBuilder, MenuItem, MenuItemKind, PredefinedMenuItem, SubmenuBuilder};
-use tauri::Manager;
-
-const MAX_RECENT: usize = 10;
-
-/// Holds a file path queued by macOS "Open With" before the frontend was ready.
-struct PendingFile(Mutex<Option<String>>);
+use tauri::menu::{MenuItem, MenuItemKind, PredefinedMenuItem, Submenu};
+use tauri::{Emitter, Manager};
+
+use lock::LockExt;
+
+mod activity;
+mod analysis;
+mod assets;
+mod autosave;
+mod backups;
+mod clipboard;
+mod default_handler;
+mod documents;
+mod encoding;
+mod files;
+mod hotkey;
+mod lock;
+mod menu;
+mod pdf;
+mod preview;
+mod quicklook;
+mod recent;
+mod render;
+mod search;
+mod settings;
+mod single_instance;
+mod snippets;
+mod spellcheck;
+mod system_recents;
+mod templates;
+mod theme;
+mod transforms;
+mod trash;
+mod tray;
+mod tree;
+mod updates;
+mod vault;
+mod view_state;
+mod watch;
+mod window_state;
+mod windows;
+
+/// File paths queued by "Open With" (or the command line) before the main
+/// window's frontend was ready to take them, oldest first.
+struct PendingFiles(Mutex<Vec<String>>);
+
+/// Set once the main window's frontend has collected its launch files;
+/// later opens go straight to it rather than queueing.
+static MAIN_WINDOW_READY: AtomicBool = AtomicBool::new(false);
+
+/// The folder-panel folder saved last session, waiting for the main window
+/// to finish loading; see `settings::set_last_folder`.
+struct RestoreFolder(Mutex<Option<String>>);
+
+/// Recently opened files (most recent first), with open times and counts.
+struct RecentFiles(Mutex<Vec<recent::RecentEntry>>);
+
+/// How the "Open Recent" menu is ordered; see `set_recent_sort`.
+struct RecentSortMode(Mutex<recent::RecentSort>);
+
+/// Files pinned above the recent list in "Open Recent", in pin order.
+struct PinnedFiles(Mutex<Vec<String>>);
+
+/// Recently shown folder-panel folders (most recent first).
+struct RecentFolders(Mutex<Vec<String>>);
+
+/// How many entries `RecentFiles` keeps; see `set_max_recent`.
+struct MaxRecent(AtomicUsize);
+
+fn max_recent(app: &tauri::AppHandle) -> usize {
+    app.state::<MaxRecent>().0.load(Ordering::Relaxed)
+}
 
-/// Ordered list of recently opened file paths (most recent first).
-struct RecentFiles(Mutex<Vec<String>>);
+/// Recent file paths in the order the menu shows them.
+fn recent_paths(app: &tauri::AppHandle) -> Vec<String> {
+    let sort = *app.state::<RecentSortMode>().0.lock_or_recover();
+    recent::ordered_paths(&app.state::<RecentFiles>().0.lock_or_recover(), sort)
+}
 
 // ── Path helpers ──────────────────────────────────────────────────────────────
 
-fn path_basename(path: &str) -> &str {
-    path.rfind(|c| c == '/' || c == '\\')
-        .map(|i| &path[i + 1..])
-        .unwrap_or(path)
-}
+const RECENT_FILES_JSON: &str = "recent-files.json";
+const RECENT_FOLDERS_JSON: &str = "recent-folders.json";
+const PINNED_FILES_JSON: &str = "pinned-files.json";
 
-fn recent_storage_path(app: &tauri::AppHandle) -> Option<PathBuf> {
+fn recent_storage_path(app: &tauri::AppHandle, file_name: &str) -> Option<PathBuf> {
     app.path()
         .app_data_dir()
         .ok()
-        .map(|d| d.join("recent-files.json"))
+        .map(|d| d.join(file_name))
 }
 
 // ── Persistence ───────────────────────────────────────────────────────────────
 
-fn load_recent_from_disk(app: &tauri::AppHandle) -> Vec<String> {
-    let Some(path) = recent_storage_path(app) else {
+/// Load the recent list, upgrading the old plain-path format.
+fn load_recent_from_disk(app: &tauri::AppHandle) -> Vec<recent::RecentEntry> {
+    let Some(path) = recent_storage_path(app, RECENT_FILES_JSON) else {
+        return vec![];
+    };
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return vec![];
+    };
+    let recent: Vec<recent::RecentEntry> = recent::parse(&content)
+        .into_iter()
+        .filter(|e| std::path::Path::new(&e.path).exists())
+        .take(max_recent(app))
+        .collect();
+    let paths: Vec<String> = recent.iter().map(|e| e.path.clone()).collect();
+    view_state::prune(app, &paths);
+    recent
+}
+
+fn save_recent_to_disk(app: &tauri::AppHandle, files: &[recent::RecentEntry]) {
+    save_list_to_disk(app, RECENT_FILES_JSON, files);
+}
+
+fn load_recent_folders_from_disk(app: &tauri::AppHandle) -> Vec<String> {
+    let Some(path) = recent_storage_path(app, RECENT_FOLDERS_JSON) else {
+        return vec![];
+    };
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return vec![];
+    };
+    serde_json::from_str::<Vec<String>>(&content)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|p| std::path::Path::new(p).is_dir())
+        .take(max_recent(app))
+        .collect()
+}
+
+/// Pinned files that still exist. Unlike the recent list there is no cap.
+fn load_pinned_from_disk(app: &tauri::AppHandle) -> Vec<String> {
+    let Some(path) = recent_storage_path(app, PINNED_FILES_JSON) else {
         return vec![];
     };
     let Ok(content) = std::fs::read_to_string(&path) else {
@@ -39,31 +144,72 @@ fn load_recent_from_disk(app: &tauri::AppHandle) -> Vec<String> {
         .unwrap_or_default()
         .into_iter()
         .filter(|p| std::path::Path::new(p).exists())
-        .take(MAX_RECENT)
         .collect()
 }
 
-fn save_recent_to_disk(app: &tauri::AppHandle, files: &[String]) {
-    let Some(path) = recent_storage_path(app) else {
+fn save_list_to_disk<T: serde::Serialize>(app: &tauri::AppHandle, file_name: &str, paths: &[T]) {
+    let Some(path) = recent_storage_path(app, file_name) else {
         return;
     };
     if let Some(parent) = path.parent() {
         let _ = std::fs::create_dir_all(parent);
     }
-    if let Ok(json) = serde_json::to_string(files) {
-        let _ = std::fs::write(&path, json);
+    if let Ok(json) = serde_json::to_string(paths) {
+        let _ = files::write_atomic(&path, json.as_bytes());
     }
 }
 
-// ── Dynamic menu rebuild ──────────────────────────────────────────────────────
+/// Point recent and pinned entries at their new paths after files were
+/// renamed, keeping each entry's position.
+fn rename_in_recent(app: &tauri::AppHandle, renames: &[(String, String)]) {
+    let renamed = |path: &str| renames.iter().find(|(old, _)| old == path).map(|(_, new)| new.clone());
+    {
+        let state = app.state::<RecentFiles>();
+        let mut files = state.0.lock_or_recover();
+        for entry in files.iter_mut() {
+            if let Some(new) = renamed(&entry.path) {
+                entry.path = new;
+            }
+        }
+        save_recent_to_disk(app, &files);
+    }
+    {
+        let state = app.state::<PinnedFiles>();
+        let mut pinned = state.0.lock_or_recover();
+        for path in pinned.iter_mut() {
+            if let Some(new) = renamed(path) {
+                *path = new;
+            }
+        }
+        save_list_to_disk(app, PINNED_FILES_JSON, &pinned);
+    }
+    recent_files_changed(app);
+}
 
-/// Clear and repopulate the "Open Recent" submenu from the current RecentFiles state.
-fn rebuild_recent_menu(app: &tauri::AppHandle) {
-    let Some(menu) = app.menu() else { return };
-    let Some(item_kind) = menu.get("open_recent") else { return };
-    let MenuItemKind::Submenu(submenu) = item_kind else { return };
+/// Drop recent entries whose files no longer exist, e.g. deleted while the
+/// app was in the background.
+fn prune_missing_recent(app: &tauri::AppHandle) {
+    let pruned = {
+        let state = app.state::<RecentFiles>();
+        let mut files = state.0.lock_or_recover();
+        let before = files.len();
+        files.retain(|e| std::path::Path::new(&e.path).exists());
+        let pruned = files.len() != before;
+        if pruned {
+            save_recent_to_disk(app, &files);
+        }
+        pruned
+    };
+    if pruned {
+        recent_files_changed(app);
+    }
+}
 
-    // Remove every existing item from the submenu.
+// ── Dynamic menu rebuild ──────────────────────────────────────────────────────
+
+/// The submenu `id` with every item removed, ready to be repopulated.
+fn cleared_submenu(app: &tauri::AppHandle, id: &str) -> Option<Submenu<tauri::Wry>> {
+    let MenuItemKind::Submenu(submenu) = app.menu()?.get(id)? else { return None };
     if let Ok(items) = submenu.items() {
         for item in items {
             let _ = match item {
@@ -75,21 +221,46 @@ fn rebuild_recent_menu(app: &tauri::AppHandle) {
             };
         }
     }
+    Some(submenu)
+}
 
-    let files = app.state::<RecentFiles>().0.lock().unwrap().clone();
-
-    if files.is_empty() {
+/// Clear and repopulate the "Open Recent" submenu: pinned files, a
+/// separator, then the current RecentFiles state. The tray's copy follows.
+fn rebuild_recent_menu(app: &tauri::AppHandle) {
+    tray::rebuild_recent(app);
+    let entries = app.state::<RecentFiles>().0.lock_or_recover().clone();
+    system_recents::sync(app, recent::ordered_paths(&entries, recent::RecentSort::Recency));
+    let Some(submenu) = cleared_submenu(app, menu::OPEN_RECENT_ID) else { return };
+    let pinned = app.state::<PinnedFiles>().0.lock_or_recover().clone();
+    let files = recent_paths(app);
+
+    if pinned.is_empty() && files.is_empty() {
         if let Ok(item) = MenuItem::with_id(app, "no_recent", "No Recent Items", false, None::<&str>) {
             let _ = submenu.append(&item);
         }
-    } else {
-        for (i, path) in files.iter().enumerate() {
-            let label = path_basename(path);
-            let id = format!("recent_{i}");
-            if let Ok(item) = MenuItem::with_id(app, id, label, true, None::<&str>) {
-                let _ = submenu.append(&item);
-            }
+        return;
+    }
+    // Label both groups together so names are told apart across them.
+    let mut labels = menu::recent_labels(&[pinned.as_slice(), files.as_slice()].concat());
+    let recent_labels = labels.split_off(pinned.len());
+    for (i, label) in labels.into_iter().enumerate() {
+        if let Ok(item) = MenuItem::with_id(app, format!("pinned_{i}"), label, true, None::<&str>) {
+            let _ = submenu.append(&item);
         }
+    }
+    if !pinned.is_empty() && !files.is_empty() {
+        if let Ok(sep) = PredefinedMenuItem::separator(app) {
+            let _ = submenu.append(&sep);
+        }
+    }
+    for (i, label) in recent_labels.into_iter().enumerate() {
+        let id = format!("recent_{i}");
+        let accelerator = menu::recent_accelerator(i);
+        if let Ok(item) = MenuItem::with_id(app, id, label, true, accelerator.as_deref()) {
+            let _ = submenu.append(&item);
+        }
+    }
+    if !files.is_empty() {
         if let Ok(sep) = PredefinedMenuItem::separator(app) {
             let _ = submenu.append(&sep);
         }
@@ -99,120 +270,422 @@ fn rebuild_recent_menu(app: &tauri::AppHandle) {
     }
 }
 
+/// Clear and repopulate the "Open Recent Folder" submenu from RecentFolders.
+fn rebuild_recent_folders_menu(app: &tauri::AppHandle) {
+    let Some(submenu) = cleared_submenu(app, menu::OPEN_RECENT_FOLDERS_ID) else { return };
+    let folders = app.state::<RecentFolders>().0.lock_or_recover().clone();
+
+    if folders.is_empty() {
+        if let Ok(item) = MenuItem::with_id(app, "no_recent_folders", "No Recent Folders", false, None::<&str>) {
+            let _ = submenu.append(&item);
+        }
+    }
+    for (i, label) in menu::recent_labels(&folders).into_iter().enumerate() {
+        if let Ok(item) = MenuItem::with_id(app, format!("recent_folder_{i}"), label, true, None::<&str>) {
+            let _ = submenu.append(&item);
+        }
+    }
+}
+
+/// Clear and repopulate the "New from Template" submenu from the templates
+/// folder. Also run when a window gains focus, so edits to the folder show
+/// up; the submenu is left alone if the folder hasn't changed.
+fn rebuild_templates_menu(app: &tauri::AppHandle) {
+    let Ok(dir) = templates::templates_dir(app) else { return };
+    let Some(listing) = templates::changed_listing(&dir) else { return };
+    let Some(submenu) = cleared_submenu(app, menu::NEW_FROM_TEMPLATE_ID) else { return };
+    for template in listing {
+        let id = format!("template_{}", template.id);
+        if let Ok(item) = MenuItem::with_id(app, id, template.name, true, None::<&str>) {
+            let _ = submenu.append(&item);
+        }
+    }
+    if let Ok(sep) = PredefinedMenuItem::separator(app) {
+        let _ = submenu.append(&sep);
+    }
+    if let Ok(item) = MenuItem::with_id(app, "open_templates_folder", "Open Templates Folder", true, None::<&str>) {
+        let _ = submenu.append(&item);
+    }
+}
+
+/// Rebuild the menu and tell every window after the recent list mutated.
+fn recent_files_changed(app: &tauri::AppHandle) {
+    rebuild_recent_menu(app);
+    let _ = app.emit("recent-files-changed", recent_paths(app));
+}
+
 // ── Tauri commands ────────────────────────────────────────────────────────────
 
 /// Called by the frontend after opening a file; pushes it to the top of
-/// the recent list (deduplicated) and rebuilds the native menu.
+/// the recent list (deduplicated, counting the open) and rebuilds the
+/// native menu.
 #[tauri::command]
 fn add_recent_file(app: tauri::AppHandle, path: String) {
+    // Untitled buffers have no path until their first save; pinned files
+    // stay in their own section.
+    if path.trim().is_empty() || app.state::<PinnedFiles>().0.lock_or_recover().contains(&path) {
+        return;
+    }
     {
         let state = app.state::<RecentFiles>();
-        let mut files = state.0.lock().unwrap();
-        files.retain(|p| p != &path);   // remove existing occurrence
-        files.insert(0, path);           // push to front
-        files.truncate(MAX_RECENT);
+        let mut files = state.0.lock_or_recover();
+        recent::touch(&mut files, path, chrono::Utc::now().timestamp());
+        files.truncate(max_recent(&app));
         save_recent_to_disk(&app, &files);
     }
-    rebuild_recent_menu(&app);
+    recent_files_changed(&app);
 }
 
-/// Returns and clears the file path that was pending before the frontend loaded.
+/// Called by the folder panel when it shows a folder; pushes it to the top
+/// of the recent folders (deduplicated) and rebuilds the native menu.
 #[tauri::command]
-fn get_opened_file(state: tauri::State<PendingFile>) -> Option<String> {
-    state.0.lock().unwrap().take()
+fn add_recent_folder(app: tauri::AppHandle, path: String) {
+    if path.trim().is_empty() {
+        return;
+    }
+    {
+        let state = app.state::<RecentFolders>();
+        let mut folders = state.0.lock_or_recover();
+        folders.retain(|p| p != &path);
+        folders.insert(0, path);
+        folders.truncate(max_recent(&app));
+        save_list_to_disk(&app, RECENT_FOLDERS_JSON, &folders);
+    }
+    rebuild_recent_folders_menu(&app);
 }
 
-/// Install the Quick Look generator for markdown preview in Finder.
+/// Recent folders, most recent first, minus any that have disappeared.
 #[tauri::command]
-fn install_quicklook_plugin(app: tauri::AppHandle) -> Result<String, String> {
-    #[cfg(target_os = "macos")]
+fn get_recent_folders(state: tauri::State<RecentFolders>) -> Vec<String> {
+    state
+        .0
+        .lock_or_recover()
+        .iter()
+        .filter(|p| std::path::Path::new(p).is_dir())
+        .cloned()
+        .collect()
+}
+
+/// Pin `path` to the top of "Open Recent", outside the recent-list limit.
+#[tauri::command]
+fn pin_recent_file(app: tauri::AppHandle, path: String) {
+    if path.trim().is_empty() {
+        return;
+    }
     {
-        let resource_dir = app
-            .path()
-            .resource_dir()
-            .map_err(|e| format!("Failed to get resource dir: {}", e))?;
-
-        let src = resource_dir.join("resources").join("UpDownQuickLook.app");
-        if !src.exists() {
-            let alt_src = resource_dir.join("UpDownQuickLook.app");
-            if !alt_src.exists() {
-                return Err(format!(
-                    "Quick Look app not found in app bundle. Checked:\n  {}\n  {}",
-                    src.display(),
-                    alt_src.display()
-                ));
-            }
-            return install_ql_from(&alt_src);
-        }
-        return install_ql_from(&src);
+        let recent_state = app.state::<RecentFiles>();
+        let pinned_state = app.state::<PinnedFiles>();
+        let mut files = recent_state.0.lock_or_recover();
+        let mut pinned = pinned_state.0.lock_or_recover();
+        recent::pin(&mut files, &mut pinned, path);
+        save_recent_to_disk(&app, &files);
+        save_list_to_disk(&app, PINNED_FILES_JSON, &pinned);
     }
+    recent_files_changed(&app);
+}
 
-    #[cfg(not(target_os = "macos"))]
+/// Unpin `path`; it goes back to the top of the recent list.
+#[tauri::command]
+fn unpin_recent_file(app: tauri::AppHandle, path: String) {
     {
-        Err("Quick Look is only available on macOS".to_string())
+        let recent_state = app.state::<RecentFiles>();
+        let pinned_state = app.state::<PinnedFiles>();
+        let mut files = recent_state.0.lock_or_recover();
+        let mut pinned = pinned_state.0.lock_or_recover();
+        recent::unpin(&mut files, &mut pinned, path, chrono::Utc::now().timestamp());
+        files.truncate(max_recent(&app));
+        save_recent_to_disk(&app, &files);
+        save_list_to_disk(&app, PINNED_FILES_JSON, &pinned);
+    }
+    recent_files_changed(&app);
+}
+
+/// Move `path` to the OS trash and forget it in the recent and pinned lists.
+#[tauri::command]
+fn trash_file(app: tauri::AppHandle, path: String) -> Result<(), String> {
+    if path.is_empty() {
+        return Err("No file is open".to_string());
+    }
+    trash::move_to_trash(std::path::Path::new(&path))?;
+    {
+        let recent_state = app.state::<RecentFiles>();
+        let pinned_state = app.state::<PinnedFiles>();
+        let mut files = recent_state.0.lock_or_recover();
+        let mut pinned = pinned_state.0.lock_or_recover();
+        files.retain(|e| e.path != path);
+        pinned.retain(|p| *p != path);
+        save_recent_to_disk(&app, &files);
+        save_list_to_disk(&app, PINNED_FILES_JSON, &pinned);
+    }
+    recent_files_changed(&app);
+    Ok(())
+}
+
+/// Rename a file on disk and follow it in the recent and pinned lists.
+/// Refuses to replace an existing file (a case-only rename of the same file
+/// is allowed).
+#[tauri::command]
+fn rename_file(app: tauri::AppHandle, old_path: String, new_path: String) -> Result<(), String> {
+    let (old, new) = (std::path::Path::new(&old_path), std::path::Path::new(&new_path));
+    if new_path.trim().is_empty() {
+        return Err("The new name is empty".to_string());
+    }
+    let same_file = std::fs::canonicalize(old).ok().is_some_and(|o| std::fs::canonicalize(new).ok() == Some(o));
+    if new.exists() && !same_file {
+        return Err(format!("{} already exists", new_path));
     }
+    std::fs::rename(old, new).map_err(|e| format!("Failed to rename {} to {}: {}", old_path, new_path, e))?;
+    rename_in_recent(&app, &[(old_path, new_path)]);
+    Ok(())
 }
 
-#[cfg(target_os = "macos")]
-fn install_ql_from(src: &std::path::Path) -> Result<String, String> {
-    use std::fs;
-    use std::process::Command;
+/// Drop a single entry from the recent list; unknown paths are ignored.
+#[tauri::command]
+fn remove_recent_file(app: tauri::AppHandle, path: String) {
+    {
+        let state = app.state::<RecentFiles>();
+        let mut files = state.0.lock_or_recover();
+        files.retain(|e| e.path != path);
+        save_recent_to_disk(&app, &files);
+    }
+    recent_files_changed(&app);
+}
 
-    let home = std::env::var("HOME").map_err(|_| "Cannot determine HOME directory".to_string())?;
-    let apps_dir = PathBuf::from(&home).join("Applications");
+/// Change how many recent files are kept (clamped to 1..=100), trimming the
+/// current list if needed. Returns the limit applied.
+#[tauri::command]
+fn set_max_recent(app: tauri::AppHandle, limit: usize) -> Result<usize, String> {
+    let limit = settings::save_max_recent(&app, limit)?;
+    apply_max_recent(&app, limit);
+    Ok(limit)
+}
 
-    if !apps_dir.exists() {
-        fs::create_dir_all(&apps_dir)
-            .map_err(|e| format!("Failed to create {}: {}", apps_dir.display(), e))?;
+/// Use a saved recent-list length at once, trimming the list to fit.
+fn apply_max_recent(app: &tauri::AppHandle, limit: usize) {
+    app.state::<MaxRecent>().0.store(limit, Ordering::Relaxed);
+    {
+        let state = app.state::<RecentFiles>();
+        let mut files = state.0.lock_or_recover();
+        files.truncate(limit);
+        save_recent_to_disk(app, &files);
     }
+    recent_files_changed(app);
+}
 
-    let dest = apps_dir.join("UpDownQuickLook.app");
+/// Order the "Open Recent" menu by recency or by how often files are opened.
+#[tauri::command]
+fn set_recent_sort(app: tauri::AppHandle, mode: recent::RecentSort) -> Result<(), String> {
+    settings::save_recent_sort(&app, mode)?;
+    apply_recent_sort(&app, mode);
+    Ok(())
+}
+
+/// Use a saved "Open Recent" order at once.
+fn apply_recent_sort(app: &tauri::AppHandle, mode: recent::RecentSort) {
+    *app.state::<RecentSortMode>().0.lock_or_recover() = mode;
+    recent_files_changed(app);
+}
 
-    if dest.exists() {
-        fs::remove_dir_all(&dest)
-            .map_err(|e| format!("Failed to remove old Quick Look app: {}", e))?;
+/// Drop recent entries whose files have been moved or deleted. Tauri has no
+/// event for a submenu opening, so the list is also pruned whenever the app
+/// is activated (see `activity`).
+#[tauri::command]
+fn clear_missing_recent(app: tauri::AppHandle) {
+    prune_missing_recent(&app);
+}
+
+/// The recent list as the "Open Recent" menu shows it, minus files that
+/// have since disappeared.
+#[tauri::command]
+fn get_recent_files(app: tauri::AppHandle) -> Vec<String> {
+    recent_paths(&app)
+        .into_iter()
+        .filter(|p| std::path::Path::new(p).exists())
+        .collect()
+}
+
+/// Returns and clears the file paths that were pending before the frontend
+/// loaded. Windows opened by the backend (e.g. `duplicate_window`) get their
+/// own file first.
+#[tauri::command]
+fn get_opened_file(
+    window: tauri::Window,
+    state: tauri::State<PendingFiles>,
+    queue: tauri::State<windows::WindowOpenQueue>,
+) -> Vec<String> {
+    if window.label() == windows::MAIN_WINDOW {
+        MAIN_WINDOW_READY.store(true, Ordering::SeqCst);
     }
+    windows::take_opened_files(&mut queue.0.lock_or_recover(), &mut state.0.lock_or_recover(), window.label())
+}
 
-    copy_dir_recursive(src, &dest)
-        .map_err(|e| format!("Failed to copy Quick Look app: {}", e))?;
+/// `get_opened_file` for frontends that open a single file: returns the
+/// first pending path and leaves the others queued.
+#[tauri::command]
+fn get_opened_file_single(
+    window: tauri::Window,
+    state: tauri::State<PendingFiles>,
+    queue: tauri::State<windows::WindowOpenQueue>,
+) -> Option<String> {
+    windows::take_opened_file(&mut queue.0.lock_or_recover(), &mut state.0.lock_or_recover(), window.label())
+}
 
-    let appex = dest.join("Contents").join("PlugIns").join("UpDownPreview.appex");
-    if appex.exists() {
-        let path = appex.to_string_lossy();
-        let _ = Command::new("pluginkit").args(["-a", path.as_ref()]).output();
-        let _ = Command::new("pluginkit")
-            .args(["-e", "use", "-i", "com.noam.updown.quicklook.preview"])
-            .output();
+/// Number of launch files still waiting for this window's `get_opened_file`,
+/// so the frontend can hold off showing an empty editor.
+#[tauri::command]
+fn pending_files_count(
+    window: tauri::Window,
+    state: tauri::State<PendingFiles>,
+    queue: tauri::State<windows::WindowOpenQueue>,
+) -> usize {
+    windows::opened_file_count(&queue.0.lock_or_recover(), &state.0.lock_or_recover(), window.label())
+}
+
+/// Build and platform details for the About dialog and bug reports.
+#[derive(serde::Serialize)]
+struct VersionInfo {
+    version: String,
+    tauri_version: &'static str,
+    /// Short git hash of the build, if it was built from a checkout.
+    commit: Option<&'static str>,
+    /// Date of that commit, `YYYY-MM-DD`.
+    commit_date: Option<&'static str>,
+    os: &'static str,
+    arch: &'static str,
+}
+
+#[tauri::command]
+fn app_version_info(app: tauri::AppHandle) -> VersionInfo {
+    VersionInfo {
+        version: app.package_info().version.to_string(),
+        tauri_version: tauri::VERSION,
+        commit: option_env!("UPDOWN_GIT_COMMIT"),
+        commit_date: option_env!("UPDOWN_COMMIT_DATE"),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
     }
+}
 
-    let _ = Command::new("/usr/bin/open").arg(&dest).output();
-    Ok(format!("Quick Look extension installed to {}", dest.display()))
+/// Open `path` like a normal open, but flagged read-only so the backend
+/// refuses to write it back while this window shows it.
+#[tauri::command]
+fn open_read_only(window: tauri::WebviewWindow, path: String) -> Result<(), String> {
+    files::check_openable(&path)?;
+    let payload = files::open_payload(path, true);
+    documents::note_opening(window.app_handle(), window.label(), &payload);
+    windows::deliver_open(&window, &payload, false);
+    Ok(())
 }
 
-#[cfg(target_os = "macos")]
-fn copy_dir_recursive(src: &std::path::Path, dst: &std::path::Path) -> std::io::Result<()> {
-    use std::fs;
-    fs::create_dir_all(dst)?;
-    for entry in fs::read_dir(src)? {
-        let entry = entry?;
-        let src_path = entry.path();
-        let dst_path = dst.join(entry.file_name());
-        if src_path.is_dir() {
-            copy_dir_recursive(&src_path, &dst_path)?;
-        } else {
-            fs::copy(&src_path, &dst_path)?;
-        }
+/// Write the editor buffer to `path`, backing up the old contents if
+/// enabled. Files open read-only (explicitly or because they lack write
+/// permission) are refused unless `force` is set, which lifts the flag.
+#[tauri::command]
+fn write_markdown_file(
+    app: tauri::AppHandle,
+    path: String,
+    content: String,
+    force: Option<bool>,
+) -> Result<(), String> {
+    if force.unwrap_or(false) {
+        documents::clear_read_only(&app, &path);
     }
+    backups::before_save(&app, &path);
+    write_document(&app, &path, &content)
+}
+
+/// Write a document atomically, refusing files open read-only. Shared by
+/// the save commands and auto-save.
+fn write_document(app: &tauri::AppHandle, path: &str, content: &str) -> Result<(), String> {
+    if documents::is_read_only(app, path) {
+        return Err(format!("{} is open read-only", path));
+    }
+    files::write_atomic(std::path::Path::new(path), content.as_bytes())
+        .map_err(|e| files::describe_write_error(path, &e))?;
+    watch::note_own_write(std::path::Path::new(path));
     Ok(())
 }
 
+/// Save the editor buffer to `path` atomically (backing up the old contents
+/// if enabled) and add it to the recent list. Refuses files opened read-only, like `write_markdown_file`.
+/// Returns the file's new stamp, for auto-save to compare against.
+#[tauri::command]
+fn save_file(app: tauri::AppHandle, path: String, content: String) -> Result<watch::Snapshot, String> {
+    backups::before_save(&app, &path);
+    write_document(&app, &path, &content)?;
+    let disk = watch::snapshot(std::path::Path::new(&path));
+    add_recent_file(app, path);
+    Ok(disk)
+}
+
+/// Open `path` in the main window like Finder's "Open With", but leave
+/// focus alone unless `focus` is set (for scripted or tray batch opens).
+#[tauri::command]
+fn open_file_bg(app: tauri::AppHandle, path: String, focus: bool) -> Result<(), String> {
+    files::check_openable(&path)?;
+    if open_file_in_running_app(&app, &path, focus) {
+        Ok(())
+    } else {
+        Err("The main window is not open".to_string())
+    }
+}
+
 // ── Frontend bridge ───────────────────────────────────────────────────────────
 
-fn open_file_in_running_app(app: &tauri::AppHandle, path_str: &str) {
-    if let Some(window) = app.get_webview_window("main") {
-        let escaped = path_str.replace('\\', "\\\\").replace('\'', "\\'");
-        let js = format!("window.__openFile && window.__openFile('{}')", escaped);
-        let _ = window.eval(&js);
+/// Hand `path` to the main window's frontend, raising the window if `focus`.
+fn open_file_in_running_app(app: &tauri::AppHandle, path_str: &str, focus: bool) -> bool {
+    open_file_in_window(app, windows::MAIN_WINDOW, path_str, focus)
+}
+
+/// Hand `path` to window `label`'s frontend, raising the window if `focus`.
+fn open_file_in_window(app: &tauri::AppHandle, label: &str, path_str: &str, focus: bool) -> bool {
+    let Some(window) = app.get_webview_window(label) else { return false };
+    let file = windows::open_path_in(&window, path_str, focus);
+    documents::note_opening(app, label, &file);
+    true
+}
+
+/// Open a batch of files handed over by the OS: the last goes to the main
+/// window (or waits in `PendingFiles` on a cold start), the others get
+/// windows of their own. All of them get new windows while the main
+/// window has unsaved changes.
+fn open_files(app: &tauri::AppHandle, mut paths: Vec<String>) {
+    // Never replace unsaved work in the main window.
+    let main_dirty = app
+        .state::<documents::Documents>()
+        .0
+        .lock_or_recover()
+        .get(windows::MAIN_WINDOW)
+        .is_some_and(|d| d.dirty);
+    if main_dirty {
+        for path in paths {
+            let _ = windows::create_document_window(app, Some(path));
+        }
+        return;
+    }
+    let Some(last) = paths.pop() else { return };
+    for path in paths {
+        let _ = windows::create_document_window(app, Some(path));
+    }
+    if !MAIN_WINDOW_READY.load(Ordering::SeqCst) {
+        if let Some(state) = app.try_state::<PendingFiles>() {
+            state.0.lock_or_recover().push(last.clone());
+        }
+    }
+    open_file_in_running_app(app, &last, true);
+}
+
+/// Another launch handed over its files (see `single_instance`): open them
+/// like an `Opened` event, or just bring the app forward if there are none.
+fn second_launch(app: &tauri::AppHandle, paths: Vec<String>) {
+    if paths.is_empty() {
+        if let Some(window) = app.get_webview_window(windows::MAIN_WINDOW) {
+            let _ = window.unminimize();
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    } else {
+        open_files(app, paths);
     }
 }
 
@@ -220,194 +693,322 @@ fn open_file_in_running_app(app: &tauri::AppHandle, path_str: &str) {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let app = tauri::Builder::default()
+    let context = tauri::generate_context!();
+    let mut builder = tauri::Builder::default();
+    // First, so a second launch exits before setting anything else up.
+    if single_instance::enabled(&context.config().identifier) {
+        builder = builder.plugin(single_instance::plugin());
+    }
+    let app = builder
+        // Stdout and a file in the app's log folder, for the failures that
+        // happen off any window.
+        .plugin(tauri_plugin_log::Builder::new().level(log::LevelFilter::Info).build())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_process::init())
-        .manage(PendingFile(Mutex::new(None)))
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .manage(PendingFiles(Mutex::new(vec![])))
+        .manage(RestoreFolder(Mutex::new(None)))
         .manage(RecentFiles(Mutex::new(vec![])))
+        .manage(RecentFolders(Mutex::new(vec![])))
+        .manage(PinnedFiles(Mutex::new(vec![])))
+        .manage(RecentSortMode(Mutex::new(recent::RecentSort::default())))
+        .manage(MaxRecent(AtomicUsize::new(settings::DEFAULT_MAX_RECENT)))
+        .manage(documents::ReadOnlyFiles::default())
+        .manage(documents::Documents::default())
+        .manage(documents::PendingClose::default())
+        .manage(documents::ClosedFiles::default())
+        .manage(autosave::AutoSaves::default())
+        .manage(hotkey::QuickCapture::default())
+        .manage(tray::TrayRecent::default())
+        .manage(preview::PreviewCssWatch::default())
+        .manage(windows::WindowOpenQueue::default())
+        .manage(activity::FocusedWindows::default())
+        .manage(files::ChunkedReads::default())
+        .manage(watch::DocumentWatches::default())
         .invoke_handler(tauri::generate_handler![
             get_opened_file,
-            install_quicklook_plugin,
-            add_recent_file
+            get_opened_file_single,
+            pending_files_count,
+            app_version_info,
+            quicklook::install_quicklook_plugin,
+            quicklook::verify_quicklook_bundle,
+            quicklook::repair_quicklook,
+            quicklook::uninstall_quicklook_plugin,
+            quicklook::quicklook_status,
+            add_recent_file,
+            get_recent_files,
+            add_recent_folder,
+            get_recent_folders,
+            remove_recent_file,
+            pin_recent_file,
+            unpin_recent_file,
+            trash_file,
+            rename_file,
+            clear_missing_recent,
+            set_max_recent,
+            set_recent_sort,
+            open_read_only,
+            write_markdown_file,
+            save_file,
+            open_file_bg,
+            transforms::save_preview,
+            files::precheck_open,
+            files::peek_file,
+            files::read_file_chunked,
+            files::cancel_read,
+            files::read_file,
+            files::file_metadata,
+            view_state::save_view_state,
+            view_state::get_view_state,
+            files::reveal_in_file_manager,
+            files::open_in_default_app,
+            default_handler::set_as_default_markdown_handler,
+            default_handler::is_default_markdown_handler,
+            updates::check_for_updates,
+            files::duplicate_file,
+            assets::save_pasted_image,
+            watch::watch_file,
+            watch::unwatch_file,
+            spellcheck::spellcheck,
+            spellcheck::add_to_dictionary,
+            documents::set_dirty,
+            documents::confirm_quit,
+            documents::note_closed,
+            documents::dirty_documents,
+            windows::cycle_windows,
+            windows::print_preview,
+            windows::create_window,
+            windows::duplicate_window,
+            analysis::export_metadata,
+            analysis::reading_progress,
+            analysis::document_stats,
+            analysis::detect_indentation,
+            analysis::document_id,
+            analysis::read_file_with_meta,
+            preview::get_preview_css,
+            preview::watch_preview_css,
+            preview::unwatch_preview_css,
+            snippets::list_snippets,
+            snippets::save_snippet,
+            snippets::delete_snippet,
+            snippets::expand_snippet,
+            templates::list_templates,
+            templates::new_from_template,
+            vault::collect_front_matter,
+            vault::rename_with_link_updates,
+            vault::find_orphans,
+            vault::export_link_graph,
+            vault::duplicate_titles,
+            tree::tree_navigate,
+            tree::list_tree,
+            vault::batch_rename,
+            vault::resolve_wiki_link,
+            render::render_preview,
+            search::search_in_files,
+            render::render_range,
+            render::extract_outline,
+            render::render_export,
+            render::export_html,
+            pdf::export_pdf,
+            settings::get_expanded,
+            settings::set_expanded,
+            settings::get_openable_extensions,
+            settings::set_openable_extensions,
+            settings::get_zoom,
+            settings::set_zoom,
+            settings::set_last_folder,
+            settings::get_settings,
+            settings::set_setting,
+            settings::get_editor_settings,
+            settings::set_editor_settings,
+            theme::get_theme,
+            theme::set_theme,
+            hotkey::set_quick_capture_shortcut,
+            tray::set_tray_enabled,
+            clipboard::copy_as_image,
+            clipboard::write_clipboard_image,
+            clipboard::copy_file_path,
+            autosave::schedule_autosave,
+            backups::list_backups,
+            backups::restore_backup,
+            activity::is_app_active,
+            menu::list_shortcuts,
+            menu::set_document_open
         ])
         .setup(|app| {
-            // Load persisted recent files and seed state.
+            // The openable extensions filter the files a launch hands over.
+            settings::apply_on_startup(app.handle());
+
+            // Load persisted recent files and seed state, honouring the
+            // saved list length.
+            let limit = settings::load_max_recent(app.handle());
+            app.state::<MaxRecent>().0.store(limit, Ordering::Relaxed);
+            *app.state::<RecentSortMode>().0.lock_or_recover() = settings::load_recent_sort(app.handle());
             let initial_recent = load_recent_from_disk(app.handle());
-            *app.state::<RecentFiles>().0.lock().unwrap() = initial_recent.clone();
-
-            // ── App menu ──────────────────────────────────────────────────────
-            let about_item = MenuItem::with_id(app, "about", "About UpDown", true, None::<&str>)?;
-
-            let app_menu = SubmenuBuilder::new(app, "UpDown")
-                .item(&about_item)
-                .separator()
-                .item(&PredefinedMenuItem::hide(app, None::<&str>)?)
-                .item(&PredefinedMenuItem::hide_others(app, None::<&str>)?)
-                .item(&PredefinedMenuItem::show_all(app, None::<&str>)?)
-                .separator()
-                .item(&PredefinedMenuItem::quit(app, None::<&str>)?)
-                .build()?;
-
-            // ── File menu ─────────────────────────────────────────────────────
-            let open_item = MenuItem::with_id(app, "open", "Open…", true, Some("CmdOrCtrl+O"))?;
-            let save_item = MenuItem::with_id(app, "save", "Save", true, Some("CmdOrCtrl+S"))?;
-            let save_as_item = MenuItem::with_id(app, "save_as", "Save As…", true, Some("CmdOrCtrl+Shift+S"))?;
-            let install_ql_item = MenuItem::with_id(app, "install_quicklook", "Install Quick Look Plugin…", true, None::<&str>)?;
-
-            // Build the "Open Recent" submenu.
-            let mut recent_builder = SubmenuBuilder::new(app, "Open Recent").id("open_recent");
-            if initial_recent.is_empty() {
-                let no_recent = MenuItem::with_id(app, "no_recent", "No Recent Items", false, None::<&str>)?;
-                recent_builder = recent_builder.item(&no_recent);
-            } else {
-                for (i, path) in initial_recent.iter().enumerate() {
-                    let label = path_basename(path);
-                    let id = format!("recent_{i}");
-                    let item = MenuItem::with_id(app, id, label, true, None::<&str>)?;
-                    recent_builder = recent_builder.item(&item);
-                }
-                let clear_init = MenuItem::with_id(app, "clear_recent", "Clear Recent Items", true, None::<&str>)?;
-                recent_builder = recent_builder.separator().item(&clear_init);
-            }
-            let recent_submenu = recent_builder.build()?;
-
-            let file_menu = SubmenuBuilder::new(app, "File")
-                .item(&open_item)
-                .item(&recent_submenu)
-                .separator()
-                .item(&save_item)
-                .item(&save_as_item)
-                .separator()
-                .item(&install_ql_item)
-                .separator()
-                .item(&PredefinedMenuItem::close_window(app, None::<&str>)?)
-                .build()?;
-
-            // ── Edit menu ─────────────────────────────────────────────────────
-            let edit_menu = SubmenuBuilder::new(app, "Edit")
-                .item(&PredefinedMenuItem::undo(app, None::<&str>)?)
-                .item(&PredefinedMenuItem::redo(app, None::<&str>)?)
-                .separator()
-                .item(&PredefinedMenuItem::cut(app, None::<&str>)?)
-                .item(&PredefinedMenuItem::copy(app, None::<&str>)?)
-                .item(&PredefinedMenuItem::paste(app, None::<&str>)?)
-                .item(&PredefinedMenuItem::select_all(app, None::<&str>)?)
-                .build()?;
-
-            // ── View menu ─────────────────────────────────────────────────────
-            let toggle_folder_item = MenuItem::with_id(app, "toggle_folder", "Toggle Folder Panel", true, Some("CmdOrCtrl+B"))?;
-            let source_item = MenuItem::with_id(app, "view_source", "Source", true, Some("CmdOrCtrl+1"))?;
-            let preview_item = MenuItem::with_id(app, "view_preview", "Preview", true, Some("CmdOrCtrl+2"))?;
-            let split_item = MenuItem::with_id(app, "view_split", "Split", true, Some("CmdOrCtrl+3"))?;
-
-            let view_menu = SubmenuBuilder::new(app, "View")
-                .item(&toggle_folder_item)
-                .separator()
-                .item(&source_item)
-                .item(&preview_item)
-                .item(&split_item)
-                .build()?;
-
-            // ── Window menu ───────────────────────────────────────────────────
-            let window_menu = SubmenuBuilder::new(app, "Window")
-                .item(&PredefinedMenuItem::minimize(app, None::<&str>)?)
-                .item(&PredefinedMenuItem::maximize(app, None::<&str>)?)
-                .separator()
-                .item(&PredefinedMenuItem::fullscreen(app, None::<&str>)?)
-                .build()?;
-
-            let menu = MenuBuilder::new(app)
-                .item(&app_menu)
-                .item(&file_menu)
-                .item(&edit_menu)
-                .item(&view_menu)
-                .item(&window_menu)
-                .build()?;
+            *app.state::<RecentFiles>().0.lock_or_recover() = initial_recent;
+            *app.state::<RecentFolders>().0.lock_or_recover() = load_recent_folders_from_disk(app.handle());
+            *app.state::<PinnedFiles>().0.lock_or_recover() = load_pinned_from_disk(app.handle());
+            *app.state::<RestoreFolder>().0.lock_or_recover() = settings::load_last_folder(app.handle());
 
+            let menu = menu::build_menu(app)?;
             app.set_menu(menu)?;
+            rebuild_recent_menu(app.handle());
+            rebuild_recent_folders_menu(app.handle());
+            if let Err(e) = templates::templates_dir(app.handle()).and_then(|d| templates::seed_defaults(&d)) {
+                log::warn!("{}", e);
+            }
+            rebuild_templates_menu(app.handle());
+            hotkey::register_saved(app.handle());
+            tray::apply_saved(app.handle());
+            // Nothing is open until the frontend says otherwise.
+            menu::set_items_enabled(app.handle(), menu::DOCUMENT_ITEMS, false);
+            menu::set_items_enabled(app.handle(), &[menu::REOPEN_CLOSED_ID], false);
+
+            // The main window starts hidden so it appears at its saved
+            // geometry (and theme) rather than jumping there.
+            theme::apply_saved_theme(app.handle());
+            window_state::restore(app.handle());
+
+            // Windows and Linux pass associated files on the command line
+            // instead of through `RunEvent::Opened` (as does a terminal
+            // launch on macOS).
+            open_files(app.handle(), windows::launch_paths(std::env::args()));
             Ok(())
         })
+        .on_page_load(|webview, payload| {
+            // Reopen last session's folder once the main window can hear it.
+            if webview.label() != windows::MAIN_WINDOW || payload.event() != tauri::webview::PageLoadEvent::Finished {
+                return;
+            }
+            let app = webview.app_handle();
+            if let Some(folder) = app.state::<RestoreFolder>().0.lock_or_recover().take() {
+                let _ = app.emit_to(windows::MAIN_WINDOW, "restore-folder", folder);
+            }
+        })
         .on_menu_event(|app, event| {
             let id = event.id().0.as_str();
             match id {
-                "about" => {
-                    if let Some(w) = app.get_webview_window("main") {
-                        let _ = w.eval("window.__menuAction && window.__menuAction('about')");
-                    }
-                }
-                "install_quicklook" => {
-                    if let Some(w) = app.get_webview_window("main") {
-                        let _ = w.eval("window.__menuAction && window.__menuAction('installQuickLook')");
-                    }
+                "cycle_windows" => windows::cycle_windows(app.clone(), true),
+                "bring_all_to_front" => windows::bring_all_to_front(app),
+                menu::REOPEN_CLOSED_ID => documents::reopen_last_closed(app),
+                "new_window" => {
+                    let _ = windows::create_document_window(app, None);
                 }
                 "clear_recent" => {
                     {
                         let state = app.state::<RecentFiles>();
-                        let mut files = state.0.lock().unwrap();
+                        let mut files = state.0.lock_or_recover();
                         files.clear();
                         save_recent_to_disk(app, &files);
                     }
-                    rebuild_recent_menu(app);
+                    recent_files_changed(app);
+                }
+                "open_templates_folder" => {
+                    if let Ok(dir) = templates::templates_dir(app) {
+                        let _ = tauri_plugin_opener::open_path(dir, None::<&str>);
+                    }
+                }
+                id if id.starts_with("template_") => {
+                    let label = windows::focused_label(app).unwrap_or_else(|| windows::MAIN_WINDOW.to_string());
+                    if let Some(w) = app.get_webview_window(&label) {
+                        menu::send_action(&w, "newFromTemplate", vec![id["template_".len()..].into()]);
+                    }
+                }
+                id if id.starts_with("pinned_") => {
+                    let path = id["pinned_".len()..]
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|idx| app.state::<PinnedFiles>().0.lock_or_recover().get(idx).cloned());
+                    if let Some(path) = path {
+                        open_file_in_running_app(app, &path, true);
+                    }
+                }
+                id if id.starts_with("recent_folder_") => {
+                    let folder = id["recent_folder_".len()..]
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|idx| app.state::<RecentFolders>().0.lock_or_recover().get(idx).cloned());
+                    let label = windows::focused_label(app).unwrap_or_else(|| windows::MAIN_WINDOW.to_string());
+                    if let (Some(folder), Some(w)) = (folder, app.get_webview_window(&label)) {
+                        menu::send_action(&w, "openFolder", vec![folder.into()]);
+                    }
                 }
                 id if id.starts_with("recent_") => {
                     if let Ok(idx) = id["recent_".len()..].parse::<usize>() {
-                        let path = app
-                            .state::<RecentFiles>()
-                            .0
-                            .lock()
-                            .unwrap()
-                            .get(idx)
-                            .cloned();
-                        if let Some(path) = path {
-                            open_file_in_running_app(app, &path);
+                        if let Some(path) = recent_paths(app).get(idx) {
+                            open_file_in_running_app(app, path, true);
                         }
                     }
                 }
-                "open" | "save" | "save_as" | "toggle_folder"
-                | "view_source" | "view_preview" | "view_split" => {
-                    if let Some(w) = app.get_webview_window("main") {
-                        let action = match id {
-                            "open" => "open",
-                            "save" => "save",
-                            "save_as" => "saveAs",
-                            "toggle_folder" => "toggleFolder",
-                            "view_source" => "viewSource",
-                            "view_preview" => "viewPreview",
-                            "view_split" => "viewSplit",
-                            _ => return,
-                        };
-                        let js = format!(
-                            "window.__menuAction && window.__menuAction('{}')",
-                            action
-                        );
-                        let _ = w.eval(&js);
+                id => {
+                    // Actions apply to the window the user is working in.
+                    let label = windows::focused_label(app).unwrap_or_else(|| windows::MAIN_WINDOW.to_string());
+                    if let (Some(action), Some(w)) = (menu::frontend_action(id), app.get_webview_window(&label)) {
+                        menu::send_action(&w, action, vec![]);
                     }
                 }
-                _ => {}
             }
         })
-        .build(tauri::generate_context!())
+        .on_window_event(|window, event| match event {
+            tauri::WindowEvent::Focused(focused) => {
+                activity::window_focus_changed(window.app_handle(), window.label(), *focused);
+                // Pick up templates added to the folder while we were away.
+                if *focused {
+                    rebuild_templates_menu(window.app_handle());
+                }
+            }
+            tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) => {
+                windows::files_dropped(window, paths);
+            }
+            tauri::WindowEvent::ThemeChanged(theme) => {
+                theme::system_theme_changed(window, *theme);
+            }
+            tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) => {
+                window_state::window_changed(window, false);
+            }
+            tauri::WindowEvent::CloseRequested { api, .. } => {
+                window_state::window_changed(window, true);
+                if documents::close_requested(window) {
+                    api.prevent_close();
+                }
+            }
+            tauri::WindowEvent::Destroyed => {
+                let app = window.app_handle();
+                let state = app.state::<documents::Documents>();
+                let closed = state.0.lock_or_recover().remove(window.label()).and_then(|d| d.path);
+                if let Some(path) = closed {
+                    documents::note_closed_path(app, path);
+                }
+                app.state::<documents::ReadOnlyFiles>().0.lock_or_recover().remove(window.label());
+                watch::stop_watching(app, window.label());
+                preview::stop_watching(app, window.label());
+                activity::window_focus_changed(app, window.label(), false);
+            }
+            _ => {}
+        })
+        .build(context)
         .expect("error while building tauri application");
 
     app.run(|app_handle, event| {
+        // Unsaved documents: hold the exit and let the frontend ask once for
+        // all of them; it answers through `confirm_quit`.
+        if let tauri::RunEvent::ExitRequested { api, .. } = &event {
+            if documents::exit_requested(app_handle) {
+                api.prevent_exit();
+            }
+        }
+
         #[cfg(any(target_os = "macos", target_os = "ios"))]
         if let tauri::RunEvent::Opened { urls } = event {
-            let files: Vec<PathBuf> = urls
+            let files: Vec<String> = urls
                 .into_iter()
                 .filter_map(|url| url.to_file_path().ok())
+                .map(|p| p.to_string_lossy().to_string())
                 .collect();
-
-            if let Some(file) = files.first() {
-                let path_str = file.to_string_lossy().to_string();
-
-                if let Some(state) = app_handle.try_state::<PendingFile>() {
-                    *state.0.lock().unwrap() = Some(path_str.clone());
-                }
-
-                open_file_in_running_app(app_handle, &path_str);
-            }
+            open_files(app_handle, windows::dedup_paths(files));
         }
     });
 }
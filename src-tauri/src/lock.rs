@@ -0,0 +1,38 @@
+use std::sync::{Mutex, MutexGuard};
+
+/// Locking that survives a poisoned mutex. A panic while one of our locks
+/// is held (say, mid menu rebuild) leaves the data itself intact, so
+/// carrying on beats every later command panicking too.
+pub trait LockExt<T> {
+    fn lock_or_recover(&self) -> MutexGuard<'_, T>;
+}
+
+impl<T> LockExt<T> for Mutex<T> {
+    fn lock_or_recover(&self) -> MutexGuard<'_, T> {
+        self.lock().unwrap_or_else(|poisoned| {
+            log::warn!("recovering from a poisoned lock");
+            self.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recent::{self, RecentEntry};
+
+    #[test]
+    fn recent_list_is_usable_after_a_panic_poisons_its_lock() {
+        let files = Mutex::new(vec![RecentEntry::new("/a.md".into(), 1)]);
+        let _ = std::panic::catch_unwind(|| {
+            let _guard = files.lock().unwrap();
+            panic!("menu rebuild failed");
+        });
+        assert!(files.is_poisoned());
+
+        recent::touch(&mut files.lock_or_recover(), "/b.md".into(), 2);
+        assert_eq!(recent::ordered_paths(&files.lock_or_recover(), recent::RecentSort::Recency), vec!["/b.md", "/a.md"]);
+        assert!(!files.is_poisoned());
+    }
+}
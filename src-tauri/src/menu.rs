@@ -0,0 +1,562 @@
+use serde::Serialize;
+use tauri::menu::{Menu, MenuBuilder, MenuItem, PredefinedMenuItem, Submenu, SubmenuBuilder};
+
+/// Id of "Reopen Last Closed File", enabled while there is one to reopen.
+pub const REOPEN_CLOSED_ID: &str = "reopen_last_closed";
+
+/// Id of the "Open Recent" submenu, repopulated as the recent list changes.
+pub const OPEN_RECENT_ID: &str = "open_recent";
+
+/// Id of the "Open Recent Folder" submenu.
+pub const OPEN_RECENT_FOLDERS_ID: &str = "open_recent_folders";
+
+/// Id of the "New from Template" submenu, filled from the templates folder.
+pub const NEW_FROM_TEMPLATE_ID: &str = "new_from_template";
+
+/// A custom menu item. `action` is the frontend action the item forwards to
+/// (see `send_action`); items handled in Rust have none.
+pub struct ItemSpec {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub accelerator: Option<&'static str>,
+    pub action: Option<&'static str>,
+}
+
+const fn item(
+    id: &'static str,
+    label: &'static str,
+    accelerator: Option<&'static str>,
+    action: Option<&'static str>,
+) -> ItemSpec {
+    ItemSpec { id, label, accelerator, action }
+}
+
+#[cfg(target_os = "macos")]
+const REVEAL_LABEL: &str = "Reveal in Finder";
+#[cfg(target_os = "windows")]
+const REVEAL_LABEL: &str = "Show in Explorer";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const REVEAL_LABEL: &str = "Show in File Manager";
+
+/// Every custom menu item; the menu and the shortcut list are built from this.
+pub const ITEMS: &[ItemSpec] = &[
+    item("about", "About UpDown", None, Some("about")),
+    item("check_for_updates", "Check for Updates…", None, Some("checkForUpdates")),
+    item("set_default_handler", "Make Default Markdown App…", None, Some("setDefaultHandler")),
+    item("new_file", "New", Some("CmdOrCtrl+N"), Some("newFile")),
+    item("new_window", "New Window", Some("CmdOrCtrl+Shift+N"), None),
+    item("open", "Open…", Some("CmdOrCtrl+O"), Some("open")),
+    item(REOPEN_CLOSED_ID, "Reopen Last Closed File", Some("CmdOrCtrl+Shift+T"), None),
+    item("save", "Save", Some("CmdOrCtrl+S"), Some("save")),
+    item("save_as", "Save As…", Some("CmdOrCtrl+Shift+S"), Some("saveAs")),
+    item("export_html", "HTML…", None, Some("exportHtml")),
+    item("export_pdf", "PDF…", None, Some("exportPdf")),
+    item("print", "Print…", Some("CmdOrCtrl+P"), Some("print")),
+    item("reveal", REVEAL_LABEL, Some("CmdOrCtrl+Alt+R"), Some("revealInFinder")),
+    item("open_default_app", "Open in Default App", None, Some("openInDefaultApp")),
+    item("move_to_trash", "Move to Trash", None, Some("moveToTrash")),
+    item("copy_file_path", "Copy File Path", Some("CmdOrCtrl+Alt+Shift+C"), Some("copyFilePath")),
+    item("copy_relative_path", "Copy Relative Path", None, Some("copyRelativePath")),
+    item("install_quicklook", "Install Quick Look Plugin…", None, Some("installQuickLook")),
+    item("copy_as_image", "Copy as Image", Some("CmdOrCtrl+Alt+C"), Some("copyAsImage")),
+    item("toggle_folder", "Toggle Folder Panel", Some("CmdOrCtrl+B"), Some("toggleFolder")),
+    item("view_source", "Source", Some("CmdOrCtrl+1"), Some("viewSource")),
+    item("view_preview", "Preview", Some("CmdOrCtrl+2"), Some("viewPreview")),
+    item("view_split", "Split", Some("CmdOrCtrl+3"), Some("viewSplit")),
+    item("view_zoom_in", "Zoom In", Some("CmdOrCtrl+="), Some("zoomIn")),
+    item("view_zoom_out", "Zoom Out", Some("CmdOrCtrl+-"), Some("zoomOut")),
+    item("view_zoom_reset", "Actual Size", Some("CmdOrCtrl+0"), Some("zoomReset")),
+    item("cycle_windows", "Cycle Through Windows", Some("CmdOrCtrl+`"), None),
+    item("bring_all_to_front", "Bring All to Front", None, None),
+];
+
+/// Items that need an open document; see `set_document_open`.
+pub const DOCUMENT_ITEMS: &[&str] = &[
+    "save",
+    "save_as",
+    "reveal",
+    "open_default_app",
+    "copy_file_path",
+    "copy_relative_path",
+    "move_to_trash",
+    "print",
+];
+
+/// Payload of the `menu-action` event.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MenuAction {
+    pub action: String,
+    pub args: Vec<serde_json::Value>,
+}
+
+/// OS-provided menu items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Predefined {
+    Hide,
+    HideOthers,
+    ShowAll,
+    Quit,
+    CloseWindow,
+    Undo,
+    Redo,
+    Cut,
+    Copy,
+    Paste,
+    SelectAll,
+    Minimize,
+    Zoom,
+    Fullscreen,
+}
+
+impl Predefined {
+    fn build<R: tauri::Runtime, M: tauri::Manager<R>>(self, m: &M) -> tauri::Result<PredefinedMenuItem<R>> {
+        match self {
+            Predefined::Hide => PredefinedMenuItem::hide(m, None),
+            Predefined::HideOthers => PredefinedMenuItem::hide_others(m, None),
+            Predefined::ShowAll => PredefinedMenuItem::show_all(m, None),
+            Predefined::Quit => PredefinedMenuItem::quit(m, None),
+            Predefined::CloseWindow => PredefinedMenuItem::close_window(m, None),
+            Predefined::Undo => PredefinedMenuItem::undo(m, None),
+            Predefined::Redo => PredefinedMenuItem::redo(m, None),
+            Predefined::Cut => PredefinedMenuItem::cut(m, None),
+            Predefined::Copy => PredefinedMenuItem::copy(m, None),
+            Predefined::Paste => PredefinedMenuItem::paste(m, None),
+            Predefined::SelectAll => PredefinedMenuItem::select_all(m, None),
+            Predefined::Minimize => PredefinedMenuItem::minimize(m, None),
+            Predefined::Zoom => PredefinedMenuItem::maximize(m, Some("Zoom")),
+            Predefined::Fullscreen => PredefinedMenuItem::fullscreen(m, None),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Entry {
+    /// A custom item from `ITEMS`, by id.
+    Item(&'static str),
+    Predefined(Predefined),
+    Separator,
+    /// The "Open Recent" submenu; filled in by `rebuild_recent_menu`.
+    OpenRecent,
+    /// The "Open Recent Folder" submenu; filled in by
+    /// `rebuild_recent_folders_menu`.
+    OpenRecentFolders,
+    /// The "New from Template" submenu; filled in by
+    /// `rebuild_templates_menu`.
+    NewFromTemplate,
+    /// A nested submenu.
+    Submenu(&'static str, &'static [Entry]),
+}
+
+/// One top-level menu.
+pub struct MenuSection {
+    pub title: &'static str,
+    pub entries: Vec<Entry>,
+}
+
+/// The whole menu bar, top to bottom.
+pub fn menu_spec() -> Vec<MenuSection> {
+    use Entry::{Item, NewFromTemplate, OpenRecent, OpenRecentFolders, Predefined as P, Separator, Submenu};
+    use Predefined::*;
+    vec![
+        MenuSection {
+            title: "UpDown",
+            entries: vec![
+                Item("about"),
+                Item("check_for_updates"),
+                Item("set_default_handler"),
+                Separator,
+                P(Hide),
+                P(HideOthers),
+                P(ShowAll),
+                Separator,
+                P(Quit),
+            ],
+        },
+        MenuSection {
+            title: "File",
+            entries: vec![
+                Item("new_file"),
+                NewFromTemplate,
+                Item("new_window"),
+                Item("open"),
+                OpenRecent,
+                OpenRecentFolders,
+                Item(REOPEN_CLOSED_ID),
+                Separator,
+                Item("save"),
+                Item("save_as"),
+                Submenu("Export", &[Item("export_html"), Item("export_pdf")]),
+                Separator,
+                Item("reveal"),
+                Item("open_default_app"),
+                Item("copy_file_path"),
+                Item("copy_relative_path"),
+                Separator,
+                Item("move_to_trash"),
+                Separator,
+                Item("print"),
+                Separator,
+                Item("install_quicklook"),
+                Separator,
+                P(CloseWindow),
+            ],
+        },
+        MenuSection {
+            title: "Edit",
+            entries: vec![
+                P(Undo),
+                P(Redo),
+                Separator,
+                P(Cut),
+                P(Copy),
+                Item("copy_as_image"),
+                P(Paste),
+                P(SelectAll),
+            ],
+        },
+        MenuSection {
+            title: "View",
+            entries: vec![
+                Item("toggle_folder"),
+                Separator,
+                Item("view_source"),
+                Item("view_preview"),
+                Item("view_split"),
+                Separator,
+                Item("view_zoom_in"),
+                Item("view_zoom_out"),
+                Item("view_zoom_reset"),
+            ],
+        },
+        MenuSection {
+            title: "Window",
+            entries: vec![
+                P(Minimize),
+                P(Zoom),
+                Separator,
+                P(Fullscreen),
+                Separator,
+                Item("cycle_windows"),
+                Separator,
+                Item("bring_all_to_front"),
+            ],
+        },
+    ]
+}
+
+/// One row of the shortcuts overlay.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ShortcutInfo {
+    pub action: String,
+    pub label: String,
+    pub accelerator: String,
+}
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+pub fn spec(id: &str) -> Option<&'static ItemSpec> {
+    ITEMS.iter().find(|s| s.id == id)
+}
+
+/// Frontend action forwarded for menu item `id`, if any.
+pub fn frontend_action(id: &str) -> Option<&'static str> {
+    spec(id).and_then(|s| s.action)
+}
+
+/// Script calling `window.__menuAction(action, ...args)` for frontends that
+/// don't listen for `menu-action`. Everything is passed as JSON literals, so
+/// no value can break out of the call.
+pub fn action_script(action: &MenuAction) -> String {
+    let call_args: Vec<String> = std::iter::once(serde_json::Value::from(action.action.as_str()))
+        .chain(action.args.iter().cloned())
+        .map(|v| v.to_string())
+        .collect();
+    format!(
+        "if (!window.__menuActionEvents && window.__menuAction) window.__menuAction({})",
+        call_args.join(", ")
+    )
+}
+
+/// Run frontend `action` in `window`: emit `menu-action` to it, with the
+/// `__menuAction` eval as a fallback that a listening frontend ignores.
+pub fn send_action(window: &tauri::WebviewWindow, action: &str, args: Vec<serde_json::Value>) {
+    use tauri::Emitter;
+    let action = MenuAction { action: action.to_string(), args };
+    let _ = window.emit_to(window.label(), "menu-action", &action);
+    let _ = window.eval(action_script(&action));
+}
+
+/// Build the native item for `id` from its spec.
+pub fn build_item<R: tauri::Runtime, M: tauri::Manager<R>>(
+    manager: &M,
+    id: &str,
+) -> tauri::Result<MenuItem<R>> {
+    let spec = spec(id).unwrap_or_else(|| panic!("menu item '{}' is not in menu::ITEMS", id));
+    MenuItem::with_id(manager, spec.id, spec.label, true, spec.accelerator)
+}
+
+fn build_submenu<R: tauri::Runtime, M: tauri::Manager<R>>(
+    manager: &M,
+    title: &str,
+    entries: &[Entry],
+) -> tauri::Result<Submenu<R>> {
+    let mut builder = SubmenuBuilder::new(manager, title);
+    for entry in entries {
+        builder = match *entry {
+            Entry::Item(id) => builder.item(&build_item(manager, id)?),
+            Entry::Predefined(p) => builder.item(&p.build(manager)?),
+            Entry::Separator => builder.separator(),
+            Entry::OpenRecent => {
+                let recent: Submenu<R> = SubmenuBuilder::new(manager, "Open Recent").id(OPEN_RECENT_ID).build()?;
+                builder.item(&recent)
+            }
+            Entry::OpenRecentFolders => {
+                let recent: Submenu<R> =
+                    SubmenuBuilder::new(manager, "Open Recent Folder").id(OPEN_RECENT_FOLDERS_ID).build()?;
+                builder.item(&recent)
+            }
+            Entry::NewFromTemplate => {
+                let templates: Submenu<R> =
+                    SubmenuBuilder::new(manager, "New from Template").id(NEW_FROM_TEMPLATE_ID).build()?;
+                builder.item(&templates)
+            }
+            Entry::Submenu(title, entries) => builder.item(&build_submenu(manager, title, entries)?),
+        };
+    }
+    builder.build()
+}
+
+/// Build the native menu bar from `menu_spec`. The recent and template
+/// submenus start empty.
+pub fn build_menu<R: tauri::Runtime, M: tauri::Manager<R>>(manager: &M) -> tauri::Result<Menu<R>> {
+    let mut bar = MenuBuilder::new(manager);
+    for section in menu_spec() {
+        bar = bar.item(&build_submenu(manager, section.title, &section.entries)?);
+    }
+    bar.build()
+}
+
+/// Every entry in the menu bar, depth first, with nested submenus expanded.
+pub fn flat_entries() -> Vec<Entry> {
+    fn walk(entries: &[Entry], out: &mut Vec<Entry>) {
+        for entry in entries {
+            out.push(*entry);
+            if let Entry::Submenu(_, nested) = entry {
+                walk(nested, out);
+            }
+        }
+    }
+    let mut out = Vec::new();
+    for section in menu_spec() {
+        walk(&section.entries, &mut out);
+    }
+    out
+}
+
+/// "Open Recent" labels for `files`: the file name, plus the shortest run of
+/// parent folders that tells apart files sharing a name, e.g.
+/// `notes.md (project-a)` and `notes.md (project-b)`.
+pub fn recent_labels(files: &[String]) -> Vec<String> {
+    let parts: Vec<Vec<&str>> = files
+        .iter()
+        .map(|f| f.split(['/', '\\']).filter(|p| !p.is_empty()).rev().collect())
+        .collect();
+    let name = |i: usize| parts[i].first().copied().unwrap_or(files[i].as_str());
+    // The `depth` parent folders closest to the file, outermost first.
+    let parents = |i: usize, depth: usize| -> String {
+        let dirs: Vec<&str> = parts[i].iter().skip(1).take(depth).rev().copied().collect();
+        dirs.join("/")
+    };
+    (0..files.len())
+        .map(|i| {
+            let rivals: Vec<usize> = (0..files.len()).filter(|&j| j != i && name(j) == name(i)).collect();
+            if rivals.is_empty() {
+                return name(i).to_string();
+            }
+            let max_depth = parts[i].len().saturating_sub(1);
+            let depth = (1..=max_depth)
+                .find(|&d| rivals.iter().all(|&j| parents(j, d) != parents(i, d)))
+                .unwrap_or(max_depth);
+            match parents(i, depth) {
+                dirs if dirs.is_empty() => name(i).to_string(),
+                dirs => format!("{} ({})", name(i), dirs),
+            }
+        })
+        .collect()
+}
+
+/// Accelerator for the recent entry at `index`: Cmd/Ctrl+Alt+1 through 9 for
+/// the first nine. Alt keeps them clear of the View menu's Cmd+1/2/3.
+pub fn recent_accelerator(index: usize) -> Option<String> {
+    (index < 9).then(|| format!("CmdOrCtrl+Alt+{}", index + 1))
+}
+
+/// Enable or disable the custom items `ids` in the app menu.
+pub fn set_items_enabled(app: &tauri::AppHandle, ids: &[&str], enabled: bool) {
+    let Some(menu) = app.menu() else { return };
+    for id in ids {
+        if let Some(tauri::menu::MenuItemKind::MenuItem(item)) = menu.get(*id) {
+            let _ = item.set_enabled(enabled);
+        }
+    }
+}
+
+/// Custom items in the order they appear in the menu bar.
+fn items_in_menu_order() -> impl Iterator<Item = &'static ItemSpec> {
+    flat_entries()
+        .into_iter()
+        .filter_map(|entry| match entry {
+            Entry::Item(id) => spec(id),
+            _ => None,
+        })
+}
+
+/// Items with an accelerator, in menu order.
+pub fn shortcuts() -> Vec<ShortcutInfo> {
+    items_in_menu_order()
+        .filter_map(|s| {
+            Some(ShortcutInfo {
+                action: s.action.unwrap_or(s.id).to_string(),
+                label: s.label.trim_end_matches('…').to_string(),
+                accelerator: s.accelerator?.to_string(),
+            })
+        })
+        .collect()
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// Menu accelerators for the frontend's shortcuts overlay.
+#[tauri::command]
+pub fn list_shortcuts() -> Vec<ShortcutInfo> {
+    shortcuts()
+}
+
+/// Called by the frontend as a document is opened or closed; grays out the
+/// items in `DOCUMENT_ITEMS` while there is nothing to save.
+#[tauri::command]
+pub fn set_document_open(app: tauri::AppHandle, open: bool) {
+    set_items_enabled(&app, DOCUMENT_ITEMS, open);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// `Shift+CmdOrCtrl+S` and `CmdOrCtrl+Shift+S` are the same chord.
+    fn normalize(accelerator: &str) -> Vec<String> {
+        let mut keys: Vec<String> = accelerator.split('+').map(|k| k.to_lowercase()).collect();
+        keys.sort();
+        keys
+    }
+
+    /// Accelerators the OS attaches to predefined items.
+    fn predefined_accelerator(p: Predefined) -> Option<&'static str> {
+        match p {
+            Predefined::Hide => Some("CmdOrCtrl+H"),
+            Predefined::HideOthers => Some("CmdOrCtrl+Alt+H"),
+            Predefined::Quit => Some("CmdOrCtrl+Q"),
+            Predefined::CloseWindow => Some("CmdOrCtrl+W"),
+            Predefined::Undo => Some("CmdOrCtrl+Z"),
+            Predefined::Redo => Some("CmdOrCtrl+Shift+Z"),
+            Predefined::Cut => Some("CmdOrCtrl+X"),
+            Predefined::Copy => Some("CmdOrCtrl+C"),
+            Predefined::Paste => Some("CmdOrCtrl+V"),
+            Predefined::SelectAll => Some("CmdOrCtrl+A"),
+            Predefined::Minimize => Some("CmdOrCtrl+M"),
+            Predefined::Fullscreen => Some("Ctrl+CmdOrCtrl+F"),
+            Predefined::ShowAll | Predefined::Zoom => None,
+        }
+    }
+
+    #[test]
+    fn spec_has_unique_ids_and_accelerators() {
+        let mut ids = HashSet::new();
+        let mut chords = HashSet::new();
+        for entry in flat_entries() {
+            let accelerator = match entry {
+                Entry::Item(id) => {
+                    assert!(ids.insert(id), "duplicate menu id {}", id);
+                    spec(id).unwrap_or_else(|| panic!("{} is not in ITEMS", id)).accelerator
+                }
+                Entry::Predefined(p) => predefined_accelerator(p),
+                Entry::OpenRecent => {
+                    assert!(ids.insert(OPEN_RECENT_ID));
+                    None
+                }
+                Entry::OpenRecentFolders => {
+                    assert!(ids.insert(OPEN_RECENT_FOLDERS_ID));
+                    None
+                }
+                Entry::NewFromTemplate => {
+                    assert!(ids.insert(NEW_FROM_TEMPLATE_ID));
+                    None
+                }
+                Entry::Separator | Entry::Submenu(..) => None,
+            };
+            if let Some(acc) = accelerator {
+                assert!(chords.insert(normalize(acc)), "accelerator {} is used twice", acc);
+            }
+        }
+        for item in ITEMS {
+            assert!(ids.contains(item.id), "{} is never placed in the menu", item.id);
+        }
+        for id in DOCUMENT_ITEMS {
+            assert!(spec(id).is_some(), "{} is not in ITEMS", id);
+        }
+    }
+
+    #[test]
+    fn every_accelerated_item_is_listed_once() {
+        let list = shortcuts();
+        for spec in ITEMS.iter().filter(|s| s.accelerator.is_some()) {
+            let action = spec.action.unwrap_or(spec.id);
+            let hits = list.iter().filter(|s| s.action == action).count();
+            assert_eq!(hits, 1, "{} listed {} times", spec.id, hits);
+        }
+        assert_eq!(list.len(), ITEMS.iter().filter(|s| s.accelerator.is_some()).count());
+    }
+
+    #[test]
+    fn action_script_passes_arguments_as_json() {
+        let action = MenuAction { action: "openFolder".into(), args: vec!["/a/it's \"x\"\n".into()] };
+        assert_eq!(
+            action_script(&action),
+            r#"if (!window.__menuActionEvents && window.__menuAction) window.__menuAction("openFolder", "/a/it's \"x\"\n")"#
+        );
+    }
+
+    #[test]
+    fn only_the_first_nine_recent_entries_get_accelerators() {
+        assert_eq!(recent_accelerator(0).as_deref(), Some("CmdOrCtrl+Alt+1"));
+        assert_eq!(recent_accelerator(8).as_deref(), Some("CmdOrCtrl+Alt+9"));
+        assert_eq!(recent_accelerator(9), None);
+        for i in 0..9 {
+            let accel = recent_accelerator(i).unwrap();
+            assert!(ITEMS.iter().all(|s| s.accelerator != Some(accel.as_str())), "{} is taken", accel);
+        }
+    }
+
+    #[test]
+    fn colliding_recent_names_get_the_shortest_distinct_parent() {
+        let files = [
+            "/work/project-a/notes.md",
+            "/work/project-b/notes.md",
+            "/home/a/docs/todo.md",
+            "/home/b/docs/todo.md",
+            "/home/readme.md",
+        ]
+        .map(String::from);
+        assert_eq!(
+            recent_labels(&files),
+            vec![
+                "notes.md (project-a)",
+                "notes.md (project-b)",
+                "todo.md (a/docs)",
+                "todo.md (b/docs)",
+                "readme.md",
+            ]
+        );
+    }
+}
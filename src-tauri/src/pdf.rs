@@ -0,0 +1,176 @@
+use serde::Deserialize;
+
+/// Largest margin accepted, in millimetres; more would leave no page.
+const MAX_MARGIN_MM: f64 = 50.0;
+
+/// Paper sizes offered for PDF export.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum PageSize {
+    #[default]
+    A4,
+    Letter,
+    Legal,
+}
+
+impl PageSize {
+    /// Width and height in millimetres, portrait.
+    #[cfg_attr(any(target_os = "macos", target_os = "windows"), allow(dead_code))]
+    pub fn dimensions_mm(self) -> (f64, f64) {
+        match self {
+            PageSize::A4 => (210.0, 297.0),
+            PageSize::Letter => (215.9, 279.4),
+            PageSize::Legal => (215.9, 355.6),
+        }
+    }
+}
+
+/// Page setup for `export_pdf`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PdfOptions {
+    pub page_size: PageSize,
+    /// Margin on every side, in millimetres.
+    pub margin_mm: f64,
+}
+
+impl Default for PdfOptions {
+    fn default() -> Self {
+        PdfOptions { page_size: PageSize::A4, margin_mm: 20.0 }
+    }
+}
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+fn validate(options: &PdfOptions) -> Result<(), String> {
+    if !(0.0..=MAX_MARGIN_MM).contains(&options.margin_mm) {
+        return Err(format!("Margin must be between 0 and {} mm", MAX_MARGIN_MM));
+    }
+    Ok(())
+}
+
+/// Print the window's page to `dest` through WebKitGTK's print operation,
+/// without a dialog. The print stylesheet leaves only the preview.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+fn print_to_pdf(
+    window: &tauri::WebviewWindow,
+    dest: &std::path::Path,
+    options: PdfOptions,
+    done: tauri::async_runtime::Sender<Result<(), String>>,
+) -> Result<(), String> {
+    use webkit2gtk::PrintOperationExt;
+
+    let uri = gtk::glib::filename_to_uri(dest, None).map_err(|e| format!("Failed to export PDF: {}", e))?;
+    window
+        .with_webview(move |webview| {
+            let settings = gtk::PrintSettings::new();
+            settings.set_printer("Print to File");
+            settings.set(gtk::PRINT_SETTINGS_OUTPUT_FILE_FORMAT, Some("pdf"));
+            settings.set(gtk::PRINT_SETTINGS_OUTPUT_URI, Some(&uri));
+
+            let (width, height) = options.page_size.dimensions_mm();
+            let setup = gtk::PageSetup::new();
+            setup.set_paper_size(&gtk::PaperSize::new_custom("updown", "UpDown", width, height, gtk::Unit::Mm));
+            setup.set_top_margin(options.margin_mm, gtk::Unit::Mm);
+            setup.set_bottom_margin(options.margin_mm, gtk::Unit::Mm);
+            setup.set_left_margin(options.margin_mm, gtk::Unit::Mm);
+            setup.set_right_margin(options.margin_mm, gtk::Unit::Mm);
+
+            let operation = webkit2gtk::PrintOperation::new(&webview.inner());
+            operation.set_print_settings(&settings);
+            operation.set_page_setup(&setup);
+            let failed = done.clone();
+            operation.connect_failed(move |_, e| {
+                let _ = failed.try_send(Err(format!("Failed to export PDF: {}", e)));
+            });
+            // `finished` also follows a failure; the first result wins.
+            operation.connect_finished(move |_| {
+                let _ = done.try_send(Ok(()));
+            });
+            operation.print();
+        })
+        .map_err(|e| format!("Failed to export PDF: {}", e))
+}
+
+/// Render the window's page to `dest` with WKWebView's `createPDF`. That
+/// API lays the content out as one continuous page, so `options` has no
+/// effect here.
+#[cfg(target_os = "macos")]
+fn print_to_pdf(
+    window: &tauri::WebviewWindow,
+    dest: &std::path::Path,
+    _options: PdfOptions,
+    done: tauri::async_runtime::Sender<Result<(), String>>,
+) -> Result<(), String> {
+    use objc2_foundation::{NSData, NSError};
+    use objc2_web_kit::WKWebView;
+
+    let dest = dest.to_path_buf();
+    window
+        .with_webview(move |webview| {
+            let handler = block2::RcBlock::new(move |data: *mut NSData, error: *mut NSError| {
+                // SAFETY: WebKit passes either valid objects or null.
+                let result = match unsafe { (data.as_ref(), error.as_ref()) } {
+                    (Some(data), _) => crate::files::write_atomic(&dest, &data.to_vec())
+                        .map_err(|e| crate::files::describe_write_error(&dest.to_string_lossy(), &e)),
+                    (None, Some(error)) => Err(format!("Failed to export PDF: {}", error.localizedDescription())),
+                    (None, None) => Err("Failed to export PDF".to_string()),
+                };
+                let _ = done.try_send(result);
+            });
+            // SAFETY: `inner` is the window's WKWebView, and `with_webview`
+            // runs this on the main thread.
+            unsafe {
+                let view: &WKWebView = &*webview.inner().cast();
+                view.createPDFWithConfiguration_completionHandler(None, &handler);
+            }
+        })
+        .map_err(|e| format!("Failed to export PDF: {}", e))
+}
+
+/// WebView2 has `PrintToPdf`, but it isn't wired up yet.
+#[cfg(target_os = "windows")]
+fn print_to_pdf(
+    _window: &tauri::WebviewWindow,
+    _dest: &std::path::Path,
+    _options: PdfOptions,
+    _done: tauri::async_runtime::Sender<Result<(), String>>,
+) -> Result<(), String> {
+    Err("PDF export is not supported on Windows yet".to_string())
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// Save the calling window's rendered preview as a PDF at `dest`. The
+/// frontend switches the page to its print layout while this runs.
+#[tauri::command]
+pub async fn export_pdf(window: tauri::WebviewWindow, dest: String, options: Option<PdfOptions>) -> Result<(), String> {
+    let options = options.unwrap_or_default();
+    validate(&options)?;
+    if dest.trim().is_empty() {
+        return Err("No destination chosen".to_string());
+    }
+    let (done, mut result) = tauri::async_runtime::channel(2);
+    print_to_pdf(&window, std::path::Path::new(&dest), options, done)?;
+    result.recv().await.unwrap_or_else(|| Err("PDF export was cancelled".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_default_to_a4_and_reject_oversized_margins() {
+        let options: PdfOptions = serde_json::from_str(r#"{"pageSize": "Letter"}"#).unwrap();
+        assert_eq!(options.page_size.dimensions_mm(), (215.9, 279.4));
+        assert_eq!(options.margin_mm, 20.0);
+        assert!(validate(&options).is_ok());
+        assert!(validate(&PdfOptions { margin_mm: 60.0, ..PdfOptions::default() }).is_err());
+        assert!(validate(&PdfOptions { margin_mm: -1.0, ..PdfOptions::default() }).is_err());
+    }
+}
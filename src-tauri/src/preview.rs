@@ -0,0 +1,105 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+use crate::lock::LockExt;
+use crate::watch::{FileWatcher, DEBOUNCE};
+
+/// Per-folder stylesheet applied on top of the built-in preview theme.
+pub const PREVIEW_CSS_NAME: &str = ".updown.css";
+
+/// Payload of `get_preview_css` and the `preview-css-changed` event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PreviewCss {
+    pub path: String,
+    pub css: String,
+}
+
+/// The preview CSS watcher of each window, keyed by window label.
+#[derive(Default)]
+pub struct PreviewCssWatch(pub Mutex<HashMap<String, FileWatcher>>);
+
+fn read_css(path: &Path) -> Option<PreviewCss> {
+    let css = std::fs::read_to_string(path).ok()?;
+    Some(PreviewCss { path: path.to_string_lossy().to_string(), css })
+}
+
+/// Watch `path` and pass its new contents to `changed` after each edit,
+/// using the same OS file events as the document watcher.
+fn watch_css<F>(path: PathBuf, debounce: Duration, changed: F) -> Result<FileWatcher, String>
+where
+    F: Fn(PreviewCss) + Send + 'static,
+{
+    FileWatcher::spawn(path, debounce, move |p| {
+        if let Some(payload) = read_css(p) {
+            changed(payload);
+        }
+    })
+}
+
+/// Stop the preview CSS watch of window `label`, if any. Called when the
+/// window closes.
+pub fn stop_watching(app: &tauri::AppHandle, label: &str) {
+    let old = app.state::<PreviewCssWatch>().0.lock_or_recover().remove(label);
+    drop(old);
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// The folder's `.updown.css`, if it has one.
+#[tauri::command]
+pub fn get_preview_css(folder: String) -> Option<PreviewCss> {
+    read_css(&Path::new(&folder).join(PREVIEW_CSS_NAME))
+}
+
+/// Emit `preview-css-changed` with the new contents to the calling window
+/// whenever `path` is edited. Replaces the window's previous watch, so
+/// switching documents or folders stops watching the old stylesheet.
+#[tauri::command]
+pub fn watch_preview_css(window: tauri::WebviewWindow, path: String) -> Result<(), String> {
+    let label = window.label().to_string();
+    let app = window.app_handle().clone();
+    let handle = app.clone();
+    let target = label.clone();
+    let watcher = watch_css(PathBuf::from(path), DEBOUNCE, move |payload| {
+        let _ = handle.emit_to(target.as_str(), "preview-css-changed", payload);
+    })?;
+    // Swap under the lock, but stop the old watcher after releasing it.
+    let old = app.state::<PreviewCssWatch>().0.lock_or_recover().insert(label, watcher);
+    drop(old);
+    Ok(())
+}
+
+/// Stop watching the preview CSS in the calling window.
+#[tauri::command]
+pub fn unwatch_preview_css(window: tauri::WebviewWindow) {
+    stop_watching(window.app_handle(), window.label());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edited_stylesheet_is_sent_once_settled() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(PREVIEW_CSS_NAME);
+        std::fs::write(&path, "body {}").unwrap();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let watcher = watch_css(path.clone(), Duration::from_millis(50), move |css| {
+            let _ = tx.send(css);
+        })
+        .unwrap();
+        std::fs::write(&path, "h1 { color: red; }").unwrap();
+        let sent = rx.recv_timeout(Duration::from_secs(2)).unwrap();
+        assert_eq!(sent, PreviewCss { path: path.to_string_lossy().to_string(), css: "h1 { color: red; }".into() });
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(rx.recv_timeout(Duration::from_millis(200)).is_err());
+        drop(watcher);
+    }
+}
@@ -0,0 +1,447 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+#[cfg(target_os = "macos")]
+use tauri::Emitter;
+use tauri::Manager;
+
+/// Bundle name of the Quick Look host app, both in our resources and once
+/// installed in `~/Applications`.
+const QUICKLOOK_APP: &str = "UpDownQuickLook.app";
+
+/// The preview extension inside the host app's `Contents/PlugIns`.
+const PREVIEW_APPEX: &str = "UpDownPreview.appex";
+
+/// Bundle id `pluginkit` enables.
+const PREVIEW_BUNDLE_ID: &str = "com.noam.updown.quicklook.preview";
+
+/// Payload of `quicklook_status` and `quicklook-status-changed`.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QuickLookStatus {
+    pub installed: bool,
+    pub path: Option<String>,
+    /// `CFBundleShortVersionString` of the installed app, if readable.
+    pub installed_version: Option<String>,
+    /// Version of the copy we would install.
+    pub bundled_version: String,
+}
+
+// ── Status ────────────────────────────────────────────────────────────────────
+
+#[cfg(target_os = "macos")]
+fn user_apps_dir() -> Result<PathBuf, String> {
+    let home = std::env::var("HOME").map_err(|_| "Cannot determine HOME directory".to_string())?;
+    Ok(PathBuf::from(home).join("Applications"))
+}
+
+/// `CFBundleShortVersionString` from an app bundle's `Info.plist`.
+#[cfg(any(target_os = "macos", test))]
+fn bundle_version(app_bundle: &Path) -> Option<String> {
+    let plist = read_plist(&app_bundle.join("Contents").join("Info.plist")).ok()?;
+    plist_string(&plist, "CFBundleShortVersionString")
+}
+
+/// Install state of the Quick Look app in `apps_dir`, compared against the
+/// bundled copy at version `bundled_version`.
+#[cfg(target_os = "macos")]
+pub fn status_in(apps_dir: &Path, bundled_version: String) -> QuickLookStatus {
+    let dest = apps_dir.join(QUICKLOOK_APP);
+    let installed = dest.is_dir();
+    QuickLookStatus {
+        installed,
+        path: installed.then(|| dest.to_string_lossy().to_string()),
+        installed_version: if installed { bundle_version(&dest) } else { None },
+        bundled_version,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn status(app: &tauri::AppHandle) -> Result<QuickLookStatus, String> {
+    let bundled = bundled_app(app)?;
+    let bundled_version = bundle_version(&bundled)
+        .ok_or_else(|| format!("{} has no CFBundleShortVersionString", bundled.display()))?;
+    Ok(status_in(&user_apps_dir()?, bundled_version))
+}
+
+/// Broadcast the current install status after a repair or uninstall so
+/// open settings views update.
+#[cfg(target_os = "macos")]
+fn emit_quicklook_status(app: &tauri::AppHandle) {
+    if let Ok(status) = status(app) {
+        let _ = app.emit("quicklook-status-changed", status);
+    }
+}
+
+// ── Bundle verification ───────────────────────────────────────────────────────
+
+/// The `<string>` value following `<key>key</key>` in an XML plist.
+fn plist_string(xml: &str, key: &str) -> Option<String> {
+    let after_key = &xml[xml.find(&format!("<key>{}</key>", key))? + key.len() + 11..];
+    let value = after_key.trim_start().strip_prefix("<string>")?;
+    Some(value[..value.find("</string>")?].trim().to_string())
+}
+
+fn read_plist(path: &Path) -> Result<String, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    if bytes.starts_with(b"bplist") {
+        // Binary plists are converted with the system tool.
+        let out = std::process::Command::new("plutil")
+            .args(["-convert", "xml1", "-o", "-"])
+            .arg(path)
+            .output()
+            .map_err(|e| format!("Failed to read binary plist {}: {}", path.display(), e))?;
+        return Ok(String::from_utf8_lossy(&out.stdout).to_string());
+    }
+    String::from_utf8(bytes).map_err(|_| format!("{} is not a valid plist", path.display()))
+}
+
+/// Check that `app_bundle` contains a complete preview extension: the
+/// `.appex`, its `Info.plist` with our bundle id, and its executable.
+/// The error names the first missing piece.
+pub fn verify_bundle(app_bundle: &Path) -> Result<(), String> {
+    if !app_bundle.is_dir() {
+        return Err(format!("Quick Look app not found at {}", app_bundle.display()));
+    }
+    let appex = app_bundle.join("Contents").join("PlugIns").join(PREVIEW_APPEX);
+    if !appex.is_dir() {
+        return Err(format!("Quick Look bundle is missing {}", appex.display()));
+    }
+    let plist_path = appex.join("Contents").join("Info.plist");
+    if !plist_path.is_file() {
+        return Err(format!("Quick Look bundle is missing {}", plist_path.display()));
+    }
+    let plist = read_plist(&plist_path)?;
+    match plist_string(&plist, "CFBundleIdentifier") {
+        Some(id) if id == PREVIEW_BUNDLE_ID => {}
+        Some(id) => {
+            return Err(format!(
+                "{} has bundle id {}, expected {}",
+                plist_path.display(),
+                id,
+                PREVIEW_BUNDLE_ID
+            ))
+        }
+        None => return Err(format!("{} has no CFBundleIdentifier", plist_path.display())),
+    }
+    let exe_name = plist_string(&plist, "CFBundleExecutable")
+        .ok_or_else(|| format!("{} has no CFBundleExecutable", plist_path.display()))?;
+    let exe = appex.join("Contents").join("MacOS").join(&exe_name);
+    if !exe.is_file() {
+        return Err(format!("Quick Look bundle is missing its executable {}", exe.display()));
+    }
+    Ok(())
+}
+
+/// The Quick Look app shipped in our resources.
+fn bundled_app(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let resource_dir = app
+        .path()
+        .resource_dir()
+        .map_err(|e| format!("Failed to get resource dir: {}", e))?;
+
+    let src = resource_dir.join("resources").join(QUICKLOOK_APP);
+    if src.exists() {
+        return Ok(src);
+    }
+    let alt_src = resource_dir.join(QUICKLOOK_APP);
+    if alt_src.exists() {
+        return Ok(alt_src);
+    }
+    Err(format!(
+        "Quick Look app not found in app bundle. Checked:\n  {}\n  {}",
+        src.display(),
+        alt_src.display()
+    ))
+}
+
+// ── Install ───────────────────────────────────────────────────────────────────
+
+/// Copy the bundled Quick Look app into `~/Applications`, register it, and
+/// broadcast the resulting status.
+fn install(app: &tauri::AppHandle) -> Result<String, String> {
+    let src = bundled_app(app)?;
+    verify_bundle(&src)?;
+
+    #[cfg(target_os = "macos")]
+    {
+        install_and_report(&src, &user_apps_dir()?, |status| {
+            let _ = app.emit("quicklook-status-changed", status);
+        })
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        Err("Quick Look is only available on macOS".to_string())
+    }
+}
+
+/// Install `src` into `apps_dir`, then hand `emit` the install status,
+/// whether or not the copy worked.
+#[cfg(target_os = "macos")]
+fn install_and_report(src: &Path, apps_dir: &Path, emit: impl FnOnce(QuickLookStatus)) -> Result<String, String> {
+    let result = install_ql_into(src, apps_dir);
+    emit(status_in(apps_dir, bundle_version(src).unwrap_or_default()));
+    result
+}
+
+#[cfg(target_os = "macos")]
+fn install_ql_into(src: &Path, apps_dir: &Path) -> Result<String, String> {
+    use std::fs;
+    use std::process::Command;
+
+    if !apps_dir.exists() {
+        fs::create_dir_all(apps_dir)
+            .map_err(|e| format!("Failed to create {}: {}", apps_dir.display(), e))?;
+    }
+
+    let dest = apps_dir.join(QUICKLOOK_APP);
+
+    if dest.exists() {
+        fs::remove_dir_all(&dest)
+            .map_err(|e| format!("Failed to remove old Quick Look app: {}", e))?;
+    }
+
+    copy_dir_recursive(src, &dest)
+        .map_err(|e| format!("Failed to copy Quick Look app: {}", e))?;
+
+    let appex = dest.join("Contents").join("PlugIns").join(PREVIEW_APPEX);
+    if appex.exists() {
+        register_extension(&appex);
+        // Launching the host app once makes macOS pick up the extension.
+        let _ = Command::new("/usr/bin/open").arg(&dest).output();
+    }
+    Ok(format!("Quick Look extension installed to {}", dest.display()))
+}
+
+/// Register the extension with `pluginkit` and enable it.
+#[cfg(target_os = "macos")]
+fn register_extension(appex: &Path) {
+    use std::process::Command;
+
+    let path = appex.to_string_lossy();
+    let _ = Command::new("pluginkit").args(["-a", path.as_ref()]).output();
+    let _ = Command::new("pluginkit")
+        .args(["-e", "use", "-i", PREVIEW_BUNDLE_ID])
+        .output();
+}
+
+/// Whether `pluginkit` lists the extension as enabled (`+` prefix).
+#[cfg(target_os = "macos")]
+fn extension_enabled() -> bool {
+    std::process::Command::new("pluginkit")
+        .args(["-m", "-i", PREVIEW_BUNDLE_ID])
+        .output()
+        .map(|out| String::from_utf8_lossy(&out.stdout).lines().any(|l| l.trim_start().starts_with('+')))
+        .unwrap_or(false)
+}
+
+/// Re-run the `pluginkit` enable sequence for an app already installed in
+/// `apps_dir`, without recopying it.
+#[cfg(target_os = "macos")]
+fn repair_in(apps_dir: &Path) -> Result<String, String> {
+    let dest = apps_dir.join(QUICKLOOK_APP);
+    if !dest.is_dir() {
+        return Err("The Quick Look plugin is not installed; install it first".to_string());
+    }
+    let appex = dest.join("Contents").join("PlugIns").join(PREVIEW_APPEX);
+    if !appex.is_dir() {
+        return Err(format!("The installed Quick Look app is missing {}; reinstall it", PREVIEW_APPEX));
+    }
+    register_extension(&appex);
+    Ok(if extension_enabled() {
+        "Quick Look extension is enabled".to_string()
+    } else {
+        "Quick Look extension is still disabled; enable it in System Settings › Extensions".to_string()
+    })
+}
+
+/// Disable the extension and remove the Quick Look app from `apps_dir`.
+/// Not being installed is reported, not treated as an error.
+#[cfg(target_os = "macos")]
+fn uninstall_from(apps_dir: &Path) -> Result<String, String> {
+    let dest = apps_dir.join(QUICKLOOK_APP);
+    if !dest.exists() {
+        return Ok("The Quick Look plugin is not installed".to_string());
+    }
+    let _ = std::process::Command::new("pluginkit")
+        .args(["-e", "ignore", "-i", PREVIEW_BUNDLE_ID])
+        .output();
+    std::fs::remove_dir_all(&dest).map_err(|e| format!("Failed to remove {}: {}", dest.display(), e))?;
+    Ok(format!("Quick Look extension removed from {}", dest.display()))
+}
+
+#[cfg(target_os = "macos")]
+fn copy_dir_recursive(src: &Path, dst: &Path) -> std::io::Result<()> {
+    use std::fs;
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let src_path = entry.path();
+        let dst_path = dst.join(entry.file_name());
+        if src_path.is_dir() {
+            copy_dir_recursive(&src_path, &dst_path)?;
+        } else {
+            fs::copy(&src_path, &dst_path)?;
+        }
+    }
+    Ok(())
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// Install the Quick Look generator for markdown preview in Finder.
+#[tauri::command]
+pub fn install_quicklook_plugin(app: tauri::AppHandle) -> Result<String, String> {
+    install(&app)
+}
+
+/// Check the bundled Quick Look app is complete before installing it.
+#[tauri::command]
+pub fn verify_quicklook_bundle(app: tauri::AppHandle) -> Result<(), String> {
+    verify_bundle(&bundled_app(&app)?)
+}
+
+/// Re-enable an installed Quick Look extension that macOS disabled (often
+/// after an OS update). Faster than reinstalling.
+#[tauri::command]
+pub fn repair_quicklook(app: tauri::AppHandle) -> Result<String, String> {
+    #[cfg(target_os = "macos")]
+    {
+        let result = repair_in(&user_apps_dir()?);
+        emit_quicklook_status(&app);
+        result
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = app;
+        Err("Quick Look is only available on macOS".to_string())
+    }
+}
+
+/// Whether the Quick Look plugin is installed, and whether it is older than
+/// the copy bundled with this version of the app.
+#[tauri::command]
+pub fn quicklook_status(app: tauri::AppHandle) -> Result<QuickLookStatus, String> {
+    #[cfg(target_os = "macos")]
+    {
+        status(&app)
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = app;
+        Err("Quick Look is only available on macOS".to_string())
+    }
+}
+
+/// Disable and remove the installed Quick Look extension.
+#[tauri::command]
+pub fn uninstall_quicklook_plugin(app: tauri::AppHandle) -> Result<String, String> {
+    #[cfg(target_os = "macos")]
+    {
+        let result = uninstall_from(&user_apps_dir()?);
+        emit_quicklook_status(&app);
+        result
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = app;
+        Err("Quick Look is only available on macOS".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLIST: &str = "<plist version=\"1.0\"><dict>\n\t<key>CFBundleExecutable</key>\n\t<string>UpDownPreview</string>\n\t<key>CFBundleIdentifier</key>\n\t<string>com.noam.updown.quicklook.preview</string>\n</dict></plist>";
+
+    #[test]
+    fn incomplete_bundle_names_the_missing_piece() {
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join(QUICKLOOK_APP);
+        let contents = bundle.join("Contents/PlugIns").join(PREVIEW_APPEX).join("Contents");
+        std::fs::create_dir_all(&bundle).unwrap();
+        assert!(verify_bundle(&bundle).unwrap_err().contains(PREVIEW_APPEX));
+
+        std::fs::create_dir_all(contents.join("MacOS")).unwrap();
+        assert!(verify_bundle(&bundle).unwrap_err().contains("Info.plist"));
+
+        std::fs::write(contents.join("Info.plist"), PLIST.replace("preview<", "other<")).unwrap();
+        assert!(verify_bundle(&bundle).unwrap_err().contains("expected com.noam.updown.quicklook.preview"));
+
+        std::fs::write(contents.join("Info.plist"), PLIST).unwrap();
+        assert!(verify_bundle(&bundle).unwrap_err().contains("executable"));
+
+        std::fs::write(contents.join("MacOS/UpDownPreview"), "").unwrap();
+        assert_eq!(verify_bundle(&bundle), Ok(()));
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn installed_bundle_reports_installed() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("bundle");
+        std::fs::create_dir_all(src.join("Contents")).unwrap();
+        std::fs::write(src.join("Contents/Info.plist"), "<plist/>").unwrap();
+        let apps = dir.path().join("Applications");
+        assert!(!status_in(&apps, "2.1.0".into()).installed);
+
+        copy_dir_recursive(&src, &apps.join(QUICKLOOK_APP)).unwrap();
+        let status = status_in(&apps, "2.1.0".into());
+        assert!(status.installed);
+        assert_eq!(status.installed_version, None);
+        assert!(status.path.unwrap().ends_with(QUICKLOOK_APP));
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn install_emits_a_status_with_installed_true() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("bundle");
+        std::fs::create_dir_all(src.join("Contents")).unwrap();
+        let plist = "<plist><dict><key>CFBundleShortVersionString</key><string>2.1.0</string></dict></plist>";
+        std::fs::write(src.join("Contents/Info.plist"), plist).unwrap();
+        let apps = dir.path().join("Applications");
+
+        let mut emitted = None;
+        let message = install_and_report(&src, &apps, |status| emitted = Some(status)).unwrap();
+        assert!(message.contains(QUICKLOOK_APP));
+        let status = emitted.expect("install reports the new status");
+        assert!(status.installed);
+        assert_eq!(status.installed_version.as_deref(), Some("2.1.0"));
+        assert_eq!(status.bundled_version, "2.1.0");
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn repair_requires_an_installed_bundle() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(repair_in(dir.path()).unwrap_err().contains("not installed"));
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn uninstall_removes_the_app_and_tolerates_a_missing_one() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(uninstall_from(dir.path()).unwrap().contains("not installed"));
+
+        std::fs::create_dir_all(dir.path().join(QUICKLOOK_APP).join("Contents")).unwrap();
+        assert!(uninstall_from(dir.path()).unwrap().contains("removed"));
+        assert!(!status_in(dir.path(), String::new()).installed);
+    }
+
+    #[test]
+    fn reads_the_bundle_short_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let contents = dir.path().join(QUICKLOOK_APP).join("Contents");
+        std::fs::create_dir_all(&contents).unwrap();
+        assert_eq!(bundle_version(&dir.path().join(QUICKLOOK_APP)), None);
+
+        let plist = PLIST.replace("</dict>", "\t<key>CFBundleShortVersionString</key>\n\t<string>2.1.0</string>\n</dict>");
+        std::fs::write(contents.join("Info.plist"), plist).unwrap();
+        assert_eq!(bundle_version(&dir.path().join(QUICKLOOK_APP)).as_deref(), Some("2.1.0"));
+    }
+}
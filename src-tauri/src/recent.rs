@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+
+/// One "Open Recent" entry as stored in `recent-files.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentEntry {
+    pub path: String,
+    /// Unix time (seconds) the file was last opened; 0 if unknown.
+    pub last_opened: i64,
+    pub open_count: u32,
+}
+
+impl RecentEntry {
+    pub fn new(path: String, now: i64) -> Self {
+        RecentEntry { path, last_opened: now, open_count: 1 }
+    }
+}
+
+/// How the "Open Recent" menu is ordered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecentSort {
+    /// Most recently opened first.
+    #[default]
+    Recency,
+    /// Most often opened first; ties go to the more recent file.
+    Frequency,
+}
+
+/// An entry in either on-disk format: older versions stored bare paths.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredEntry {
+    Entry(RecentEntry),
+    Path(String),
+}
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// Parse `recent-files.json`, upgrading the old list of plain paths (whose
+/// open times are unknown) to entries. Malformed content yields no entries.
+pub fn parse(json: &str) -> Vec<RecentEntry> {
+    serde_json::from_str::<Vec<StoredEntry>>(json)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|stored| match stored {
+            StoredEntry::Entry(entry) => entry,
+            StoredEntry::Path(path) => RecentEntry::new(path, 0),
+        })
+        .collect()
+}
+
+/// Record an open of `path` at `now`: move it to the front, bumping its
+/// count, or add it there.
+pub fn touch(entries: &mut Vec<RecentEntry>, path: String, now: i64) {
+    let entry = match entries.iter().position(|e| e.path == path) {
+        Some(i) => {
+            let mut entry = entries.remove(i);
+            entry.last_opened = now;
+            entry.open_count = entry.open_count.saturating_add(1);
+            entry
+        }
+        None => RecentEntry::new(path, now),
+    };
+    entries.insert(0, entry);
+}
+
+/// Pin `path`: it leaves the recent entries (so it isn't listed twice or
+/// counted against the recent limit) and joins the end of `pinned`.
+pub fn pin(entries: &mut Vec<RecentEntry>, pinned: &mut Vec<String>, path: String) {
+    entries.retain(|e| e.path != path);
+    if !pinned.contains(&path) {
+        pinned.push(path);
+    }
+}
+
+/// Unpin `path`, returning it to the top of the recent entries.
+pub fn unpin(entries: &mut Vec<RecentEntry>, pinned: &mut Vec<String>, path: String, now: i64) {
+    let before = pinned.len();
+    pinned.retain(|p| *p != path);
+    if pinned.len() != before {
+        touch(entries, path, now);
+    }
+}
+
+/// Entry paths in menu order. `entries` is kept most-recent-first, so the
+/// stable sort by count leaves ties in recency order.
+pub fn ordered_paths(entries: &[RecentEntry], sort: RecentSort) -> Vec<String> {
+    let mut ordered: Vec<&RecentEntry> = entries.iter().collect();
+    if sort == RecentSort::Frequency {
+        ordered.sort_by_key(|e| std::cmp::Reverse(e.open_count));
+    }
+    ordered.into_iter().map(|e| e.path.clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_plain_paths_and_orders_by_mode() {
+        let mut entries = parse(r#"["/a.md", "/b.md"]"#);
+        assert_eq!(entries, vec![RecentEntry::new("/a.md".into(), 0), RecentEntry::new("/b.md".into(), 0)]);
+
+        touch(&mut entries, "/b.md".into(), 100);
+        touch(&mut entries, "/c.md".into(), 200);
+        touch(&mut entries, "/b.md".into(), 300);
+        assert_eq!(entries[0], RecentEntry { path: "/b.md".into(), last_opened: 300, open_count: 3 });
+        assert_eq!(ordered_paths(&entries, RecentSort::Recency), vec!["/b.md", "/c.md", "/a.md"]);
+
+        touch(&mut entries, "/a.md".into(), 400);
+        assert_eq!(ordered_paths(&entries, RecentSort::Frequency), vec!["/b.md", "/a.md", "/c.md"]);
+
+        let json = serde_json::to_string(&entries).unwrap();
+        assert_eq!(parse(&json), entries);
+        assert!(parse("not json").is_empty());
+    }
+
+    #[test]
+    fn pinned_files_leave_and_rejoin_the_recent_list() {
+        let mut entries = vec![RecentEntry::new("/a.md".into(), 1), RecentEntry::new("/b.md".into(), 2)];
+        let mut pinned = Vec::new();
+        pin(&mut entries, &mut pinned, "/b.md".into());
+        pin(&mut entries, &mut pinned, "/b.md".into());
+        assert_eq!(pinned, vec!["/b.md"]);
+        assert_eq!(ordered_paths(&entries, RecentSort::Recency), vec!["/a.md"]);
+
+        unpin(&mut entries, &mut pinned, "/c.md".into(), 5);
+        assert_eq!(entries.len(), 1);
+        unpin(&mut entries, &mut pinned, "/b.md".into(), 5);
+        assert!(pinned.is_empty());
+        assert_eq!(ordered_paths(&entries, RecentSort::Recency), vec!["/b.md", "/a.md"]);
+    }
+}
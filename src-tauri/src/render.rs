@@ -0,0 +1,544 @@
+use pulldown_cmark::{html, CodeBlockKind, CowStr, Event, LinkType, Options, Parser, Tag, TagEnd, TextMergeWithOffset};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::sync::OnceLock;
+
+/// Frontend stylesheet for the rendered preview, inlined into HTML exports.
+const PREVIEW_STYLESHEET: &str = "css/markdown.css";
+
+/// Options for HTML meant to leave the app.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ExportOptions {
+    /// Prefix for relative `src`/`href` targets, e.g. a site's asset URL.
+    pub base_url: Option<String>,
+}
+
+/// Which GitHub Flavored Markdown extensions to render; all on by default.
+/// Fenced code always gets a `language-*` class for the highlighter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RenderOptions {
+    pub tables: bool,
+    pub strikethrough: bool,
+    pub task_lists: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions { tables: true, strikethrough: true, task_lists: true }
+    }
+}
+
+/// Link and image schemes allowed in rendered HTML. Targets with any other
+/// scheme (`javascript:`, `data:`, …) are dropped, keeping their text.
+const SAFE_SCHEMES: &[&str] = &["http", "https", "mailto", "tel", "file"];
+
+/// A parser event and the byte range of `input` it came from.
+type Spanned<'a> = (Event<'a>, Range<usize>);
+
+/// A heading in the document outline. `line` is 1-based; `id` is the
+/// anchor the preview gives the heading, empty if it has none.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OutlineItem {
+    pub level: u8,
+    pub text: String,
+    pub line: usize,
+    pub id: String,
+}
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+pub fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// Heading anchor id, matching the preview's slug rule in `render.js`.
+pub fn slugify(text: &str) -> String {
+    let kept: String = text
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-' || c.is_whitespace())
+        .collect();
+    let mut slug = String::with_capacity(kept.len());
+    let mut in_space = false;
+    for c in kept.trim().chars() {
+        if c.is_whitespace() || c == '-' {
+            if !in_space {
+                slug.push('-');
+            }
+            in_space = true;
+        } else {
+            slug.push(c);
+            in_space = false;
+        }
+    }
+    slug
+}
+
+/// Whether `url` may be a link or image target: relative, or with one of
+/// `SAFE_SCHEMES`. Browsers ignore tabs and newlines inside a scheme, so
+/// they are ignored here too; a lone drive letter (`C:\`) counts as a path.
+fn is_safe_url(url: &str) -> bool {
+    let url: String = url.chars().filter(|c| !c.is_ascii_whitespace() && !c.is_ascii_control()).collect();
+    match url.find([':', '/', '?', '#']) {
+        Some(i) if url[i..].starts_with(':') => {
+            let scheme = &url[..i];
+            (scheme.len() == 1 && scheme.chars().all(|c| c.is_ascii_alphabetic()))
+                || SAFE_SCHEMES.iter().any(|s| s.eq_ignore_ascii_case(scheme))
+        }
+        _ => true,
+    }
+}
+
+/// Is `url` relative to the document (not a scheme URL, protocol-relative,
+/// root-absolute, or an in-page anchor)?
+fn is_relative_url(url: &str) -> bool {
+    let scheme_len = url
+        .find(':')
+        .filter(|&i| i > 0 && url[..i].chars().all(|c| c.is_ascii_alphanumeric() || "+.-".contains(c)));
+    !(url.is_empty() || url.starts_with('#') || url.starts_with('/') || scheme_len.is_some())
+}
+
+/// `url` prefixed with `base_url` if it is relative.
+fn rebase<'a>(url: CowStr<'a>, base_url: Option<&str>) -> CowStr<'a> {
+    match base_url {
+        Some(base) if is_relative_url(&url) => {
+            format!("{}/{}", base.trim_end_matches('/'), url.trim_start_matches("./")).into()
+        }
+        _ => url,
+    }
+}
+
+/// `url` without trailing punctuation that more likely ends the sentence,
+/// keeping a `)` that closes a `(` inside the URL.
+fn trim_url(url: &str) -> &str {
+    let mut url = url;
+    while let Some(last) = url.chars().last().filter(|c| ".,;:!?)\"'".contains(*c)) {
+        if last == ')' && url.matches('(').count() >= url.matches(')').count() {
+            break;
+        }
+        url = &url[..url.len() - 1];
+    }
+    url
+}
+
+/// Push `text` onto `out`, turning bare `http(s)://` and `www.` URLs into
+/// links as the preview's linkify does.
+fn push_linkified<'a>(text: CowStr<'a>, range: Range<usize>, out: &mut Vec<Spanned<'a>>) {
+    static URL: OnceLock<regex::Regex> = OnceLock::new();
+    let re = URL.get_or_init(|| regex::Regex::new(r"(?:https?://|www\.)[^\s<]+").unwrap());
+    let mut rest = 0;
+    for m in re.find_iter(&text) {
+        let url = trim_url(m.as_str());
+        let prefix = if url.starts_with("www.") { 4 } else { url.find("//").map_or(0, |i| i + 2) };
+        if url.len() <= prefix || text[..m.start()].chars().last().is_some_and(char::is_alphanumeric) {
+            continue;
+        }
+        if m.start() > rest {
+            out.push((Event::Text(text[rest..m.start()].to_string().into()), range.clone()));
+        }
+        let href = if url.starts_with("www.") { format!("http://{url}") } else { url.to_string() };
+        let link = Tag::Link {
+            link_type: LinkType::Autolink,
+            dest_url: href.clone().into(),
+            title: href.into(),
+            id: CowStr::Borrowed(""),
+        };
+        out.push((Event::Start(link), range.clone()));
+        out.push((Event::Text(url.to_string().into()), range.clone()));
+        out.push((Event::End(TagEnd::Link), range.clone()));
+        rest = m.start() + url.len();
+    }
+    match rest {
+        0 => out.push((Event::Text(text), range)),
+        n if n < text.len() => out.push((Event::Text(text[n..].to_string().into()), range)),
+        _ => {}
+    }
+}
+
+/// Text content of `events`, without markup.
+fn plain_text(events: &[Spanned]) -> String {
+    events
+        .iter()
+        .filter_map(|(e, _)| match e {
+            Event::Text(t) | Event::Code(t) => Some(t.as_ref()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Index of the `End` matching the `Start` at `start`.
+fn matching_end(events: &[Spanned], start: usize) -> usize {
+    let mut depth = 0;
+    for (i, (event, _)) in events.iter().enumerate().skip(start) {
+        match event {
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth -= 1,
+            _ => {}
+        }
+        if depth == 0 {
+            return i;
+        }
+    }
+    events.len() - 1
+}
+
+/// Give every heading an anchor id slugged from its markdown source, as
+/// `render.js` does.
+fn add_heading_ids(input: &str, events: &mut [Spanned]) {
+    for i in 0..events.len() {
+        if !matches!(events[i].0, Event::Start(Tag::Heading { id: None, .. })) {
+            continue;
+        }
+        let end = matching_end(events, i);
+        let source = match (events.get(i + 1), events.get(end - 1)) {
+            (Some((_, first)), Some((_, last))) if end > i + 1 => &input[first.start..last.end],
+            _ => "",
+        };
+        let slug = slugify(source);
+        if let (Event::Start(Tag::Heading { id, .. }), false) = (&mut events[i].0, slug.is_empty()) {
+            *id = Some(slug.into());
+        }
+    }
+}
+
+/// Parse `input` into the events we render: raw HTML shown as text, unsafe
+/// link and image targets dropped, bare URLs linked, links titled with
+/// their target, mermaid fences left for mermaid.js and headings given
+/// anchor ids. Relative targets get `base_url` in front when it is set.
+fn events<'a>(input: &'a str, opts: &RenderOptions, base_url: Option<&str>) -> Vec<Spanned<'a>> {
+    let mut flags = Options::empty();
+    flags.set(Options::ENABLE_TABLES, opts.tables);
+    flags.set(Options::ENABLE_STRIKETHROUGH, opts.strikethrough);
+    flags.set(Options::ENABLE_TASKLISTS, opts.task_lists);
+    let parser = TextMergeWithOffset::new(Parser::new_ext(input, flags).into_offset_iter());
+
+    let mut out: Vec<Spanned> = Vec::new();
+    let (mut in_code, mut in_link, mut in_image) = (false, false, false);
+    let (mut dropped_link, mut dropped_image) = (false, false);
+    let mut mermaid: Option<(String, Range<usize>)> = None;
+    for (event, range) in parser {
+        if let Some((code, _)) = &mut mermaid {
+            match event {
+                Event::Text(text) => code.push_str(&text),
+                Event::End(TagEnd::CodeBlock) => {
+                    let (code, range) = mermaid.take().unwrap_or_default();
+                    let html = format!("<pre class=\"mermaid\">{}</pre>\n", escape_html(code.trim()));
+                    out.push((Event::Html(html.into()), range));
+                }
+                _ => {}
+            }
+            continue;
+        }
+        let event = match event {
+            Event::Start(Tag::HtmlBlock) => Event::Start(Tag::Paragraph),
+            Event::End(TagEnd::HtmlBlock) => Event::End(TagEnd::Paragraph),
+            Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(lang))) if lang.trim().eq_ignore_ascii_case("mermaid") => {
+                mermaid = Some((String::new(), range));
+                continue;
+            }
+            Event::Start(Tag::CodeBlock(kind)) => {
+                in_code = true;
+                Event::Start(Tag::CodeBlock(kind))
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                in_code = false;
+                Event::End(TagEnd::CodeBlock)
+            }
+            Event::Start(Tag::Link { link_type, dest_url, title, id }) => {
+                if link_type != LinkType::Email && !is_safe_url(&dest_url) {
+                    dropped_link = true;
+                    continue;
+                }
+                in_link = true;
+                let dest_url = rebase(dest_url, base_url);
+                let title = if title.is_empty() { dest_url.clone() } else { title };
+                Event::Start(Tag::Link { link_type, dest_url, title, id })
+            }
+            Event::End(TagEnd::Link) => {
+                in_link = false;
+                if std::mem::take(&mut dropped_link) {
+                    continue;
+                }
+                Event::End(TagEnd::Link)
+            }
+            Event::Start(Tag::Image { link_type, dest_url, title, id }) => {
+                if !is_safe_url(&dest_url) {
+                    dropped_image = true;
+                    continue;
+                }
+                in_image = true;
+                Event::Start(Tag::Image { link_type, dest_url: rebase(dest_url, base_url), title, id })
+            }
+            Event::End(TagEnd::Image) => {
+                in_image = false;
+                if std::mem::take(&mut dropped_image) {
+                    continue;
+                }
+                Event::End(TagEnd::Image)
+            }
+            Event::Text(text) if !(in_code || in_link || in_image) => {
+                push_linkified(text, range, &mut out);
+                continue;
+            }
+            other => other,
+        };
+        out.push((event, range));
+    }
+    add_heading_ids(input, &mut out);
+    out
+}
+
+/// HTML for `events`.
+fn to_html<'a>(events: impl IntoIterator<Item = Event<'a>>, capacity: usize) -> String {
+    let mut out = String::with_capacity(capacity);
+    html::push_html(&mut out, events.into_iter());
+    out
+}
+
+/// Byte offset of the start of every line in `input`.
+fn line_starts(input: &str) -> Vec<usize> {
+    std::iter::once(0).chain(input.match_indices('\n').map(|(i, _)| i + 1)).collect()
+}
+
+/// 0-based line holding byte `offset`.
+fn line_of(starts: &[usize], offset: usize) -> usize {
+    starts.partition_point(|&s| s <= offset).saturating_sub(1)
+}
+
+/// Render a markdown document to HTML. This is the canonical renderer:
+/// HTML exports go through it, and the preview and Quick Look can target
+/// it so they don't drift apart.
+pub fn render_markdown(input: &str, options: RenderOptions) -> String {
+    let events = events(input, &options, None);
+    to_html(events.into_iter().map(|(e, _)| e), input.len() * 3 / 2)
+}
+
+/// Render `input` for export, applying `options`.
+pub fn render_for_export(input: &str, options: &ExportOptions) -> String {
+    let base = options.base_url.as_deref().filter(|b| !b.is_empty());
+    let events = events(input, &RenderOptions::default(), base);
+    to_html(events.into_iter().map(|(e, _)| e), input.len() * 3 / 2)
+}
+
+/// Wrap rendered `body` in a standalone HTML page. The body sits in
+/// `#preview` so the app's preview stylesheet applies unchanged when
+/// inlined as `css`.
+pub fn html_document(title: &str, body: &str, css: Option<&str>) -> String {
+    let style = css
+        .map(|css| format!("<style>\nbody {{ max-width: 860px; margin: 2em auto; padding: 0 1em; }}\n{}</style>\n", css))
+        .unwrap_or_default();
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n{}</head>\n<body>\n<article id=\"preview\">\n{}</article>\n</body>\n</html>\n",
+        escape_html(title),
+        style,
+        body
+    )
+}
+
+/// Render only the top-level blocks touching lines `start_line..=end_line`
+/// (1-based), so a range starting mid-list or mid-fence still renders the
+/// whole block. Returns the HTML and the expanded 1-based line range.
+pub fn render_lines(input: &str, start_line: usize, end_line: usize) -> (String, Range<usize>) {
+    let (lo, hi) = (start_line.saturating_sub(1), end_line.max(start_line));
+    let starts = line_starts(input);
+    let events = events(input, &RenderOptions::default(), None);
+    let mut picked = Vec::new();
+    let mut lines: Option<Range<usize>> = None;
+    let mut i = 0;
+    while i < events.len() {
+        let end = if matches!(events[i].0, Event::Start(_)) { matching_end(&events, i) } else { i };
+        // Lists and quotes run on over trailing blank lines.
+        let span = &events[i].1;
+        let span_end = span.start + input[span.clone()].trim_end().len();
+        let (first, last) = (line_of(&starts, span.start), line_of(&starts, span_end.saturating_sub(1).max(span.start)) + 1);
+        if first < hi && last > lo {
+            picked.extend(events[i..=end].iter().map(|(e, _)| e.clone()));
+            lines = Some(lines.map_or(first + 1..last, |l| l.start..last));
+        }
+        i = end + 1;
+    }
+    (to_html(picked, 0), lines.unwrap_or(start_line..end_line))
+}
+
+/// ATX and setext headings of `input`, skipping anything in code blocks,
+/// with the same anchor ids `render_markdown` gives them.
+pub fn outline(input: &str) -> Vec<OutlineItem> {
+    let starts = line_starts(input);
+    let events = events(input, &RenderOptions::default(), None);
+    let mut out = Vec::new();
+    for (i, (event, range)) in events.iter().enumerate() {
+        if let Event::Start(Tag::Heading { level, id, .. }) = event {
+            out.push(OutlineItem {
+                level: *level as u8,
+                text: plain_text(&events[i + 1..matching_end(&events, i)]).trim().to_string(),
+                line: line_of(&starts, range.start) + 1,
+                id: id.as_deref().unwrap_or_default().to_string(),
+            });
+        }
+    }
+    out
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// Canonical HTML for `markdown`, with every GFM extension on, for the
+/// preview and Quick Look to render through.
+#[tauri::command]
+pub fn render_preview(markdown: String) -> String {
+    render_markdown(&markdown, RenderOptions::default())
+}
+
+/// HTML for the blocks covering lines `start_line..=end_line` (1-based), for
+/// windowed preview of very large documents.
+#[tauri::command]
+pub fn render_range(text: String, start_line: usize, end_line: usize) -> String {
+    render_lines(&text, start_line, end_line).0
+}
+
+/// Headings of `markdown` for the outline sidebar; each `id` matches the
+/// anchor in the rendered preview.
+#[tauri::command]
+pub fn extract_outline(markdown: String) -> Vec<OutlineItem> {
+    outline(&markdown)
+}
+
+/// Rendered HTML for exporting, e.g. with image paths pointed at a site.
+#[tauri::command]
+pub fn render_export(markdown: String, options: Option<ExportOptions>) -> String {
+    render_for_export(&markdown, &options.unwrap_or_default())
+}
+
+/// Render `markdown` to a standalone HTML file at `dest`, with the preview
+/// stylesheet inlined when `embed_css` is set.
+#[tauri::command]
+pub fn export_html(app: tauri::AppHandle, markdown: String, dest: String, embed_css: bool) -> Result<(), String> {
+    let css = if embed_css {
+        let asset = app
+            .asset_resolver()
+            .get(PREVIEW_STYLESHEET.to_string())
+            .ok_or_else(|| format!("Missing stylesheet {}", PREVIEW_STYLESHEET))?;
+        Some(String::from_utf8_lossy(&asset.bytes).into_owned())
+    } else {
+        None
+    };
+    let path = std::path::Path::new(&dest);
+    let title = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let html = html_document(&title, &render_markdown(&markdown, RenderOptions::default()), css.as_deref());
+    crate::files::write_atomic(path, html.as_bytes()).map_err(|e| crate::files::describe_write_error(&dest, &e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_common_blocks() {
+        let md = "# Hello *World*\n\nSome **bold** and `code` with [a link](https://x.io).\n\n- one\n- [x] two\n\n```rust\nfn main() {}\n```\n";
+        let html = render_markdown(md, RenderOptions::default());
+        assert!(html.contains("<h1 id=\"hello-world\">Hello <em>World</em></h1>"));
+        assert!(html.contains("<p>Some <strong>bold</strong> and <code>code</code> with <a href=\"https://x.io\" title=\"https://x.io\">a link</a>.</p>"));
+        assert!(html.contains("<li>one</li>"));
+        assert!(html.contains("type=\"checkbox\" checked=\"\"/>\ntwo</li>"));
+        assert!(html.contains("<pre><code class=\"language-rust\">fn main() {}\n</code></pre>"));
+        assert_eq!(
+            render_markdown("See www.x.io/a_(b). `https://no.io`", RenderOptions::default()),
+            "<p>See <a href=\"http://www.x.io/a_(b)\" title=\"http://www.x.io/a_(b)\">www.x.io/a_(b)</a>. <code>https://no.io</code></p>\n"
+        );
+    }
+
+    #[test]
+    fn gfm_extensions_can_be_turned_off() {
+        let md = "| a |\n|---|\n| 1 |\n\n~~gone~~\n\n- [ ] todo\n";
+        let all = render_markdown(md, RenderOptions::default());
+        assert!(all.contains("<table>") && all.contains("<del>gone</del>") && all.contains("checkbox"));
+
+        let plain = render_markdown(md, RenderOptions { tables: false, strikethrough: false, task_lists: false });
+        assert!(!plain.contains("<table>"));
+        assert!(plain.contains("<p>~~gone~~</p>"));
+        assert!(plain.contains("<li>[ ] todo</li>"));
+    }
+
+    #[test]
+    fn raw_html_is_escaped() {
+        assert_eq!(render_markdown("<script>x</script>", RenderOptions::default()), "<p>&lt;script&gt;x&lt;/script&gt;</p>\n");
+    }
+
+    #[test]
+    fn script_and_data_urls_are_dropped() {
+        let md = "[x](javascript:alert(1)) ![z](data:image/svg+xml,<svg>) <vbscript:msgbox> [ok](notes/a.md) <me@x.io>";
+        assert_eq!(
+            render_markdown(md, RenderOptions::default()),
+            "<p>x z vbscript:msgbox <a href=\"notes/a.md\" title=\"notes/a.md\">ok</a> \
+             <a href=\"mailto:me@x.io\" title=\"me@x.io\">me@x.io</a></p>\n"
+        );
+        assert!(!is_safe_url("java\tscript:alert(1)") && !is_safe_url(" DATA:text/html,x"));
+        assert!(is_safe_url("https://x.io") && is_safe_url("a.md#top") && is_safe_url("C:\\notes\\a.md"));
+    }
+
+    #[test]
+    fn range_inside_list_expands_to_whole_list() {
+        let md = "Intro\n\n- a\n- b\n  continued\n- c\n\nOutro\n";
+        let (html, range) = render_lines(md, 4, 4);
+        assert_eq!(range, 3..6);
+        assert_eq!(html, "<ul>\n<li>a</li>\n<li>b\ncontinued</li>\n<li>c</li>\n</ul>\n");
+        assert!(!html.contains("Intro") && !html.contains("Outro"));
+    }
+
+    #[test]
+    fn range_covering_fence_start_includes_the_fence() {
+        let md = "```\ncode\nmore\n```\nafter\n";
+        let (html, range) = render_lines(md, 2, 2);
+        assert_eq!(range, 1..4);
+        assert_eq!(html, "<pre><code>code\nmore\n</code></pre>\n");
+    }
+
+    #[test]
+    fn base_url_prefixes_local_targets_only() {
+        let md = "![logo](./img/logo.png) [docs](guide.md) [site](https://x.io/a.png) [top](#intro) ![abs](/root.png)";
+        let opts = ExportOptions { base_url: Some("https://cdn.example.com/notes/".into()) };
+        let html = render_for_export(md, &opts);
+        assert!(html.contains("src=\"https://cdn.example.com/notes/img/logo.png\""));
+        assert!(html.contains("href=\"https://cdn.example.com/notes/guide.md\""));
+        assert!(html.contains("href=\"https://x.io/a.png\""));
+        assert!(html.contains("href=\"#intro\""));
+        assert!(html.contains("src=\"/root.png\""));
+        assert_eq!(render_for_export(md, &ExportOptions::default()), render_markdown(md, RenderOptions::default()));
+    }
+
+    #[test]
+    fn exported_page_is_standalone() {
+        let body = render_markdown("| a |\n|---|\n| 1 |\n\n- [ ] todo\n", RenderOptions::default());
+        let page = html_document("A <b> note", &body, Some("#preview { color: red; }"));
+        assert!(page.starts_with("<!DOCTYPE html>"));
+        assert!(page.contains("<title>A &lt;b&gt; note</title>"));
+        assert!(page.contains("#preview { color: red; }"));
+        assert!(page.contains("<article id=\"preview\">\n<table>"));
+        assert!(page.contains("type=\"checkbox\"/>\ntodo"));
+        assert!(!html_document("t", "", None).contains("<style>"));
+    }
+
+    #[test]
+    fn outline_ids_match_rendered_anchors() {
+        let md = "# Intro *fast*\n\n```\n# not a heading\n```\n\nSetup & Use\n---\n\n> ## Quoted\n\n- item\n\n  ### Nested\n";
+        let items = outline(md);
+        let summary: Vec<_> = items.iter().map(|h| (h.level, h.text.as_str(), h.line)).collect();
+        assert_eq!(summary, [(1, "Intro fast", 1), (2, "Setup & Use", 7), (2, "Quoted", 10), (3, "Nested", 14)]);
+        let html = render_markdown(md, RenderOptions::default());
+        for item in &items {
+            assert!(html.contains(&format!(" id=\"{}\">", item.id)), "{}", item.id);
+        }
+    }
+}
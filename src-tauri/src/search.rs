@@ -0,0 +1,112 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::vault;
+
+/// Most hits returned; the search stops once it has this many.
+const MAX_HITS: usize = 500;
+
+/// Files larger than this are skipped rather than scanned.
+const MAX_FILE_BYTES: u64 = 2 * 1024 * 1024;
+
+/// Longest `line_text` sent back; long lines are cut at a char boundary.
+const MAX_LINE_CHARS: usize = 300;
+
+/// One match of the query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SearchHit {
+    pub path: String,
+    /// File name, for the result list.
+    pub name: String,
+    /// 1-based.
+    pub line_number: usize,
+    pub line_text: String,
+    /// 1-based, in characters.
+    pub column: usize,
+}
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// 1-based character columns where `query` occurs in `line`.
+fn match_columns(line: &str, query: &str, case_sensitive: bool) -> Vec<usize> {
+    let (line, query) = if case_sensitive {
+        (line.to_string(), query.to_string())
+    } else {
+        (line.to_lowercase(), query.to_lowercase())
+    };
+    line.match_indices(&query)
+        .map(|(byte, _)| line[..byte].chars().count() + 1)
+        .collect()
+}
+
+/// Search the markdown files under `root` (skipping dot-directories and
+/// `node_modules`) for `query`, up to `MAX_HITS` hits.
+pub fn search(root: &Path, query: &str, case_sensitive: bool) -> Vec<SearchHit> {
+    let mut hits = Vec::new();
+    if query.is_empty() {
+        return hits;
+    }
+    for file in vault::markdown_files(root) {
+        if std::fs::metadata(&file).map_or(true, |m| m.len() > MAX_FILE_BYTES) {
+            continue;
+        }
+        let Ok(text) = std::fs::read_to_string(&file) else { continue };
+        let path = file.to_string_lossy().to_string();
+        let name = file.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| path.clone());
+        for (i, line) in text.lines().enumerate() {
+            for column in match_columns(line, query, case_sensitive) {
+                if hits.len() == MAX_HITS {
+                    return hits;
+                }
+                hits.push(SearchHit {
+                    path: path.clone(),
+                    name: name.clone(),
+                    line_number: i + 1,
+                    line_text: line.chars().take(MAX_LINE_CHARS).collect(),
+                    column,
+                });
+            }
+        }
+    }
+    hits
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// Project-wide search for the folder panel. Walks on a blocking worker so
+/// the UI stays responsive on big folders.
+#[tauri::command]
+pub async fn search_in_files(root: String, query: String, case_sensitive: bool) -> Result<Vec<SearchHit>, String> {
+    if !Path::new(&root).is_dir() {
+        return Err(format!("Failed to search {}: not a folder", root));
+    }
+    let dir = PathBuf::from(&root);
+    tauri::async_runtime::spawn_blocking(move || search(&dir, &query, case_sensitive))
+        .await
+        .map_err(|e| format!("Failed to search {}: {}", root, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_matches_with_columns_and_skips_hidden_folders() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("node_modules")).unwrap();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::write(root.join("a.md"), "# Plan\n\nThe plan: é plan\n").unwrap();
+        std::fs::write(root.join("node_modules/b.md"), "plan").unwrap();
+        std::fs::write(root.join(".git/c.md"), "plan").unwrap();
+
+        let hits = search(root, "plan", false);
+        let found: Vec<(usize, usize)> = hits.iter().map(|h| (h.line_number, h.column)).collect();
+        assert_eq!(found, vec![(1, 3), (3, 5), (3, 13)]);
+        assert_eq!(hits[0].name, "a.md");
+        assert_eq!(hits[1].line_text, "The plan: é plan");
+
+        assert_eq!(search(root, "Plan", true).len(), 1);
+        assert!(search(root, "", false).is_empty());
+    }
+}
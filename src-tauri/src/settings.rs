@@ -0,0 +1,614 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{PoisonError, RwLock};
+use tauri::{Emitter, Manager};
+
+use crate::autosave::AutoSave;
+use crate::recent::RecentSort;
+use crate::theme::ThemePreference;
+
+/// File extensions opened as markdown unless the user adds more.
+pub const DEFAULT_OPENABLE_EXTENSIONS: &[&str] = &["md", "markdown"];
+
+/// Recent-list length used when the setting is missing or malformed.
+pub const DEFAULT_MAX_RECENT: usize = 10;
+
+/// Bounds `max_recent` is clamped to.
+pub const MAX_RECENT_LIMITS: (usize, usize) = (1, 100);
+
+/// Current `settings.json` layout. Bump it when a field changes meaning so
+/// `load_settings` can migrate older files; new fields just need a default.
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// Preview zoom bounds, matching the toolbar's 50–200%.
+pub const ZOOM_LIMITS: (f64, f64) = (0.5, 2.0);
+
+/// Editor font size bounds, in CSS pixels.
+pub const FONT_SIZE_LIMITS: (u32, u32) = (8, 48);
+
+/// Tab width bounds, in columns.
+pub const TAB_WIDTH_LIMITS: (u32, u32) = (1, 8);
+
+/// The active extension allowlist, loaded from settings at startup. Empty
+/// means the defaults.
+static OPENABLE_EXTENSIONS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+/// Persisted user settings (`settings.json` in the app data directory).
+/// Unknown or missing fields fall back to their defaults.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Layout version the file was written with; see `SETTINGS_VERSION`.
+    pub version: u32,
+    /// Expanded folder-panel directories, keyed by the open folder's root.
+    pub expanded: BTreeMap<String, Vec<String>>,
+    /// Extensions (lowercase, no dot) treated as markdown documents.
+    pub openable_extensions: Vec<String>,
+    /// Length of the "Open Recent" list.
+    #[serde(deserialize_with = "lenient_max_recent")]
+    pub max_recent: usize,
+    /// Preview zoom factor; 1.0 is actual size.
+    pub zoom: f64,
+    /// Order of the "Open Recent" menu.
+    pub recent_sort: RecentSort,
+    /// Folder shown in the folder panel when the app last ran.
+    pub last_folder: Option<String>,
+    /// Colour theme for the window chrome and the page.
+    pub theme: ThemePreference,
+    /// Background saving of edited files.
+    pub auto_save: AutoSave,
+    /// Keep a copy of each file's previous contents on save; see `backups`.
+    pub enable_backups: bool,
+    /// Send later launches' files to the running app instead of starting
+    /// another process. Takes effect on the next launch.
+    pub single_instance: bool,
+    /// Global shortcut that shows or hides the main window; see `hotkey`.
+    pub quick_capture_shortcut: Option<String>,
+    /// Show an icon with quick actions in the system tray / menu bar.
+    pub show_tray_icon: bool,
+    /// Editor preferences; the frontend applies them.
+    pub editor: EditorSettings,
+    /// Release manifest `check_for_updates` reads; GitHub releases if unset.
+    pub update_feed_url: Option<String>,
+    /// Size in bytes above which `files::precheck_open` holds a file back
+    /// from the editor.
+    pub large_file_threshold: u64,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            version: SETTINGS_VERSION,
+            expanded: BTreeMap::new(),
+            openable_extensions: DEFAULT_OPENABLE_EXTENSIONS.iter().map(|e| e.to_string()).collect(),
+            max_recent: DEFAULT_MAX_RECENT,
+            zoom: 1.0,
+            recent_sort: RecentSort::default(),
+            last_folder: None,
+            theme: ThemePreference::default(),
+            auto_save: AutoSave::default(),
+            enable_backups: false,
+            single_instance: true,
+            quick_capture_shortcut: None,
+            show_tray_icon: false,
+            editor: EditorSettings::default(),
+            update_feed_url: None,
+            large_file_threshold: crate::files::DEFAULT_LARGE_FILE_THRESHOLD,
+        }
+    }
+}
+
+/// The `editor` setting. Fields missing from older files take their
+/// defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct EditorSettings {
+    pub word_wrap: bool,
+    /// In CSS pixels.
+    pub font_size: u32,
+    pub tab_width: u32,
+    pub line_numbers: bool,
+}
+
+impl Default for EditorSettings {
+    fn default() -> Self {
+        EditorSettings { word_wrap: true, font_size: 14, tab_width: 2, line_numbers: false }
+    }
+}
+
+impl EditorSettings {
+    /// Bring the sizes within `FONT_SIZE_LIMITS` and `TAB_WIDTH_LIMITS`.
+    pub fn clamped(self) -> Self {
+        EditorSettings {
+            font_size: self.font_size.clamp(FONT_SIZE_LIMITS.0, FONT_SIZE_LIMITS.1),
+            tab_width: self.tab_width.clamp(TAB_WIDTH_LIMITS.0, TAB_WIDTH_LIMITS.1),
+            ..self
+        }
+    }
+}
+
+/// A malformed `max_recent` falls back to the default rather than
+/// discarding the whole settings file.
+fn lenient_max_recent<'de, D: serde::Deserializer<'de>>(d: D) -> Result<usize, D::Error> {
+    let value = serde_json::Value::deserialize(d)?;
+    Ok(value
+        .as_u64()
+        .map(|n| clamp_max_recent(n.min(usize::MAX as u64) as usize))
+        .unwrap_or(DEFAULT_MAX_RECENT))
+}
+
+pub fn clamp_max_recent(limit: usize) -> usize {
+    limit.clamp(MAX_RECENT_LIMITS.0, MAX_RECENT_LIMITS.1)
+}
+
+/// Bound a zoom factor and round away float drift from repeated steps.
+/// Non-finite values reset to 1.0.
+pub fn clamp_zoom(factor: f64) -> f64 {
+    if !factor.is_finite() {
+        return 1.0;
+    }
+    (factor.clamp(ZOOM_LIMITS.0, ZOOM_LIMITS.1) * 100.0).round() / 100.0
+}
+
+// ── Persistence ───────────────────────────────────────────────────────────────
+
+pub fn settings_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|d| d.join("settings.json"))
+        .map_err(|e| format!("Cannot locate the app data directory: {}", e))
+}
+
+/// Settings from parsed `settings.json`, taking each field on its own: a
+/// field with a bad value falls back to its default without discarding
+/// the others.
+fn settings_from_value(value: serde_json::Value) -> Settings {
+    let serde_json::Value::Object(saved) = value else { return Settings::default() };
+    let Ok(serde_json::Value::Object(mut merged)) = serde_json::to_value(Settings::default()) else {
+        return Settings::default();
+    };
+    for (key, field) in saved {
+        if !merged.contains_key(&key) {
+            continue;
+        }
+        let mut attempt = merged.clone();
+        attempt.insert(key, field);
+        if serde_json::from_value::<Settings>(serde_json::Value::Object(attempt.clone())).is_ok() {
+            merged = attempt;
+        }
+    }
+    serde_json::from_value(serde_json::Value::Object(merged)).unwrap_or_default()
+}
+
+pub fn load_settings(path: &Path) -> Settings {
+    let mut settings = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .map(settings_from_value)
+        .unwrap_or_default();
+    // Nothing to migrate yet: version 1 files only gained defaulted fields.
+    settings.version = SETTINGS_VERSION;
+    settings
+}
+
+/// Set one top-level field of `settings` from its JSON value. Unknown keys,
+/// `version` and values of the wrong type are rejected.
+pub fn apply_setting(settings: &mut Settings, key: &str, value: serde_json::Value) -> Result<(), String> {
+    let mut json = serde_json::to_value(&*settings).map_err(|e| e.to_string())?;
+    let fields = json.as_object_mut().ok_or("Settings are not an object")?;
+    if key == "version" || !fields.contains_key(key) {
+        return Err(format!("Unknown setting: {}", key));
+    }
+    fields.insert(key.to_string(), value);
+    let mut updated: Settings =
+        serde_json::from_value(json).map_err(|e| format!("Invalid value for {}: {}", key, e))?;
+    updated.openable_extensions = normalize_extensions(updated.openable_extensions);
+    updated.zoom = clamp_zoom(updated.zoom);
+    updated.editor = updated.editor.clamped();
+    *settings = updated;
+    Ok(())
+}
+
+/// Write `settings` atomically. A file on disk that isn't valid JSON (and so
+/// was loaded as defaults) is kept as `settings.json.bak` rather than lost.
+pub fn save_settings(path: &Path, settings: &Settings) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let unreadable = std::fs::read_to_string(path)
+        .is_ok_and(|c| serde_json::from_str::<serde_json::Value>(&c).is_err());
+    if unreadable {
+        let backup = path.with_extension("json.bak");
+        std::fs::copy(path, &backup).map_err(|e| format!("Failed to back up {}: {}", path.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    crate::files::write_atomic(path, json.as_bytes()).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+// ── Openable extensions ───────────────────────────────────────────────────────
+
+/// Lowercase, strip leading dots, drop blanks and duplicates (keeping order).
+pub fn normalize_extensions(list: Vec<String>) -> Vec<String> {
+    let mut out: Vec<String> = Vec::with_capacity(list.len());
+    for ext in list {
+        let ext = ext.trim().trim_start_matches('.').to_lowercase();
+        if !ext.is_empty() && !out.contains(&ext) {
+            out.push(ext);
+        }
+    }
+    out
+}
+
+/// Make `list` the allowlist used by `is_openable_extension`.
+pub fn apply_openable_extensions(list: &[String]) {
+    *OPENABLE_EXTENSIONS.write().unwrap_or_else(PoisonError::into_inner) = list.to_vec();
+}
+
+/// Whether `ext` is in `list` (case-insensitive).
+pub fn extension_allowed(list: &[String], ext: &str) -> bool {
+    list.contains(&ext.to_lowercase())
+}
+
+/// The active allowlist.
+pub fn openable_extensions() -> Vec<String> {
+    let active = OPENABLE_EXTENSIONS.read().unwrap_or_else(PoisonError::into_inner);
+    if active.is_empty() {
+        Settings::default().openable_extensions
+    } else {
+        active.clone()
+    }
+}
+
+/// Check `ext` against the active allowlist.
+pub fn is_openable_extension(ext: &str) -> bool {
+    extension_allowed(&openable_extensions(), ext)
+}
+
+/// Load persisted settings that take effect process-wide.
+pub fn apply_on_startup(app: &tauri::AppHandle) {
+    if let Ok(path) = settings_path(app) {
+        apply_openable_extensions(&normalize_extensions(load_settings(&path).openable_extensions));
+    }
+}
+
+/// The saved recent-list length, for seeding state at startup.
+pub fn load_max_recent(app: &tauri::AppHandle) -> usize {
+    settings_path(app)
+        .map(|p| load_settings(&p).max_recent)
+        .unwrap_or(DEFAULT_MAX_RECENT)
+}
+
+/// Persist a new recent-list length (clamped) and return it.
+pub fn save_max_recent(app: &tauri::AppHandle, limit: usize) -> Result<usize, String> {
+    let limit = clamp_max_recent(limit);
+    let path = settings_path(app)?;
+    let mut settings = load_settings(&path);
+    settings.max_recent = limit;
+    save_settings(&path, &settings)?;
+    Ok(limit)
+}
+
+/// The saved "Open Recent" order, for seeding state at startup.
+pub fn load_recent_sort(app: &tauri::AppHandle) -> RecentSort {
+    settings_path(app)
+        .map(|p| load_settings(&p).recent_sort)
+        .unwrap_or_default()
+}
+
+pub fn save_recent_sort(app: &tauri::AppHandle, sort: RecentSort) -> Result<(), String> {
+    let path = settings_path(app)?;
+    let mut settings = load_settings(&path);
+    settings.recent_sort = sort;
+    save_settings(&path, &settings)
+}
+
+/// The saved folder-panel folder, cleared if it has since been deleted or
+/// moved.
+pub fn existing_last_folder(settings: &mut Settings) -> Option<String> {
+    if settings.last_folder.as_deref().is_some_and(|f| !Path::new(f).is_dir()) {
+        settings.last_folder = None;
+    }
+    settings.last_folder.clone()
+}
+
+/// The folder to restore in the folder panel at startup, if any.
+pub fn load_last_folder(app: &tauri::AppHandle) -> Option<String> {
+    let path = settings_path(app).ok()?;
+    let mut settings = load_settings(&path);
+    let had_folder = settings.last_folder.is_some();
+    let folder = existing_last_folder(&mut settings);
+    if had_folder && folder.is_none() {
+        let _ = save_settings(&path, &settings);
+    }
+    folder
+}
+
+// ── Expanded folders ──────────────────────────────────────────────────────────
+
+/// Expanded directories saved for `root`, dropping any that no longer exist.
+/// Returns whether anything was pruned so the caller can persist the result.
+pub fn expanded_for(settings: &mut Settings, root: &str) -> (Vec<String>, bool) {
+    let Some(paths) = settings.expanded.get_mut(root) else { return (vec![], false) };
+    let before = paths.len();
+    paths.retain(|p| Path::new(p).is_dir());
+    let pruned = paths.len() != before;
+    let paths = paths.clone();
+    if paths.is_empty() {
+        settings.expanded.remove(root);
+    }
+    (paths, pruned)
+}
+
+pub fn set_expanded_for(settings: &mut Settings, root: &str, mut paths: Vec<String>) {
+    paths.sort();
+    paths.dedup();
+    if paths.is_empty() {
+        settings.expanded.remove(root);
+    } else {
+        settings.expanded.insert(root.to_string(), paths);
+    }
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// Folder-panel directories to re-expand when `root` is opened.
+#[tauri::command]
+pub fn get_expanded(app: tauri::AppHandle, root: String) -> Result<Vec<String>, String> {
+    let path = settings_path(&app)?;
+    let mut settings = load_settings(&path);
+    let (paths, pruned) = expanded_for(&mut settings, &root);
+    if pruned {
+        save_settings(&path, &settings)?;
+    }
+    Ok(paths)
+}
+
+#[tauri::command]
+pub fn set_expanded(app: tauri::AppHandle, root: String, paths: Vec<String>) -> Result<(), String> {
+    let path = settings_path(&app)?;
+    let mut settings = load_settings(&path);
+    set_expanded_for(&mut settings, &root, paths);
+    save_settings(&path, &settings)
+}
+
+/// Extensions opened as markdown documents.
+#[tauri::command]
+pub fn get_openable_extensions(app: tauri::AppHandle) -> Result<Vec<String>, String> {
+    Ok(normalize_extensions(load_settings(&settings_path(&app)?).openable_extensions))
+}
+
+/// Replace the extension allowlist; returns it normalized.
+#[tauri::command]
+pub fn set_openable_extensions(app: tauri::AppHandle, list: Vec<String>) -> Result<Vec<String>, String> {
+    let list = normalize_extensions(list);
+    if list.is_empty() {
+        return Err("At least one extension must be openable".to_string());
+    }
+    let path = settings_path(&app)?;
+    let mut settings = load_settings(&path);
+    settings.openable_extensions = list.clone();
+    save_settings(&path, &settings)?;
+    apply_openable_extensions(&list);
+    Ok(list)
+}
+
+/// The saved preview zoom factor, applied by each window at startup.
+#[tauri::command]
+pub fn get_zoom(app: tauri::AppHandle) -> Result<f64, String> {
+    Ok(clamp_zoom(load_settings(&settings_path(&app)?).zoom))
+}
+
+/// Remember the preview zoom factor (clamped). Returns the factor saved.
+#[tauri::command]
+pub fn set_zoom(app: tauri::AppHandle, factor: f64) -> Result<f64, String> {
+    let factor = clamp_zoom(factor);
+    let path = settings_path(&app)?;
+    let mut settings = load_settings(&path);
+    settings.zoom = factor;
+    save_settings(&path, &settings)?;
+    Ok(factor)
+}
+
+/// The whole preferences blob.
+#[tauri::command]
+pub fn get_settings(app: tauri::AppHandle) -> Result<Settings, String> {
+    Ok(load_settings(&settings_path(&app)?))
+}
+
+/// Change one setting by its field name, put it into effect and return the
+/// saved settings. A shortcut that can't be registered is refused before
+/// anything is saved. Other values are read when next needed, or (for
+/// `single_instance`) on the next launch.
+#[tauri::command]
+pub fn set_setting(app: tauri::AppHandle, key: String, value: serde_json::Value) -> Result<Settings, String> {
+    let path = settings_path(&app)?;
+    let mut settings = load_settings(&path);
+    apply_setting(&mut settings, &key, value)?;
+    if settings.openable_extensions.is_empty() {
+        return Err("At least one extension must be openable".to_string());
+    }
+    if key == "quick_capture_shortcut" {
+        let accelerator = settings.quick_capture_shortcut.take().map(|a| a.trim().to_string());
+        crate::hotkey::apply(&app, accelerator.as_deref())?;
+        settings.quick_capture_shortcut = accelerator.filter(|a| !a.is_empty());
+    }
+    save_settings(&path, &settings)?;
+    match key.as_str() {
+        "openable_extensions" => apply_openable_extensions(&settings.openable_extensions),
+        "theme" => crate::theme::apply_saved_theme(&app),
+        "show_tray_icon" => crate::tray::apply(&app, settings.show_tray_icon)?,
+        "max_recent" => crate::apply_max_recent(&app, settings.max_recent),
+        "recent_sort" => crate::apply_recent_sort(&app, settings.recent_sort),
+        "editor" => {
+            let _ = app.emit("editor-settings-changed", settings.editor);
+        }
+        _ => {}
+    }
+    Ok(settings)
+}
+
+#[tauri::command]
+pub fn get_editor_settings(app: tauri::AppHandle) -> Result<EditorSettings, String> {
+    Ok(load_settings(&settings_path(&app)?).editor.clamped())
+}
+
+/// Save the editor preferences (clamped) and send them to every window as
+/// `editor-settings-changed`, so all editors match.
+#[tauri::command]
+pub fn set_editor_settings(app: tauri::AppHandle, editor: EditorSettings) -> Result<EditorSettings, String> {
+    let editor = editor.clamped();
+    let path = settings_path(&app)?;
+    let mut settings = load_settings(&path);
+    settings.editor = editor;
+    save_settings(&path, &settings)?;
+    let _ = app.emit("editor-settings-changed", editor);
+    Ok(editor)
+}
+
+/// Remember the folder the folder panel is showing, for the next launch.
+#[tauri::command]
+pub fn set_last_folder(app: tauri::AppHandle, path: String) -> Result<(), String> {
+    let settings_file = settings_path(&app)?;
+    let mut settings = load_settings(&settings_file);
+    settings.last_folder = Some(path);
+    save_settings(&settings_file, &settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expanded_state_round_trips_through_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_string_lossy().to_string();
+        let notes = dir.path().join("notes");
+        std::fs::create_dir(&notes).unwrap();
+        let notes = notes.to_string_lossy().to_string();
+
+        let path = dir.path().join("settings.json");
+        let mut settings = load_settings(&path);
+        set_expanded_for(&mut settings, &root, vec![notes.clone(), notes.clone()]);
+        save_settings(&path, &settings).unwrap();
+
+        let mut loaded = load_settings(&path);
+        assert_eq!(loaded, settings);
+        assert_eq!(expanded_for(&mut loaded, &root), (vec![notes], false));
+    }
+
+    #[test]
+    fn deleted_directories_are_pruned_on_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_string_lossy().to_string();
+        let keep = dir.path().join("keep");
+        let gone = dir.path().join("gone");
+        std::fs::create_dir(&keep).unwrap();
+        std::fs::create_dir(&gone).unwrap();
+        let keep = keep.to_string_lossy().to_string();
+
+        let mut settings = Settings::default();
+        set_expanded_for(&mut settings, &root, vec![keep.clone(), gone.to_string_lossy().to_string()]);
+        std::fs::remove_dir(&gone).unwrap();
+
+        assert_eq!(expanded_for(&mut settings, &root), (vec![keep.clone()], true));
+        assert_eq!(settings.expanded[&root], vec![keep]);
+    }
+
+    #[test]
+    fn added_extension_passes_validation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        let note = dir.path().join("Chapter.QMD");
+        std::fs::write(&note, "# Quarto").unwrap();
+
+        let mut settings = load_settings(&path);
+        assert!(crate::files::validate_markdown_path_in(&note, &settings.openable_extensions).is_err());
+
+        settings.openable_extensions =
+            normalize_extensions(vec!["md".into(), ".QMD".into(), "qmd".into(), " ".into()]);
+        assert_eq!(settings.openable_extensions, vec!["md", "qmd"]);
+        save_settings(&path, &settings).unwrap();
+
+        let loaded = load_settings(&path);
+        assert!(crate::files::validate_markdown_path_in(&note, &loaded.openable_extensions).is_ok());
+    }
+
+    #[test]
+    fn max_recent_is_clamped_and_malformed_values_fall_back() {
+        let parse = |json: &str| serde_json::from_str::<Settings>(json).unwrap();
+        assert_eq!(parse("{}").max_recent, DEFAULT_MAX_RECENT);
+        assert_eq!(parse(r#"{"max_recent": 25}"#).max_recent, 25);
+        assert_eq!(parse(r#"{"max_recent": 0}"#).max_recent, 1);
+        assert_eq!(parse(r#"{"max_recent": 5000}"#).max_recent, 100);
+        let malformed = parse(r#"{"max_recent": "lots", "openable_extensions": ["md", "qmd"]}"#);
+        assert_eq!(malformed.max_recent, DEFAULT_MAX_RECENT);
+        assert_eq!(malformed.openable_extensions, vec!["md", "qmd"]);
+    }
+
+    #[test]
+    fn a_bad_field_or_file_never_wipes_the_rest() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        std::fs::write(&path, r#"{"zoom": "huge", "show_tray_icon": true, "last_folder": "/notes"}"#).unwrap();
+        let loaded = load_settings(&path);
+        assert_eq!(loaded.zoom, 1.0);
+        assert!(loaded.show_tray_icon);
+        assert_eq!(loaded.last_folder.as_deref(), Some("/notes"));
+
+        std::fs::write(&path, r#"{"show_tray_icon": tr"#).unwrap();
+        save_settings(&path, &load_settings(&path)).unwrap();
+        assert_eq!(std::fs::read_to_string(path.with_extension("json.bak")).unwrap(), r#"{"show_tray_icon": tr"#);
+        assert_eq!(load_settings(&path), Settings::default());
+    }
+
+    #[test]
+    fn zoom_is_clamped_and_rounded() {
+        assert_eq!(clamp_zoom(1.0 + 0.1 + 0.1 + 0.1), 1.3);
+        assert_eq!(clamp_zoom(0.1), ZOOM_LIMITS.0);
+        assert_eq!(clamp_zoom(10.0), ZOOM_LIMITS.1);
+        assert_eq!(clamp_zoom(f64::NAN), 1.0);
+    }
+
+    #[test]
+    fn missing_last_folder_is_cleared() {
+        let dir = tempfile::tempdir().unwrap();
+        let folder = dir.path().to_string_lossy().to_string();
+        let mut settings = Settings { last_folder: Some(folder.clone()), ..Settings::default() };
+        assert_eq!(existing_last_folder(&mut settings), Some(folder));
+
+        settings.last_folder = Some(dir.path().join("gone").to_string_lossy().to_string());
+        assert_eq!(existing_last_folder(&mut settings), None);
+        assert_eq!(settings.last_folder, None);
+    }
+
+    #[test]
+    fn set_setting_updates_known_fields_only() {
+        let mut settings = load_settings(Path::new("/nonexistent/settings.json"));
+        assert_eq!(settings.version, SETTINGS_VERSION);
+
+        apply_setting(&mut settings, "zoom", serde_json::json!(1.234)).unwrap();
+        assert_eq!(settings.zoom, 1.23);
+        apply_setting(&mut settings, "recent_sort", serde_json::json!("Frequency")).unwrap();
+        assert_eq!(settings.recent_sort, RecentSort::Frequency);
+        assert_eq!(settings.large_file_threshold, crate::files::DEFAULT_LARGE_FILE_THRESHOLD);
+        apply_setting(&mut settings, "large_file_threshold", serde_json::json!(1_000_000)).unwrap();
+        assert_eq!(settings.large_file_threshold, 1_000_000);
+
+        assert!(apply_setting(&mut settings, "zoom", serde_json::json!("big")).is_err());
+        assert!(apply_setting(&mut settings, "colour", serde_json::json!(1)).is_err());
+        assert!(apply_setting(&mut settings, "version", serde_json::json!(9)).is_err());
+        assert_eq!(settings.zoom, 1.23);
+    }
+
+    #[test]
+    fn editor_settings_fill_missing_fields_and_clamp() {
+        let settings: Settings = serde_json::from_str(r#"{"editor": {"font_size": 200}}"#).unwrap();
+        assert_eq!(settings.editor.font_size, 200);
+        let editor = settings.editor.clamped();
+        assert_eq!(editor.font_size, FONT_SIZE_LIMITS.1);
+        assert!(editor.word_wrap);
+        assert_eq!(editor.tab_width, 2);
+        assert_eq!(Settings::default().editor, EditorSettings::default());
+    }
+}
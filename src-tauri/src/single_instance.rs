@@ -0,0 +1,41 @@
+use std::path::{Path, PathBuf};
+use tauri::plugin::TauriPlugin;
+
+/// `settings.json` for the app with config `identifier`, found the way
+/// `app_data_dir` does. The plugin has to be registered before any
+/// `AppHandle` exists, so `settings::settings_path` can't be used.
+fn settings_path(identifier: &str) -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join(identifier).join("settings.json"))
+}
+
+/// Whether the `single_instance` setting is on (the default).
+pub fn enabled(identifier: &str) -> bool {
+    settings_path(identifier).is_none_or(|p| crate::settings::load_settings(&p).single_instance)
+}
+
+/// Hands a second launch's files to the running instance and exits it
+/// before it builds any window. Register it as the first plugin.
+pub fn plugin() -> TauriPlugin<tauri::Wry> {
+    tauri_plugin_single_instance::init(|app, argv, cwd| {
+        // Relative paths are relative to where the second launch ran.
+        let argv = argv.into_iter().map(|a| if a.starts_with('-') { a } else { absolute_in(&cwd, &a) });
+        crate::second_launch(app, crate::windows::launch_paths(argv));
+    })
+}
+
+fn absolute_in(cwd: &str, arg: &str) -> String {
+    Path::new(cwd).join(arg).to_string_lossy().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forwarded_paths_resolve_against_the_second_launch() {
+        let cwd = if cfg!(windows) { r"C:\notes" } else { "/notes" };
+        assert_eq!(PathBuf::from(absolute_in(cwd, "a.md")), Path::new(cwd).join("a.md"));
+        let absolute = if cfg!(windows) { r"D:\b.md" } else { "/b.md" };
+        assert_eq!(absolute_in(cwd, absolute), absolute);
+    }
+}
@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+/// Marks where the caret goes after a snippet is inserted.
+const CURSOR_MARKER: &str = "{{cursor}}";
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Snippet {
+    pub name: String,
+    pub trigger: String,
+    pub body: String,
+}
+
+/// A snippet body with placeholders filled in. `cursor` is the caret offset
+/// in UTF-16 code units (matching JS string indices), if the body had a
+/// `{{cursor}}` marker.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Expansion {
+    pub text: String,
+    pub cursor: Option<usize>,
+}
+
+// ── Persistence ───────────────────────────────────────────────────────────────
+
+fn snippets_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|d| d.join("snippets.json"))
+        .map_err(|e| format!("Cannot locate the app data directory: {}", e))
+}
+
+/// The saved snippets; none if the file doesn't exist yet. A file that
+/// can't be parsed is an error rather than an empty list, so the next save
+/// doesn't overwrite the user's snippets.
+pub fn load_snippets(path: &Path) -> Result<Vec<Snippet>, String> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    serde_json::from_str(&text).map_err(|e| format!("{} is not valid snippets JSON: {}", path.display(), e))
+}
+
+/// Write `snippets` atomically.
+pub fn save_snippets(path: &Path, snippets: &[Snippet]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let json = serde_json::to_string_pretty(snippets).map_err(|e| e.to_string())?;
+    crate::files::write_atomic(path, json.as_bytes()).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+// ── Expansion ─────────────────────────────────────────────────────────────────
+
+/// Replace `{{name}}` placeholders from `vars`. `{{date}}` defaults to
+/// `today` when not supplied; unknown placeholders are left untouched.
+pub fn expand(body: &str, vars: &HashMap<String, String>, today: &str) -> Expansion {
+    let mut text = String::with_capacity(body.len());
+    let mut cursor = None;
+    let mut rest = body;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start..].find("}}") else { break };
+        let token = &rest[start..start + len + 2];
+        let name = token[2..token.len() - 2].trim();
+        text.push_str(&rest[..start]);
+        if token == CURSOR_MARKER || name == "cursor" {
+            cursor.get_or_insert_with(|| text.encode_utf16().count());
+        } else if let Some(value) = vars.get(name) {
+            text.push_str(value);
+        } else if name == "date" {
+            text.push_str(today);
+        } else {
+            text.push_str(token);
+        }
+        rest = &rest[start + token.len()..];
+    }
+    text.push_str(rest);
+    Expansion { text, cursor }
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn list_snippets(app: tauri::AppHandle) -> Result<Vec<Snippet>, String> {
+    load_snippets(&snippets_path(&app)?)
+}
+
+/// Add a snippet, replacing any existing one with the same name.
+#[tauri::command]
+pub fn save_snippet(app: tauri::AppHandle, snippet: Snippet) -> Result<(), String> {
+    if snippet.name.trim().is_empty() {
+        return Err("Snippet name cannot be empty".to_string());
+    }
+    let path = snippets_path(&app)?;
+    let mut snippets = load_snippets(&path)?;
+    match snippets.iter_mut().find(|s| s.name == snippet.name) {
+        Some(existing) => *existing = snippet,
+        None => snippets.push(snippet),
+    }
+    save_snippets(&path, &snippets)
+}
+
+/// Remove the named snippet. Not an error if it doesn't exist.
+#[tauri::command]
+pub fn delete_snippet(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    let path = snippets_path(&app)?;
+    let mut snippets = load_snippets(&path)?;
+    snippets.retain(|s| s.name != name);
+    save_snippets(&path, &snippets)
+}
+
+/// Expand the named snippet's placeholders for insertion at the caret.
+#[tauri::command]
+pub fn expand_snippet(
+    app: tauri::AppHandle,
+    name: String,
+    vars: Option<HashMap<String, String>>,
+) -> Result<Expansion, String> {
+    let snippets = load_snippets(&snippets_path(&app)?)?;
+    let snippet = snippets
+        .iter()
+        .find(|s| s.name == name)
+        .ok_or_else(|| format!("No snippet named '{}'", name))?;
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    Ok(expand(&snippet.body, &vars.unwrap_or_default(), &today))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_are_expanded() {
+        let vars = HashMap::from([("title".to_string(), "Standup".to_string())]);
+        let out = expand("# {{title}} — {{date}}\n{{ unknown }}", &vars, "2024-05-01");
+        assert_eq!(out.text, "# Standup — 2024-05-01\n{{ unknown }}");
+        assert_eq!(out.cursor, None);
+    }
+
+    #[test]
+    fn cursor_offset_is_reported_in_utf16_units() {
+        let out = expand("## Notes — {{date}}\n\n- {{cursor}}\n", &HashMap::new(), "2024-05-01");
+        assert_eq!(out.text, "## Notes — 2024-05-01\n\n- \n");
+        assert_eq!(out.cursor, Some("## Notes — 2024-05-01\n\n- ".encode_utf16().count()));
+    }
+
+    #[test]
+    fn snippets_round_trip_through_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snippets.json");
+        assert!(load_snippets(&path).unwrap().is_empty());
+        let snippet = Snippet { name: "daily".into(), trigger: ";d".into(), body: "{{date}}".into() };
+        save_snippets(&path, std::slice::from_ref(&snippet)).unwrap();
+        assert_eq!(load_snippets(&path).unwrap(), vec![snippet]);
+    }
+
+    #[test]
+    fn corrupt_file_is_an_error_not_an_empty_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snippets.json");
+        std::fs::write(&path, r#"[{"name": "daily", "#).unwrap();
+        assert!(load_snippets(&path).is_err());
+    }
+}
@@ -0,0 +1,354 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::Manager;
+
+/// Per-folder list of extra words, one per line.
+pub const FOLDER_DICT_NAME: &str = ".updown-dict";
+
+/// Personal word list shared by every folder, stored in the app data dir.
+const GLOBAL_DICT_NAME: &str = "custom-dict.txt";
+
+/// At most this many suggestions are returned per misspelling.
+const MAX_SUGGESTIONS: usize = 5;
+
+const EN_WORDS: &str = include_str!("../dictionaries/en.txt");
+
+/// A word not found in the dictionary. `line` and `col` are 1-based; `col`
+/// counts characters, not bytes. `offset` is where the word starts in the
+/// whole text, in UTF-16 code units (matching JS string indices).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Misspelling {
+    pub word: String,
+    pub line: usize,
+    pub col: usize,
+    pub offset: usize,
+    pub suggestions: Vec<String>,
+}
+
+// ── Dictionaries ──────────────────────────────────────────────────────────────
+
+fn parse_word_list(content: &str) -> impl Iterator<Item = String> + '_ {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(str::to_lowercase)
+}
+
+fn bundled(lang: &str) -> Result<&'static HashSet<String>, String> {
+    static EN: OnceLock<HashSet<String>> = OnceLock::new();
+    match lang.to_lowercase().replace('_', "-").as_str() {
+        "en" | "en-us" | "en-gb" => Ok(EN.get_or_init(|| parse_word_list(EN_WORDS).collect())),
+        other => Err(format!("No dictionary for language '{}'", other)),
+    }
+}
+
+/// Read a custom word list, treating a missing file as empty.
+pub fn load_word_list(path: &Path) -> HashSet<String> {
+    std::fs::read_to_string(path)
+        .map(|c| parse_word_list(&c).collect())
+        .unwrap_or_default()
+}
+
+fn global_dict_path(app: &tauri::AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|d| d.join(GLOBAL_DICT_NAME))
+}
+
+/// Append `word` to the list at `path` unless it's already there (ignoring case).
+pub fn append_word(path: &Path, word: &str) -> Result<(), String> {
+    let word = word.trim();
+    if word.is_empty() || word.chars().any(char::is_whitespace) {
+        return Err(format!("'{}' is not a single word", word));
+    }
+    if load_word_list(path).contains(&word.to_lowercase()) {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let existing = std::fs::read_to_string(path).unwrap_or_default();
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Cannot open {}: {}", path.display(), e))?;
+    // Don't glue the new word onto a last line that lacks a newline.
+    let sep = if existing.is_empty() || existing.ends_with('\n') { "" } else { "\n" };
+    writeln!(file, "{}{}", sep, word).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+struct Checker<'a> {
+    base: &'a HashSet<String>,
+    extra: HashSet<String>,
+}
+
+impl Checker<'_> {
+    fn knows(&self, word: &str) -> bool {
+        let lower = word.to_lowercase().replace('’', "'");
+        let known = |w: &str| self.base.contains(w) || self.extra.contains(w);
+        // The word list only has possessives for some nouns.
+        known(&lower) || lower.strip_suffix("'s").is_some_and(known)
+    }
+
+    /// Known words one edit (delete, transpose, replace, insert) away.
+    fn suggest(&self, word: &str) -> Vec<String> {
+        let lower: Vec<char> = word.to_lowercase().chars().collect();
+        let mut out: Vec<String> = Vec::new();
+        let mut push = |cand: Vec<char>| {
+            let cand: String = cand.into_iter().collect();
+            if out.len() < MAX_SUGGESTIONS && !out.contains(&cand) && self.knows(&cand) {
+                out.push(cand);
+            }
+        };
+        for i in 0..lower.len() {
+            if i + 1 < lower.len() {
+                let mut t = lower.clone();
+                t.swap(i, i + 1);
+                push(t);
+            }
+            let mut d = lower.clone();
+            d.remove(i);
+            push(d);
+        }
+        for i in 0..=lower.len() {
+            for c in 'a'..='z' {
+                if i < lower.len() {
+                    let mut r = lower.clone();
+                    r[i] = c;
+                    push(r);
+                }
+                let mut ins = lower.clone();
+                ins.insert(i, c);
+                push(ins);
+            }
+        }
+        out
+    }
+}
+
+// ── Text scanning ─────────────────────────────────────────────────────────────
+
+/// Number of leading lines taken up by a `---` front-matter block.
+fn front_matter_lines(lines: &[&str]) -> usize {
+    if lines.first().map(|l| l.trim_end()) != Some("---") {
+        return 0;
+    }
+    lines
+        .iter()
+        .skip(1)
+        .position(|l| matches!(l.trim_end(), "---" | "..."))
+        .map(|i| i + 2)
+        .unwrap_or(0)
+}
+
+fn fence_marker(line: &str) -> Option<&'static str> {
+    let t = line.trim_start();
+    if t.starts_with("```") {
+        Some("```")
+    } else if t.starts_with("~~~") {
+        Some("~~~")
+    } else {
+        None
+    }
+}
+
+/// Blank out inline code spans, URLs and link targets so their contents are
+/// never treated as prose. Character positions are preserved.
+fn mask_line(line: &str) -> Vec<char> {
+    let mut chars: Vec<char> = line.chars().collect();
+    let blank = |span: &mut [char]| span.fill(' ');
+
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '`' {
+            let ticks = chars[i..].iter().take_while(|&&c| c == '`').count();
+            let close = (i + ticks..chars.len()).find(|&j| {
+                chars[j..].iter().take_while(|&&c| c == '`').count() == ticks
+            });
+            let end = close.map(|j| j + ticks).unwrap_or(chars.len());
+            blank(&mut chars[i..end]);
+            i = end;
+        } else if chars[i] == ']' && chars.get(i + 1) == Some(&'(') {
+            let end = (i + 2..chars.len())
+                .find(|&j| chars[j] == ')')
+                .map(|j| j + 1)
+                .unwrap_or(chars.len());
+            blank(&mut chars[i + 1..end]);
+            i = end;
+        } else if chars[i] == '<' {
+            // Autolinks like <https://example.com>.
+            match (i + 1..chars.len()).find(|&j| chars[j] == '>' || chars[j] == ' ') {
+                Some(j) if chars[j] == '>' && chars[i + 1..j].contains(&':') => {
+                    blank(&mut chars[i..=j]);
+                    i = j + 1;
+                }
+                _ => i += 1,
+            }
+        } else {
+            let rest: String = chars[i..].iter().take(8).collect();
+            if rest.starts_with("http://") || rest.starts_with("https://") || rest.starts_with("www.") {
+                let end = (i..chars.len())
+                    .find(|&j| chars[j].is_whitespace())
+                    .unwrap_or(chars.len());
+                blank(&mut chars[i..end]);
+                i = end;
+            } else {
+                i += 1;
+            }
+        }
+    }
+    chars
+}
+
+/// Words in a masked line as `(0-based char column, word)`. Apostrophes,
+/// straight or curly, inside a word are kept (`don't`); leading/trailing
+/// ones are not.
+fn words(chars: &[char]) -> Vec<(usize, String)> {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if !chars[i].is_alphanumeric() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len()
+            && (chars[i].is_alphanumeric()
+                || (matches!(chars[i], '\'' | '’') && chars.get(i + 1).is_some_and(|c| c.is_alphabetic())))
+        {
+            i += 1;
+        }
+        out.push((start, chars[start..i].iter().collect()));
+    }
+    out
+}
+
+fn check_with(text: &str, checker: &Checker) -> Vec<Misspelling> {
+    let lines: Vec<&str> = text.lines().collect();
+    let line_starts: Vec<usize> = text
+        .split_inclusive('\n')
+        .scan(0, |start, line| {
+            let this = *start;
+            *start += line.encode_utf16().count();
+            Some(this)
+        })
+        .collect();
+    let mut out = Vec::new();
+    let mut fence: Option<&str> = None;
+
+    for (idx, line) in lines.iter().enumerate().skip(front_matter_lines(&lines)) {
+        if let Some(open) = fence {
+            if line.trim_start().starts_with(open) {
+                fence = None;
+            }
+            continue;
+        }
+        if let Some(marker) = fence_marker(line) {
+            fence = Some(marker);
+            continue;
+        }
+        for (col, word) in words(&mask_line(line)) {
+            // Single letters, numbers and identifiers with digits aren't prose.
+            if word.chars().count() < 2 || word.chars().any(|c| c.is_ascii_digit()) {
+                continue;
+            }
+            if !checker.knows(&word) {
+                let in_line: usize = line.chars().take(col).map(char::len_utf16).sum();
+                out.push(Misspelling {
+                    suggestions: checker.suggest(&word),
+                    word,
+                    line: idx + 1,
+                    col: col + 1,
+                    offset: line_starts[idx] + in_line,
+                });
+            }
+        }
+    }
+    out
+}
+
+/// Check `text` against the bundled dictionary for `lang` plus any extra
+/// words (e.g. from a folder's `.updown-dict`).
+pub fn check_text(text: &str, lang: &str, extra: HashSet<String>) -> Result<Vec<Misspelling>, String> {
+    let checker = Checker { base: bundled(lang)?, extra };
+    Ok(check_with(text, &checker))
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// Report misspelled words in `text`, skipping front matter, code and URLs.
+/// Words in the global custom dictionary and, when `folder` is given, its
+/// `.updown-dict` count as correct.
+#[tauri::command]
+pub fn spellcheck(
+    app: tauri::AppHandle,
+    text: String,
+    lang: String,
+    folder: Option<String>,
+) -> Result<Vec<Misspelling>, String> {
+    let mut extra = global_dict_path(&app)
+        .map(|p| load_word_list(&p))
+        .unwrap_or_default();
+    if let Some(folder) = folder {
+        extra.extend(load_word_list(&Path::new(&folder).join(FOLDER_DICT_NAME)));
+    }
+    check_text(&text, &lang, extra)
+}
+
+/// Add `word` to the folder's `.updown-dict`, or to the global custom
+/// dictionary when no folder is given.
+#[tauri::command]
+pub fn add_to_dictionary(app: tauri::AppHandle, folder: Option<String>, word: String) -> Result<(), String> {
+    let path = match folder {
+        Some(folder) => Path::new(&folder).join(FOLDER_DICT_NAME),
+        None => global_dict_path(&app).ok_or("Cannot locate the app data directory")?,
+    };
+    append_word(&path, &word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn misspelling_gets_suggestions() {
+        let hits = check_text("Open the 🙂\r\nfile in teh editor", "en", HashSet::new()).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].word, "teh");
+        assert_eq!((hits[0].line, hits[0].col, hits[0].offset), (2, 9, 21));
+        assert!(hits[0].suggestions.contains(&"the".to_string()));
+    }
+
+    #[test]
+    fn ordinary_prose_has_no_misspellings() {
+        let text = "# Meeting notes\n\nWe opened the files she'd shared and reviewed the drafts. The editor's \
+                    autosave kept running while everyone was typing, so nothing was lost. Tomorrow we’ll \
+                    organize the remaining folders, rename older documents and publish the updated guides.\n";
+        assert_eq!(check_text(text, "en", HashSet::new()).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn code_urls_and_front_matter_are_skipped() {
+        let text = "---\ntitle: Zzqx\n---\nRun `fooqux` now, see https://exmple.com/wrd\n\n```\nqwzzy\n```\n";
+        assert!(check_text(text, "en", HashSet::new()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn added_word_is_no_longer_flagged() {
+        let dir = tempfile::tempdir().unwrap();
+        let dict = dir.path().join(FOLDER_DICT_NAME);
+        assert_eq!(check_text("Open updown", "en", load_word_list(&dict)).unwrap().len(), 1);
+
+        append_word(&dict, "UpDown").unwrap();
+        append_word(&dict, "updown").unwrap();
+        assert_eq!(std::fs::read_to_string(&dict).unwrap(), "UpDown\n");
+        assert!(check_text("Open updown", "en", load_word_list(&dict)).unwrap().is_empty());
+    }
+}
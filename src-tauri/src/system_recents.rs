@@ -0,0 +1,96 @@
+use std::sync::Mutex;
+
+use crate::lock::LockExt;
+
+/// Recent paths last handed to the OS, most recent first.
+static NOTED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// The paths in front of the previously newest one, i.e. those opened
+/// since the last sync, oldest first so the newest is noted last.
+#[cfg(any(target_os = "windows", test))]
+fn opened_since<'a>(previous: &[String], paths: &'a [String]) -> Vec<&'a String> {
+    let mut fresh: Vec<&String> = paths.iter().take_while(|p| previous.first() != Some(*p)).collect();
+    fresh.reverse();
+    fresh
+}
+
+/// macOS: the Dock menu lists `NSDocumentController`'s recent documents,
+/// which we replace wholesale so removals and "Clear Recent Items" carry
+/// over.
+#[cfg(target_os = "macos")]
+mod platform {
+    use objc2::MainThreadMarker;
+    use objc2_app_kit::NSDocumentController;
+    use objc2_foundation::{NSString, NSURL};
+
+    pub fn note(_previous: &[String], paths: &[String]) {
+        let Some(mtm) = MainThreadMarker::new() else { return };
+        let controller = NSDocumentController::sharedDocumentController(mtm);
+        // SAFETY: main thread (checked above); `None` is a valid sender.
+        unsafe {
+            controller.clearRecentDocuments(None);
+            for path in paths.iter().rev() {
+                controller.noteNewRecentDocumentURL(&NSURL::fileURLWithPath(&NSString::from_str(path)));
+            }
+        }
+    }
+}
+
+/// Windows: files passed to `SHAddToRecentDocs` fill the Recent category
+/// of our taskbar Jump List. Windows owns that list, so only newly opened
+/// files are added; there is no way to drop just ours.
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::ffi::c_void;
+    use std::os::windows::ffi::OsStrExt;
+
+    const SHARD_PATHW: u32 = 3;
+
+    #[link(name = "shell32")]
+    extern "system" {
+        fn SHAddToRecentDocs(flags: u32, pv: *const c_void);
+    }
+
+    pub fn note(previous: &[String], paths: &[String]) {
+        for path in super::opened_since(previous, paths) {
+            let wide: Vec<u16> = std::ffi::OsStr::new(path).encode_wide().chain(Some(0)).collect();
+            unsafe { SHAddToRecentDocs(SHARD_PATHW, wide.as_ptr().cast()) };
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    pub fn note(_previous: &[String], _paths: &[String]) {}
+}
+
+/// Mirror the recent files (most recent first) into the Dock menu or
+/// Jump List. Called from `rebuild_recent_menu`.
+pub fn sync(app: &tauri::AppHandle, paths: Vec<String>) {
+    let previous = {
+        let mut noted = NOTED.lock_or_recover();
+        if *noted == paths {
+            return;
+        }
+        std::mem::replace(&mut *noted, paths.clone())
+    };
+    // AppKit's document controller is main-thread only.
+    let _ = app.run_on_main_thread(move || platform::note(&previous, &paths));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_files_opened_since_the_last_sync_are_new() {
+        let paths = |list: &[&str]| list.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+        let before = paths(&["/b.md", "/a.md"]);
+        let after = paths(&["/c.md", "/a.md", "/b.md"]);
+        assert_eq!(opened_since(&before, &after), ["/a.md", "/c.md"]);
+        assert_eq!(opened_since(&[], &before), ["/a.md", "/b.md"]);
+        assert!(opened_since(&before, &before).is_empty());
+    }
+}
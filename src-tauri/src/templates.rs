@@ -0,0 +1,169 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::lock::LockExt;
+
+/// Written into an empty templates folder so the submenu isn't bare.
+const DEFAULT_TEMPLATES: &[(&str, &str)] = &[
+    (
+        "Meeting Notes.md",
+        "# {{title}}\n\n**Date:** {{date}}\n**Attendees:**\n\n## Agenda\n\n- \n\n## Notes\n\n## Action Items\n\n- [ ] \n",
+    ),
+    ("Daily Journal.md", "# {{date}}\n\n## Today\n\n- \n\n## Notes\n\n## Tomorrow\n\n- [ ] \n"),
+];
+
+/// Left in the templates folder once the defaults are written, so deleting
+/// them sticks.
+const SEEDED_MARKER: &str = ".seeded";
+
+/// The listing the "New from Template" submenu was last built from.
+static LISTED: Mutex<Option<Vec<TemplateInfo>>> = Mutex::new(None);
+
+/// A template in the templates folder.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TemplateInfo {
+    /// The file name; pass back to `new_from_template`.
+    pub id: String,
+    /// The file name without its extension, for menus.
+    pub name: String,
+}
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+pub fn templates_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|d| d.join("templates"))
+        .map_err(|e| format!("Cannot locate the app data directory: {}", e))
+}
+
+fn is_template(path: &Path) -> bool {
+    path.is_file() && path.extension().is_some_and(|e| e.eq_ignore_ascii_case("md"))
+}
+
+/// Create `dir` with the default templates the first time it is used.
+/// Templates the user deletes afterwards are not brought back.
+pub fn seed_defaults(dir: &Path) -> Result<(), String> {
+    let marker = dir.join(SEEDED_MARKER);
+    if marker.exists() {
+        return Ok(());
+    }
+    let has_templates = std::fs::read_dir(dir)
+        .is_ok_and(|mut entries| entries.any(|e| e.is_ok_and(|e| is_template(&e.path()))));
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    if !has_templates {
+        for (name, body) in DEFAULT_TEMPLATES {
+            let path = dir.join(name);
+            std::fs::write(&path, body).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        }
+    }
+    std::fs::write(&marker, "").map_err(|e| format!("Failed to write {}: {}", marker.display(), e))
+}
+
+/// Templates in `dir`, sorted by name.
+pub fn list_in(dir: &Path) -> Vec<TemplateInfo> {
+    let mut templates: Vec<TemplateInfo> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| is_template(p))
+                .filter_map(|p| {
+                    Some(TemplateInfo {
+                        id: p.file_name()?.to_string_lossy().to_string(),
+                        name: p.file_stem()?.to_string_lossy().to_string(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    templates.sort_by_key(|t| t.name.to_lowercase());
+    templates
+}
+
+/// Templates in `dir` if they differ from the listing last returned here,
+/// so the submenu is only rebuilt when the folder changed.
+pub fn changed_listing(dir: &Path) -> Option<Vec<TemplateInfo>> {
+    let templates = list_in(dir);
+    let mut listed = LISTED.lock_or_recover();
+    if listed.as_ref() == Some(&templates) {
+        return None;
+    }
+    *listed = Some(templates.clone());
+    Some(templates)
+}
+
+/// The body of template `id` in `dir`. Ids are bare file names, so nothing
+/// outside the folder can be read.
+pub fn read_in(dir: &Path, id: &str) -> Result<String, String> {
+    if id.is_empty() || id.contains(['/', '\\']) || id.starts_with('.') {
+        return Err(format!("Invalid template id: {}", id));
+    }
+    std::fs::read_to_string(dir.join(id)).map_err(|e| format!("Failed to read template {}: {}", id, e))
+}
+
+/// Expand `{{title}}`, `{{date}}` and `{{time}}` in `body`. Other
+/// placeholders are left for the user to fill in.
+pub fn fill(body: &str, title: &str, now: chrono::DateTime<chrono::Local>) -> String {
+    let vars = HashMap::from([
+        ("title".to_string(), title.to_string()),
+        ("time".to_string(), now.format("%H:%M").to_string()),
+    ]);
+    crate::snippets::expand(body, &vars, &now.format("%Y-%m-%d").to_string()).text
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// Templates available for File › New from Template.
+#[tauri::command]
+pub fn list_templates(app: tauri::AppHandle) -> Result<Vec<TemplateInfo>, String> {
+    Ok(list_in(&templates_dir(&app)?))
+}
+
+/// Contents for a new document started from `template_id`, with its
+/// placeholders filled in. `title` defaults to "Untitled".
+#[tauri::command]
+pub fn new_from_template(app: tauri::AppHandle, template_id: String, title: Option<String>) -> Result<String, String> {
+    let body = read_in(&templates_dir(&app)?, &template_id)?;
+    Ok(fill(&body, title.as_deref().unwrap_or("Untitled"), chrono::Local::now()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn defaults_seed_an_empty_folder_and_placeholders_expand() {
+        let dir = tempfile::tempdir().unwrap();
+        let templates = dir.path().join("templates");
+        seed_defaults(&templates).unwrap();
+        let listed = list_in(&templates);
+        assert_eq!(listed.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(), ["Daily Journal", "Meeting Notes"]);
+
+        std::fs::remove_file(templates.join("Daily Journal.md")).unwrap();
+        seed_defaults(&templates).unwrap();
+        assert_eq!(list_in(&templates).len(), 1);
+
+        assert!(changed_listing(&templates).is_some());
+        assert!(changed_listing(&templates).is_none());
+        std::fs::write(templates.join("Ideas.md"), "").unwrap();
+        assert_eq!(changed_listing(&templates).map(|l| l.len()), Some(2));
+
+        let now = chrono::Local.with_ymd_and_hms(2024, 3, 9, 14, 5, 0).unwrap();
+        let body = read_in(&templates, "Meeting Notes.md").unwrap();
+        let filled = fill(&body, "Standup", now);
+        assert!(filled.starts_with("# Standup\n\n**Date:** 2024-03-09\n"));
+        assert_eq!(fill("{{time}} {{who}}", "x", now), "14:05 {{who}}");
+        assert!(read_in(&templates, "../settings.json").is_err());
+
+        for template in list_in(&templates) {
+            std::fs::remove_file(templates.join(template.id)).unwrap();
+        }
+        seed_defaults(&templates).unwrap();
+        assert!(list_in(&templates).is_empty());
+    }
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+
+use crate::settings;
+
+/// The user's colour theme choice.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThemePreference {
+    /// Follow the OS appearance.
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl ThemePreference {
+    /// The theme forced on native windows; `None` lets the OS decide.
+    pub fn native(self) -> Option<tauri::Theme> {
+        match self {
+            ThemePreference::System => None,
+            ThemePreference::Light => Some(tauri::Theme::Light),
+            ThemePreference::Dark => Some(tauri::Theme::Dark),
+        }
+    }
+}
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// `"light"` or `"dark"`, the `theme-changed` payload.
+fn theme_name(theme: tauri::Theme) -> &'static str {
+    match theme {
+        tauri::Theme::Dark => "dark",
+        _ => "light",
+    }
+}
+
+pub fn load_theme(app: &tauri::AppHandle) -> ThemePreference {
+    settings::settings_path(app)
+        .map(|p| settings::load_settings(&p).theme)
+        .unwrap_or_default()
+}
+
+/// Give `window` the titlebar theme for `preference` and tell its frontend
+/// which theme is now in effect.
+fn apply_to_window(window: &tauri::WebviewWindow, preference: ThemePreference) {
+    let _ = window.set_theme(preference.native());
+    let theme = preference.native().or_else(|| window.theme().ok());
+    if let Some(theme) = theme {
+        let _ = window.emit_to(window.label(), "theme-changed", theme_name(theme));
+    }
+}
+
+/// Apply the saved theme to every open window. Called in `setup` before the
+/// main window is shown so it never flashes the wrong theme.
+pub fn apply_saved_theme(app: &tauri::AppHandle) {
+    let preference = load_theme(app);
+    for window in app.webview_windows().values() {
+        apply_to_window(window, preference);
+    }
+}
+
+/// The OS appearance changed. Windows following the system pass it on to
+/// their frontend; a forced theme ignores it.
+pub fn system_theme_changed(window: &tauri::Window, theme: tauri::Theme) {
+    if load_theme(window.app_handle()) == ThemePreference::System {
+        let _ = window.emit_to(window.label(), "theme-changed", theme_name(theme));
+    }
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn get_theme(app: tauri::AppHandle) -> ThemePreference {
+    load_theme(&app)
+}
+
+/// Save the theme choice and apply it to all windows.
+#[tauri::command]
+pub fn set_theme(app: tauri::AppHandle, theme: ThemePreference) -> Result<(), String> {
+    let path = settings::settings_path(&app)?;
+    let mut saved = settings::load_settings(&path);
+    saved.theme = theme;
+    settings::save_settings(&path, &saved)?;
+    for window in app.webview_windows().values() {
+        apply_to_window(window, theme);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preference_round_trips_through_settings() {
+        let settings: settings::Settings = serde_json::from_str(r#"{"theme": "Dark"}"#).unwrap();
+        assert_eq!(settings.theme, ThemePreference::Dark);
+        assert_eq!(settings.theme.native(), Some(tauri::Theme::Dark));
+        assert_eq!(settings::Settings::default().theme.native(), None);
+    }
+}
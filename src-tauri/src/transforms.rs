@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+
+/// Line-ending normalization applied on save.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum LineEnding {
+    /// Keep whatever the file on disk (or else the buffer) already uses.
+    #[default]
+    Preserve,
+    Lf,
+    Crlf,
+}
+
+/// Transforms applied to the buffer right before it is written.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SaveOpts {
+    pub line_ending: LineEnding,
+    pub ensure_trailing_newline: bool,
+    pub trim_trailing_whitespace: bool,
+}
+
+/// One contiguous changed region. Line numbers are 1-based; lines keep their
+/// line endings so that CRLF/LF changes are visible.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffHunk {
+    pub old_start: usize,
+    pub old_lines: Vec<String>,
+    pub new_start: usize,
+    pub new_lines: Vec<String>,
+}
+
+/// Beyond this many lines in the differing middle section we skip the LCS and
+/// report the whole middle as one replaced hunk.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+// ── Transforms ────────────────────────────────────────────────────────────────
+
+fn detect_line_ending(text: &str) -> LineEnding {
+    if text.contains("\r\n") {
+        LineEnding::Crlf
+    } else {
+        LineEnding::Lf
+    }
+}
+
+/// Apply the on-save transforms to `text`. `on_disk` is the current file
+/// content, used to resolve `LineEnding::Preserve`.
+pub fn apply_save_transforms(text: &str, opts: &SaveOpts, on_disk: Option<&str>) -> String {
+    let ending = match opts.line_ending {
+        LineEnding::Preserve => detect_line_ending(on_disk.unwrap_or(text)),
+        other => other,
+    };
+    let eol = if ending == LineEnding::Crlf { "\r\n" } else { "\n" };
+
+    let had_final_newline = text.ends_with('\n');
+    let mut lines: Vec<&str> = text.split('\n').collect();
+    if had_final_newline {
+        lines.pop();
+    }
+
+    let mut out = String::with_capacity(text.len() + lines.len());
+    for (i, line) in lines.iter().enumerate() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let line = if opts.trim_trailing_whitespace { line.trim_end() } else { line };
+        out.push_str(line);
+        let is_last = i + 1 == lines.len();
+        if !is_last || had_final_newline || opts.ensure_trailing_newline {
+            out.push_str(eol);
+        }
+    }
+    out
+}
+
+// ── Line diff ─────────────────────────────────────────────────────────────────
+
+/// Line-based diff of `old` → `new`. Common prefix/suffix are trimmed first so
+/// the LCS only runs over the region that actually changed.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffHunk> {
+    let a: Vec<&str> = old.split_inclusive('\n').collect();
+    let b: Vec<&str> = new.split_inclusive('\n').collect();
+
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let a_mid = &a[prefix..a.len() - suffix];
+    let b_mid = &b[prefix..b.len() - suffix];
+
+    if a_mid.is_empty() && b_mid.is_empty() {
+        return vec![];
+    }
+    if a_mid.len().saturating_mul(b_mid.len()) > MAX_DIFF_CELLS {
+        return vec![make_hunk(prefix, a_mid, prefix, b_mid)];
+    }
+
+    // LCS table over the middle section.
+    let (n, m) = (a_mid.len(), b_mid.len());
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if a_mid[i] == b_mid[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut hunks = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && a_mid[i] == b_mid[j] {
+            i += 1;
+            j += 1;
+            continue;
+        }
+        let (si, sj) = (i, j);
+        while (i < n || j < m) && !(i < n && j < m && a_mid[i] == b_mid[j]) {
+            if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+                j += 1;
+            } else {
+                i += 1;
+            }
+        }
+        hunks.push(make_hunk(prefix + si, &a_mid[si..i], prefix + sj, &b_mid[sj..j]));
+    }
+    hunks
+}
+
+fn make_hunk(old_idx: usize, old: &[&str], new_idx: usize, new: &[&str]) -> DiffHunk {
+    DiffHunk {
+        old_start: old_idx + 1,
+        old_lines: old.iter().map(|s| s.to_string()).collect(),
+        new_start: new_idx + 1,
+        new_lines: new.iter().map(|s| s.to_string()).collect(),
+    }
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// Dry run of a save: applies the on-save transforms in memory and returns
+/// how they would change `text`, without writing anything.
+#[tauri::command]
+pub fn save_preview(path: String, text: String, opts: SaveOpts) -> Result<Vec<DiffHunk>, String> {
+    let on_disk = match std::fs::read_to_string(&path) {
+        Ok(content) => Some(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(format!("Failed to read {}: {}", path, e)),
+    };
+    let transformed = apply_save_transforms(&text, &opts, on_disk.as_deref());
+    Ok(diff_lines(&text, &transformed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trailing_newline_fix_produces_single_hunk() {
+        let opts = SaveOpts { ensure_trailing_newline: true, ..Default::default() };
+        let text = "# Title\n\nbody";
+        let out = apply_save_transforms(text, &opts, None);
+        assert_eq!(out, "# Title\n\nbody\n");
+        assert_eq!(
+            diff_lines(text, &out),
+            vec![DiffHunk {
+                old_start: 3,
+                old_lines: vec!["body".into()],
+                new_start: 3,
+                new_lines: vec!["body\n".into()],
+            }]
+        );
+    }
+
+    #[test]
+    fn no_transforms_means_no_hunks() {
+        let text = "a\nb\n";
+        let out = apply_save_transforms(text, &SaveOpts::default(), None);
+        assert!(diff_lines(text, &out).is_empty());
+    }
+
+    #[test]
+    fn preserve_follows_line_endings_on_disk() {
+        let out = apply_save_transforms("a\nb\n", &SaveOpts::default(), Some("x\r\ny\r\n"));
+        assert_eq!(out, "a\r\nb\r\n");
+    }
+}
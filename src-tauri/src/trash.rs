@@ -0,0 +1,41 @@
+use std::path::Path;
+
+/// What the platform calls its trash, for error messages.
+#[cfg(target_os = "windows")]
+const TRASH: &str = "the Recycle Bin";
+#[cfg(target_os = "macos")]
+const TRASH: &str = "the Trash";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const TRASH: &str = "the trash";
+
+/// Move `path` to the OS trash / Recycle Bin so the deletion can be undone.
+/// Symlinks are trashed themselves, not their targets; on Linux files on
+/// other mounts go to that mount's trash.
+pub fn move_to_trash(path: &Path) -> Result<(), String> {
+    let display = path.display();
+    if path.symlink_metadata().is_err() {
+        return Err(format!("{} does not exist", display));
+    }
+
+    #[allow(unused_mut)]
+    let mut context = ::trash::TrashContext::new();
+    // Finder's "Put Back" would need the Automation permission prompt.
+    #[cfg(target_os = "macos")]
+    {
+        use ::trash::macos::{DeleteMethod, TrashContextExtMacos};
+        context.set_delete_method(DeleteMethod::NsFileManager);
+    }
+    context.delete(path).map_err(|e| format!("Failed to move {} to {}: {}", display, TRASH, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_files_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("gone.md");
+        assert_eq!(move_to_trash(&missing), Err(format!("{} does not exist", missing.display())));
+    }
+}
@@ -0,0 +1,148 @@
+use std::sync::Mutex;
+use tauri::menu::{MenuBuilder, MenuItem, PredefinedMenuItem, Submenu, SubmenuBuilder};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::Manager;
+
+use crate::lock::LockExt;
+use crate::{menu, settings, windows};
+
+const TRAY_ID: &str = "updown_tray";
+
+/// The tray's "Open Recent" submenu while the tray is shown, kept so
+/// `rebuild_recent` can repopulate it.
+#[derive(Default)]
+pub struct TrayRecent(pub Mutex<Option<Submenu<tauri::Wry>>>);
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+fn show_main_window(app: &tauri::AppHandle) -> Option<tauri::WebviewWindow> {
+    let window = app.get_webview_window(windows::MAIN_WINDOW)?;
+    let _ = window.show();
+    let _ = window.unminimize();
+    let _ = window.set_focus();
+    Some(window)
+}
+
+/// Repopulate the tray's "Open Recent" from `RecentFiles`, in the same
+/// order as the app menu. Called from `rebuild_recent_menu`.
+pub fn rebuild_recent(app: &tauri::AppHandle) {
+    let state = app.state::<TrayRecent>();
+    let submenu = state.0.lock_or_recover();
+    let Some(submenu) = submenu.as_ref() else { return };
+    if let Ok(items) = submenu.items() {
+        for item in items {
+            let _ = submenu.remove(&item);
+        }
+    }
+    let files = crate::recent_paths(app);
+    if files.is_empty() {
+        if let Ok(item) = MenuItem::with_id(app, "tray_no_recent", "No Recent Items", false, None::<&str>) {
+            let _ = submenu.append(&item);
+        }
+    }
+    for (i, label) in menu::recent_labels(&files).into_iter().enumerate() {
+        if let Ok(item) = MenuItem::with_id(app, format!("tray_recent_{i}"), label, true, None::<&str>) {
+            let _ = submenu.append(&item);
+        }
+    }
+}
+
+fn on_menu_event(app: &tauri::AppHandle, id: &str) {
+    match id {
+        "tray_show" => {
+            show_main_window(app);
+        }
+        "tray_new_note" => {
+            if let Some(window) = show_main_window(app) {
+                menu::send_action(&window, "newFile", vec![]);
+            }
+        }
+        // Goes through `ExitRequested`, so unsaved changes are still asked about.
+        "tray_quit" => app.exit(0),
+        id => {
+            let path = id
+                .strip_prefix("tray_recent_")
+                .and_then(|i| i.parse::<usize>().ok())
+                .and_then(|i| crate::recent_paths(app).get(i).cloned());
+            if let Some(path) = path {
+                crate::open_file_in_running_app(app, &path, true);
+            }
+        }
+    }
+}
+
+/// Add the tray icon if it isn't shown yet.
+fn show(app: &tauri::AppHandle) -> Result<(), String> {
+    if app.tray_by_id(TRAY_ID).is_some() {
+        return Ok(());
+    }
+    let build = || -> tauri::Result<Submenu<tauri::Wry>> {
+        let recent = SubmenuBuilder::new(app, "Open Recent").build()?;
+        let tray_menu = MenuBuilder::new(app)
+            .item(&MenuItem::with_id(app, "tray_show", "Show UpDown", true, None::<&str>)?)
+            .item(&MenuItem::with_id(app, "tray_new_note", "New Note", true, None::<&str>)?)
+            .item(&recent)
+            .item(&PredefinedMenuItem::separator(app)?)
+            .item(&MenuItem::with_id(app, "tray_quit", "Quit UpDown", true, None::<&str>)?)
+            .build()?;
+        let mut tray = TrayIconBuilder::with_id(TRAY_ID)
+            .tooltip("UpDown")
+            .menu(&tray_menu)
+            .show_menu_on_left_click(false)
+            .on_menu_event(|app, event| on_menu_event(app, event.id().0.as_str()))
+            .on_tray_icon_event(|tray, event| {
+                if let TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Up, .. } = event {
+                    show_main_window(tray.app_handle());
+                }
+            });
+        if let Some(icon) = app.default_window_icon() {
+            tray = tray.icon(icon.clone());
+        }
+        tray.build(app)?;
+        Ok(recent)
+    };
+    let recent = build().map_err(|e| format!("Failed to create the tray icon: {}", e))?;
+    *app.state::<TrayRecent>().0.lock_or_recover() = Some(recent);
+    rebuild_recent(app);
+    Ok(())
+}
+
+fn hide(app: &tauri::AppHandle) {
+    app.state::<TrayRecent>().0.lock_or_recover().take();
+    app.remove_tray_by_id(TRAY_ID);
+}
+
+/// Show the tray icon if the `show_tray_icon` setting is on. Called from
+/// `setup`.
+pub fn apply_saved(app: &tauri::AppHandle) {
+    let enabled = settings::settings_path(app)
+        .map(|p| settings::load_settings(&p).show_tray_icon)
+        .unwrap_or(false);
+    if enabled {
+        if let Err(e) = show(app) {
+            log::warn!("{}", e);
+        }
+    }
+}
+
+/// Show or remove the tray icon.
+pub fn apply(app: &tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    if enabled {
+        show(app)
+    } else {
+        hide(app);
+        Ok(())
+    }
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// Turn the tray icon on or off and remember the choice.
+#[tauri::command]
+pub fn set_tray_enabled(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    apply(&app, enabled)?;
+    let path = settings::settings_path(&app)?;
+    let mut saved = settings::load_settings(&path);
+    saved.show_tray_icon = enabled;
+    settings::save_settings(&path, &saved)
+}
@@ -0,0 +1,259 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::vault;
+
+/// How deep `list_tree` descends below the root.
+const MAX_TREE_DEPTH: usize = 16;
+
+/// Most entries `list_tree` returns before giving up on the rest.
+const MAX_TREE_NODES: usize = 5000;
+
+/// A directory or markdown file in `list_tree`'s result.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TreeNode {
+    pub name: String,
+    pub path: String,
+    pub is_dir: bool,
+    pub children: Vec<TreeNode>,
+    /// Some of this directory's entries were left out by the depth or size
+    /// limit.
+    pub truncated: bool,
+}
+
+// ── Whole tree ────────────────────────────────────────────────────────────────
+
+/// A walk of `dir` in folder-panel order: directories first, then files,
+/// each sorted case-insensitively. Dot entries are always skipped; with
+/// `respect_gitignore`, so is everything git ignores (`.gitignore` files
+/// here and above, `.git/info/exclude` and the global excludes file).
+fn walker(dir: &Path, respect_gitignore: bool) -> ignore::WalkBuilder {
+    let mut builder = ignore::WalkBuilder::new(dir);
+    builder
+        .standard_filters(false)
+        .hidden(true)
+        .parents(respect_gitignore)
+        .git_ignore(respect_gitignore)
+        .git_exclude(respect_gitignore)
+        .git_global(respect_gitignore)
+        .sort_by_file_path(|a, b| {
+            let is_dir = |p: &Path| p.symlink_metadata().is_ok_and(|m| m.is_dir());
+            let key = |p: &Path| p.file_name().map(|n| n.to_string_lossy().to_lowercase());
+            is_dir(b).cmp(&is_dir(a)).then_with(|| key(a).cmp(&key(b)))
+        });
+    builder
+}
+
+/// A directory, or a markdown file; anything else the panel leaves out.
+fn is_listed(entry: &ignore::DirEntry) -> bool {
+    match entry.file_type() {
+        Some(t) if t.is_dir() => true,
+        Some(t) if t.is_file() => vault::is_markdown(entry.path()),
+        _ => false,
+    }
+}
+
+/// The entries directly inside `dir`, exactly as `build_tree` lists them.
+fn listing(dir: &Path, respect_gitignore: bool) -> Vec<PathBuf> {
+    let walk = walker(dir, respect_gitignore).max_depth(Some(1)).build();
+    walk.flatten().filter(|e| e.depth() == 1 && is_listed(e)).map(|e| e.into_path()).collect()
+}
+
+fn tree_node(path: &Path, is_dir: bool) -> TreeNode {
+    TreeNode {
+        name: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+        path: path.to_string_lossy().to_string(),
+        is_dir,
+        children: Vec::new(),
+        truncated: false,
+    }
+}
+
+/// Every directory and markdown file under `root`, nested, in folder-panel
+/// order. Directories past `MAX_TREE_DEPTH`, and entries past
+/// `MAX_TREE_NODES`, are left out with `truncated` set on their parent.
+pub fn build_tree(root: &Path, respect_gitignore: bool) -> TreeNode {
+    // The directories being filled, outermost first; `open[d]` is at depth d.
+    let mut open = vec![tree_node(root, true)];
+    let close = |open: &mut Vec<TreeNode>| {
+        let done = open.pop().expect("the root is never closed here");
+        open.last_mut().expect("closed directories have a parent").children.push(done);
+    };
+    let walk = walker(root, respect_gitignore).max_depth(Some(MAX_TREE_DEPTH + 1)).build();
+    let mut nodes = 0;
+    for entry in walk.flatten().filter(|e| e.depth() > 0 && is_listed(e)) {
+        while open.len() > entry.depth() {
+            close(&mut open);
+        }
+        let parent = open.last_mut().expect("the root stays open");
+        if entry.depth() > MAX_TREE_DEPTH || nodes >= MAX_TREE_NODES {
+            parent.truncated = true;
+            if nodes >= MAX_TREE_NODES {
+                break;
+            }
+            continue;
+        }
+        nodes += 1;
+        let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+        let node = tree_node(entry.path(), is_dir);
+        if is_dir {
+            open.push(node);
+        } else {
+            parent.children.push(node);
+        }
+    }
+    while open.len() > 1 {
+        close(&mut open);
+    }
+    open.pop().expect("the root stays open")
+}
+
+// ── Navigation ────────────────────────────────────────────────────────────────
+
+/// Where keyboard navigation from `current` lands, over the same entries
+/// `list_tree` shows. `next`/`prev` move among siblings and wrap around;
+/// `parent` stops at `root`; `child` enters a non-empty directory at its
+/// first entry.
+pub fn navigate(root: &Path, current: &Path, dir: &str, respect_gitignore: bool) -> Result<Option<PathBuf>, String> {
+    if !current.starts_with(root) {
+        return Err(format!("{} is not inside {}", current.display(), root.display()));
+    }
+    let parent = current.parent().filter(|_| current != root);
+    Ok(match dir {
+        "next" | "prev" => {
+            let Some(parent) = parent else { return Ok(None) };
+            let siblings = listing(parent, respect_gitignore);
+            let Some(i) = siblings.iter().position(|p| p == current) else { return Ok(None) };
+            let len = siblings.len();
+            let j = if dir == "next" { (i + 1) % len } else { (i + len - 1) % len };
+            Some(siblings[j].clone())
+        }
+        "parent" => parent.map(Path::to_path_buf),
+        "child" if current.is_dir() => listing(current, respect_gitignore).into_iter().next(),
+        "child" => None,
+        other => {
+            return Err(format!("Unknown direction '{}'; expected next, prev, parent or child", other))
+        }
+    })
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// Folder-panel keyboard navigation: the path reached from `current` in
+/// direction `dir` (`next|prev|parent|child`), or `None` at a boundary.
+/// Pass the `respect_gitignore` the panel was listed with.
+#[tauri::command]
+pub fn tree_navigate(
+    root: String,
+    current: String,
+    dir: String,
+    respect_gitignore: bool,
+) -> Result<Option<String>, String> {
+    let target = navigate(Path::new(&root), Path::new(&current), &dir, respect_gitignore)?;
+    Ok(target.map(|p| p.to_string_lossy().to_string()))
+}
+
+/// The project under `root` for the folder panel, optionally honouring
+/// `.gitignore` files. Large trees come back cut short with `truncated` set.
+#[tauri::command]
+pub fn list_tree(root: String, respect_gitignore: bool) -> Result<TreeNode, String> {
+    let path = Path::new(&root);
+    if !path.is_dir() {
+        return Err(format!("Failed to list {}: not a folder", root));
+    }
+    Ok(build_tree(path, respect_gitignore))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("Notes/empty")).unwrap();
+        std::fs::write(root.join("Notes/x.md"), "").unwrap();
+        std::fs::create_dir(root.join(".git")).unwrap();
+        std::fs::write(root.join("b.md"), "").unwrap();
+        std::fs::write(root.join("A.md"), "").unwrap();
+        std::fs::write(root.join("image.png"), "").unwrap();
+        dir
+    }
+
+    fn nav(root: &Path, current: &str, dir: &str) -> Option<PathBuf> {
+        navigate(root, &root.join(current), dir, false).unwrap()
+    }
+
+    #[test]
+    fn next_and_prev_wrap_among_siblings() {
+        let dir = fixture();
+        let root = dir.path();
+        assert_eq!(nav(root, "Notes", "next"), Some(root.join("A.md")));
+        assert_eq!(nav(root, "A.md", "next"), Some(root.join("b.md")));
+        assert_eq!(nav(root, "b.md", "next"), Some(root.join("Notes")));
+        assert_eq!(nav(root, "Notes", "prev"), Some(root.join("b.md")));
+    }
+
+    #[test]
+    fn navigation_visits_what_the_tree_lists() {
+        let dir = fixture();
+        let root = dir.path();
+        std::fs::create_dir(root.join("node_modules")).unwrap();
+        std::fs::write(root.join(".gitignore"), "b.md\n").unwrap();
+        assert_eq!(nav(root, "A.md", "next"), Some(root.join("b.md")));
+        assert_eq!(nav(root, "A.md", "prev"), Some(root.join("Notes")));
+        assert_eq!(nav(root, "Notes", "prev"), Some(root.join("node_modules")));
+
+        let step = |from: &str, dir: &str| navigate(root, &root.join(from), dir, true).unwrap();
+        assert_eq!(step("A.md", "next"), Some(root.join("node_modules")));
+        assert_eq!(step("node_modules", "prev"), Some(root.join("A.md")));
+    }
+
+    #[test]
+    fn tree_lists_directories_first_and_can_honour_gitignore() {
+        let dir = fixture();
+        let root = dir.path();
+        std::fs::create_dir(root.join("build")).unwrap();
+        std::fs::write(root.join("build/out.md"), "").unwrap();
+        std::fs::write(root.join(".gitignore"), "build/\nb.md\n").unwrap();
+
+        let names = |node: &TreeNode| node.children.iter().map(|c| c.name.clone()).collect::<Vec<_>>();
+        let tree = build_tree(root, false);
+        assert_eq!(names(&tree), vec!["build", "Notes", "A.md", "b.md"]);
+        assert_eq!(names(&tree.children[1]), vec!["empty", "x.md"]);
+        assert!(!tree.truncated);
+
+        let tree = build_tree(root, true);
+        assert_eq!(names(&tree), vec!["Notes", "A.md"]);
+    }
+
+    #[test]
+    fn gitignore_follows_git() {
+        let dir = fixture();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join(".git/info")).unwrap();
+        std::fs::write(root.join(".git/info/exclude"), "A.md\n").unwrap();
+        std::fs::write(root.join(".gitignore"), "*.[oa].md\n").unwrap();
+        std::fs::write(root.join("Notes/draft.o.md"), "").unwrap();
+        std::fs::write(root.join("Notes/draft.x.md"), "").unwrap();
+
+        let names = |node: &TreeNode| node.children.iter().map(|c| c.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&build_tree(root, true)), vec!["Notes", "b.md"]);
+        // Rooted below the repository, the parent's rules still apply.
+        let notes = build_tree(&root.join("Notes"), true);
+        assert_eq!(names(&notes), vec!["empty", "draft.x.md", "x.md"]);
+        assert_eq!(names(&build_tree(&root.join("Notes"), false)), vec!["empty", "draft.o.md", "draft.x.md", "x.md"]);
+    }
+
+    #[test]
+    fn parent_and_child_stop_at_boundaries() {
+        let dir = fixture();
+        let root = dir.path();
+        assert_eq!(nav(root, "Notes/x.md", "parent"), Some(root.join("Notes")));
+        assert_eq!(navigate(root, root, "parent", false).unwrap(), None);
+        assert_eq!(nav(root, "Notes", "child"), Some(root.join("Notes/empty")));
+        assert_eq!(nav(root, "Notes/empty", "child"), None);
+        assert_eq!(nav(root, "A.md", "child"), None);
+        assert!(navigate(root, root, "sideways", false).is_err());
+    }
+}
@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+
+use crate::settings;
+
+/// Latest published release, used unless the `update_feed_url` setting
+/// names another manifest.
+const DEFAULT_FEED: &str = "https://api.github.com/repos/neshkoli/updown/releases/latest";
+
+/// Give up on the feed after this long.
+const TIMEOUT_SECS: &str = "10";
+
+const OFFLINE: &str = "Could not reach the update server. Check your internet connection and try again.";
+
+/// Installer file suffixes for this platform, most preferred first.
+#[cfg(target_os = "macos")]
+const INSTALLER_SUFFIXES: &[&str] = &[".dmg"];
+#[cfg(target_os = "windows")]
+const INSTALLER_SUFFIXES: &[&str] = &["-setup.exe", ".msi"];
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const INSTALLER_SUFFIXES: &[&str] = &[".AppImage", ".deb", ".rpm"];
+
+/// Result of `check_for_updates`. `download_url` is this platform's
+/// installer when the release has one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UpdateInfo {
+    pub update_available: bool,
+    pub latest_version: String,
+    pub notes_url: Option<String>,
+    pub download_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// The two manifest shapes we read: a GitHub release, or a custom feed's
+/// `{ "version", "notesUrl", "downloadUrl" }`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Feed {
+    Github {
+        tag_name: String,
+        html_url: String,
+        #[serde(default)]
+        assets: Vec<GithubAsset>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Custom {
+        version: String,
+        notes_url: Option<String>,
+        download_url: Option<String>,
+    },
+}
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// `v2.1`, `2.1.0` and ` 2.1.0 ` all parse; a missing patch number is 0.
+fn parse_version(tag: &str) -> Result<semver::Version, String> {
+    let bare = tag.trim().trim_start_matches(['v', 'V']);
+    let padded = if bare.matches('.').count() == 1 { format!("{}.0", bare) } else { bare.to_string() };
+    semver::Version::parse(&padded).map_err(|e| format!("The update feed has an invalid version '{}': {}", tag, e))
+}
+
+/// Names this CPU goes by in release file names.
+fn arch_aliases() -> &'static [&'static str] {
+    match std::env::consts::ARCH {
+        "aarch64" => &["aarch64", "arm64"],
+        "x86_64" => &["x64", "x86_64", "amd64"],
+        _ => &[],
+    }
+}
+
+/// The installer to offer: by suffix preference, favouring a build for
+/// this CPU among files with the same suffix.
+fn pick_installer(assets: &[GithubAsset], suffixes: &[&str], arches: &[&str]) -> Option<String> {
+    suffixes.iter().find_map(|suffix| {
+        let matching: Vec<&GithubAsset> = assets.iter().filter(|a| a.name.ends_with(suffix)).collect();
+        matching
+            .iter()
+            .find(|a| arches.iter().any(|arch| a.name.contains(arch)))
+            .or(matching.first())
+            .map(|a| a.browser_download_url.clone())
+    })
+}
+
+/// Compare the release described by `manifest` with `current`.
+fn compare(current: &semver::Version, manifest: &str) -> Result<UpdateInfo, String> {
+    let feed: Feed =
+        serde_json::from_str(manifest).map_err(|e| format!("The update feed could not be read: {}", e))?;
+    let (version, notes_url, download_url) = match feed {
+        Feed::Github { tag_name, html_url, assets } => {
+            let download = pick_installer(&assets, INSTALLER_SUFFIXES, arch_aliases());
+            (tag_name, Some(html_url), download)
+        }
+        Feed::Custom { version, notes_url, download_url } => (version, notes_url, download_url),
+    };
+    let latest = parse_version(&version)?;
+    Ok(UpdateInfo { update_available: latest > *current, latest_version: latest.to_string(), notes_url, download_url })
+}
+
+/// GET `url` with curl, which ships with macOS, Windows 10+ and desktop
+/// Linux, so the app needs no TLS stack of its own.
+fn fetch(url: &str, user_agent: &str) -> Result<String, String> {
+    let output = std::process::Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--location", "--max-time", TIMEOUT_SECS])
+        .args(["--user-agent", user_agent, "--header", "Accept: application/vnd.github+json", url])
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => "Checking for updates needs curl, which was not found".to_string(),
+            _ => format!("Failed to run curl: {}", e),
+        })?;
+    match output.status.code() {
+        Some(0) => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+        // Couldn't resolve, couldn't connect, timed out, TLS handshake failed.
+        Some(6 | 7 | 28 | 35) => Err(OFFLINE.to_string()),
+        _ => Err(format!("The update server returned an error: {}", String::from_utf8_lossy(&output.stderr).trim())),
+    }
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// Fetch the release feed and compare its version with the running app's.
+#[tauri::command]
+pub async fn check_for_updates(app: tauri::AppHandle) -> Result<UpdateInfo, String> {
+    let feed = settings::settings_path(&app)
+        .ok()
+        .and_then(|p| settings::load_settings(&p).update_feed_url)
+        .filter(|url| !url.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_FEED.to_string());
+    let current = app.package_info().version.clone();
+    let user_agent = format!("UpDown/{}", current);
+    let manifest = tauri::async_runtime::spawn_blocking(move || fetch(&feed, &user_agent))
+        .await
+        .map_err(|e| format!("Update check failed: {}", e))??;
+    compare(&current, &manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn feeds_are_compared_with_the_running_version() {
+        let current = semver::Version::new(2, 1, 0);
+        let github = r#"{"tag_name": "v2.2", "html_url": "https://example.com/r/2.2", "assets": [
+            {"name": "UpDown_2.2.0_x64.dmg", "browser_download_url": "https://example.com/x64.dmg"},
+            {"name": "UpDown_2.2.0_aarch64.dmg", "browser_download_url": "https://example.com/arm.dmg"}]}"#;
+        let info = compare(&current, github).unwrap();
+        assert!(info.update_available);
+        assert_eq!(info.latest_version, "2.2.0");
+        assert_eq!(info.notes_url.as_deref(), Some("https://example.com/r/2.2"));
+
+        let assets: Feed = serde_json::from_str(github).unwrap();
+        let Feed::Github { assets, .. } = assets else { panic!("not a GitHub release") };
+        assert_eq!(pick_installer(&assets, &[".dmg"], &["aarch64"]).as_deref(), Some("https://example.com/arm.dmg"));
+        assert_eq!(pick_installer(&assets, &[".dmg"], &[]).as_deref(), Some("https://example.com/x64.dmg"));
+        assert_eq!(pick_installer(&assets, &[".msi"], &["x64"]), None);
+
+        let custom = compare(&current, r#"{"version": "2.1.0", "downloadUrl": "https://example.com/dl"}"#).unwrap();
+        assert!(!custom.update_available);
+        assert_eq!(custom.download_url.as_deref(), Some("https://example.com/dl"));
+        assert!(compare(&current, r#"{"version": "soon"}"#).is_err());
+    }
+}
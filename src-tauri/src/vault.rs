@@ -0,0 +1,937 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+use crate::analysis;
+use crate::lock::LockExt;
+
+/// Note excluded from `find_orphans` unless another name is given.
+const DEFAULT_HOME_NOTE: &str = "index";
+
+/// Directories never descended into.
+const SKIP_DIRS: &[&str] = &["node_modules", "target"];
+
+/// One file's requested front-matter fields, flattened next to its path.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FrontMatterRow {
+    pub path: String,
+    #[serde(flatten)]
+    pub fields: BTreeMap<String, Value>,
+}
+
+/// Outcome of `rename_with_link_updates`. `edited_files` lists the files whose
+/// links were (or, on a dry run, would be) rewritten.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RenameReport {
+    pub old_path: String,
+    pub new_path: String,
+    pub edited_files: Vec<String>,
+    pub applied: bool,
+}
+
+/// Options for `batch_rename`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct BatchRenameOpts {
+    /// Rename on disk; otherwise only preview the old → new pairs.
+    pub apply: bool,
+    pub case_insensitive: bool,
+    /// Include files in subfolders, not just `root` itself.
+    pub recursive: bool,
+}
+
+// ── Walking ───────────────────────────────────────────────────────────────────
+
+/// Whether `path` has one of the user's openable extensions.
+pub fn is_markdown(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(crate::settings::is_openable_extension)
+}
+
+pub fn is_skipped_dir(name: &str) -> bool {
+    name.starts_with('.') || SKIP_DIRS.contains(&name)
+}
+
+/// Every markdown file under `root`, sorted, skipping dot-directories and
+/// dependency folders.
+pub fn markdown_files(root: &Path) -> Vec<PathBuf> {
+    let mut out = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            match entry.file_type() {
+                Ok(t) if t.is_dir() && !is_skipped_dir(&name) => stack.push(path),
+                Ok(t) if t.is_file() && is_markdown(&path) => out.push(path),
+                _ => {}
+            }
+        }
+    }
+    out.sort();
+    out
+}
+
+/// Apply `f` to every item across a few worker threads, keeping input order.
+pub fn parallel_map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let workers = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+    let chunk = items.len().div_ceil(workers).max(1);
+    std::thread::scope(|s| {
+        let handles: Vec<_> = items
+            .chunks(chunk)
+            .map(|part| s.spawn(|| part.iter().map(&f).collect::<Vec<R>>()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("worker thread panicked"))
+            .collect()
+    })
+}
+
+// ── Front matter table ────────────────────────────────────────────────────────
+
+pub fn front_matter_row(path: &Path, fields: &[String]) -> FrontMatterRow {
+    let text = std::fs::read_to_string(path).unwrap_or_default();
+    let (raw, _, _) = analysis::split_front_matter(&text);
+    let mut fm = raw.map(analysis::parse_front_matter).unwrap_or_default();
+    FrontMatterRow {
+        path: path.to_string_lossy().to_string(),
+        fields: fields
+            .iter()
+            .map(|f| (f.clone(), fm.remove(f).unwrap_or(Value::Null)))
+            .collect(),
+    }
+}
+
+// ── Link rewriting ────────────────────────────────────────────────────────────
+
+/// Resolve `.` and `..` without touching the filesystem.
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for c in path.components() {
+        match c {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+/// Is `dest` a link to another local file (not a URL, anchor or absolute path)?
+pub fn is_relative_link(dest: &str) -> bool {
+    !(dest.is_empty()
+        || dest.starts_with('#')
+        || dest.starts_with('/')
+        || dest.contains("://")
+        || dest.starts_with("mailto:"))
+}
+
+/// `name` without a trailing `.md`, in any case.
+fn strip_md(name: &str) -> &str {
+    match name.len().checked_sub(3) {
+        Some(i) if name.is_char_boundary(i) && name[i..].eq_ignore_ascii_case(".md") => &name[..i],
+        _ => name,
+    }
+}
+
+/// Byte ranges of `text`'s code blocks and inline code spans, where link
+/// syntax is just text.
+fn code_ranges(text: &str) -> Vec<Range<usize>> {
+    pulldown_cmark::Parser::new(text)
+        .into_offset_iter()
+        .filter_map(|(event, range)| match event {
+            pulldown_cmark::Event::Start(pulldown_cmark::Tag::CodeBlock(_)) | pulldown_cmark::Event::Code(_) => {
+                Some(range)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn in_code(code: &[Range<usize>], offset: usize) -> bool {
+    code.iter().any(|r| r.contains(&offset))
+}
+
+/// Rewrite `[[old]]` wikilinks (keeping any `#heading` or `|alias`) to point
+/// at `new_stem`. Matching ignores case and an optional `.md` suffix; code
+/// is left alone.
+fn rewrite_wikilinks(text: &str, old_stem: &str, new_stem: &str) -> String {
+    let code = code_ranges(text);
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("[[") {
+        if in_code(&code, text.len() - rest.len() + start) {
+            out.push_str(&rest[..start + 2]);
+            rest = &rest[start + 2..];
+            continue;
+        }
+        let Some(len) = rest[start + 2..].find("]]") else { break };
+        let inner = &rest[start + 2..start + 2 + len];
+        let target_end = inner.find(['|', '#']).unwrap_or(inner.len());
+        let bare = strip_md(inner[..target_end].trim());
+        out.push_str(&rest[..start + 2]);
+        if bare.to_lowercase() == old_stem.to_lowercase() {
+            out.push_str(new_stem);
+            out.push_str(&inner[target_end..]);
+        } else {
+            out.push_str(inner);
+        }
+        out.push_str("]]");
+        rest = &rest[start + 2 + len + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Rewrite `[text](dest)` links in a file living in `dir` whose destination
+/// resolves to `old`, swapping in `new_name` as the last path segment.
+/// Handles `<angle bracket>` destinations and `%20`-encoded spaces; code is
+/// left alone.
+fn rewrite_relative_links(text: &str, dir: &Path, old: &Path, new_name: &str) -> String {
+    let code = code_ranges(text);
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("](") {
+        if in_code(&code, text.len() - rest.len() + start) {
+            out.push_str(&rest[..start + 2]);
+            rest = &rest[start + 2..];
+            continue;
+        }
+        let Some(len) = rest[start + 2..].find(')') else { break };
+        let inner = &rest[start + 2..start + 2 + len];
+        let angled = inner.strip_prefix('<').and_then(|r| r.find('>'));
+        let (dest, dest_end) = match angled {
+            Some(close) => (&inner[1..close + 1], close + 2),
+            None => {
+                let end = inner.find(' ').unwrap_or(inner.len());
+                (&inner[..end], end)
+            }
+        };
+        let (file_part, fragment) = dest.split_at(dest.find('#').unwrap_or(dest.len()));
+        let decoded = file_part.replace("%20", " ");
+        out.push_str(&rest[..start + 2]);
+        if is_relative_link(file_part) && normalize(&dir.join(&decoded)) == old {
+            let prefix = file_part.rfind('/').map(|i| &file_part[..=i]).unwrap_or("");
+            let name = if file_part.contains("%20") {
+                new_name.replace(' ', "%20")
+            } else {
+                new_name.to_string()
+            };
+            let (open, close) = if angled.is_some() { ("<", ">") } else { ("", "") };
+            out.push_str(&format!("{open}{prefix}{name}{fragment}{close}"));
+            out.push_str(&inner[dest_end..]);
+        } else {
+            out.push_str(inner);
+        }
+        out.push(')');
+        rest = &rest[start + 2 + len + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The new file name for a rename, keeping the old extension when
+/// `new_name` doesn't give one.
+fn renamed_file_name(old: &Path, new_name: &str) -> String {
+    match (Path::new(new_name).extension(), old.extension()) {
+        (None, Some(ext)) => format!("{}.{}", new_name, ext.to_string_lossy()),
+        _ => new_name.to_string(),
+    }
+}
+
+pub fn rename_with_links(
+    old_path: &Path,
+    new_name: &str,
+    root: &Path,
+    apply: bool,
+) -> Result<RenameReport, String> {
+    if new_name.is_empty() || new_name.contains(['/', '\\']) {
+        return Err(format!("'{}' is not a valid file name", new_name));
+    }
+    if !old_path.is_file() {
+        return Err(format!("{} does not exist", old_path.display()));
+    }
+    let new_file_name = renamed_file_name(old_path, new_name);
+    let new_path = old_path.with_file_name(&new_file_name);
+    if new_path.exists() {
+        return Err(format!("{} already exists", new_path.display()));
+    }
+
+    let old_norm = normalize(old_path);
+    let (old_stem, new_stem) = (file_stem(old_path), file_stem(&new_path));
+    let files = markdown_files(root);
+    // The renamed note's own links are written to its new path.
+    let edits: Vec<(PathBuf, String, String)> = parallel_map(&files, |file| {
+        let text = std::fs::read_to_string(file).ok()?;
+        let dir = normalize(file.parent().unwrap_or(root));
+        let updated = rewrite_wikilinks(&text, &old_stem, &new_stem);
+        let updated = rewrite_relative_links(&updated, &dir, &old_norm, &new_file_name);
+        let target = if normalize(file) == old_norm { new_path.clone() } else { file.clone() };
+        (updated != text).then_some((target, text, updated))
+    })
+    .into_iter()
+    .flatten()
+    .collect();
+
+    if apply {
+        std::fs::rename(old_path, &new_path)
+            .map_err(|e| format!("Failed to rename {}: {}", old_path.display(), e))?;
+        for (done, (file, _, text)) in edits.iter().enumerate() {
+            if let Err(e) = crate::files::write_atomic(file, text.as_bytes()) {
+                // Put back the links already rewritten and the old name.
+                for (file, original, _) in &edits[..done] {
+                    let _ = crate::files::write_atomic(file, original.as_bytes());
+                }
+                let _ = std::fs::rename(&new_path, old_path);
+                return Err(format!("Failed to update links in {}: {}", file.display(), e));
+            }
+        }
+    }
+
+    Ok(RenameReport {
+        old_path: old_path.to_string_lossy().to_string(),
+        new_path: new_path.to_string_lossy().to_string(),
+        edited_files: edits.into_iter().map(|(f, ..)| f.to_string_lossy().to_string()).collect(),
+        applied: apply,
+    })
+}
+
+// ── Batch rename ──────────────────────────────────────────────────────────────
+
+/// Old → new paths for every markdown file whose name (without extension)
+/// matches `find`, with `replace` applied (`$1` etc. allowed). Fails on any
+/// collision rather than returning a partial plan.
+pub fn plan_batch_rename(
+    root: &Path,
+    find: &str,
+    replace: &str,
+    opts: &BatchRenameOpts,
+) -> Result<Vec<(PathBuf, PathBuf)>, String> {
+    let re = regex::RegexBuilder::new(find)
+        .case_insensitive(opts.case_insensitive)
+        .build()
+        .map_err(|e| format!("Invalid pattern: {}", e))?;
+    let files: Vec<PathBuf> = if opts.recursive {
+        markdown_files(root)
+    } else {
+        markdown_files(root).into_iter().filter(|p| p.parent() == Some(root)).collect()
+    };
+
+    let mut plan = Vec::new();
+    for old in &files {
+        let stem = file_stem(old);
+        let new_stem = re.replace_all(&stem, replace);
+        if new_stem == stem {
+            continue;
+        }
+        if new_stem.is_empty() || new_stem.contains(['/', '\\']) {
+            return Err(format!("'{}' would become an invalid name '{}'", stem, new_stem));
+        }
+        let ext = old.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+        plan.push((old.clone(), old.with_file_name(format!("{new_stem}{ext}"))));
+    }
+
+    for (i, (old, new)) in plan.iter().enumerate() {
+        if plan[..i].iter().any(|(_, other)| same_name(other, new)) {
+            return Err(format!("More than one file would be renamed to {}", new.display()));
+        }
+        // Includes case-only renames, where `new` "exists" as `old` itself.
+        let freed = plan.iter().any(|(o, _)| same_name(o, new));
+        if new.exists() && !freed {
+            return Err(format!("Renaming {} would overwrite {}", old.display(), new.display()));
+        }
+    }
+    Ok(plan)
+}
+
+/// Whether two paths name the same file, ignoring case on macOS and
+/// Windows whose file systems do.
+fn same_name(a: &Path, b: &Path) -> bool {
+    if cfg!(any(target_os = "macos", target_os = "windows")) {
+        a.to_string_lossy().to_lowercase() == b.to_string_lossy().to_lowercase()
+    } else {
+        a == b
+    }
+}
+
+/// Carry out a plan from `plan_batch_rename`. Renames go through temporary
+/// names first so swaps like `a → b, b → a` work. On failure every file is
+/// moved back to its old name.
+pub fn apply_batch_rename(plan: &[(PathBuf, PathBuf)]) -> Result<(), String> {
+    let staged: Vec<PathBuf> = plan
+        .iter()
+        .enumerate()
+        .map(|(i, (old, _))| old.with_file_name(format!(".updown-rename-{i}.tmp")))
+        .collect();
+    // Files `..finished` are at their new name, `finished..staging` at the
+    // temporary one.
+    let undo = |finished: usize, staging: usize| {
+        for (i, ((old, new), tmp)) in plan.iter().zip(&staged).enumerate().take(staging) {
+            let _ = std::fs::rename(if i < finished { new } else { tmp }, old);
+        }
+    };
+    for (i, ((old, _), tmp)) in plan.iter().zip(&staged).enumerate() {
+        if let Err(e) = std::fs::rename(old, tmp) {
+            undo(0, i);
+            return Err(format!("Failed to rename {}: {}", old.display(), e));
+        }
+    }
+    for (i, ((_, new), tmp)) in plan.iter().zip(&staged).enumerate() {
+        if let Err(e) = std::fs::rename(tmp, new) {
+            undo(i, plan.len());
+            return Err(format!("Failed to rename to {}: {}", new.display(), e));
+        }
+    }
+    Ok(())
+}
+
+// ── Link graph ────────────────────────────────────────────────────────────────
+
+type LinkCache = Mutex<HashMap<PathBuf, (SystemTime, Vec<String>)>>;
+
+/// Raw link targets of a file, cached until its mtime changes.
+fn outgoing_links(path: &Path) -> Vec<String> {
+    static CACHE: OnceLock<LinkCache> = OnceLock::new();
+    let cache = CACHE.get_or_init(Default::default);
+    let mtime = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    if let Some(mtime) = mtime {
+        if let Some((cached_at, links)) = cache.lock_or_recover().get(path) {
+            if *cached_at == mtime {
+                return links.clone();
+            }
+        }
+    }
+    let text = std::fs::read_to_string(path).unwrap_or_default();
+    let (_, body, _) = analysis::split_front_matter(&text);
+    let links = analysis::links(body);
+    if let Some(mtime) = mtime {
+        cache.lock_or_recover().insert(path.to_path_buf(), (mtime, links.clone()));
+    }
+    links
+}
+
+/// Resolve a link target found in a file in `dir` to one of the vault's
+/// files: relative paths first, then (for bare names) a case-insensitive
+/// match on the file stem as wikilinks do.
+fn resolve_link(
+    dir: &Path,
+    target: &str,
+    by_path: &HashMap<PathBuf, usize>,
+    by_stem: &HashMap<String, usize>,
+) -> Option<usize> {
+    let file_part = &target[..target.find('#').unwrap_or(target.len())];
+    if !is_relative_link(file_part) {
+        return None;
+    }
+    let decoded = file_part.replace("%20", " ");
+    if let Some(&i) = by_path.get(&normalize(&dir.join(&decoded))) {
+        return Some(i);
+    }
+    if decoded.contains('/') {
+        return None;
+    }
+    let stem = strip_md(&decoded).to_lowercase();
+    by_stem.get(&stem).copied()
+}
+
+/// Files under `root` and the deduplicated links between them, as
+/// `(from, to, count)` index triples. Self-links are dropped.
+pub fn link_graph(root: &Path) -> (Vec<PathBuf>, Vec<(usize, usize, usize)>) {
+    let files: Vec<PathBuf> = markdown_files(root).iter().map(|p| normalize(p)).collect();
+    let by_path: HashMap<PathBuf, usize> =
+        files.iter().cloned().enumerate().map(|(i, p)| (p, i)).collect();
+    let mut by_stem: HashMap<String, usize> = HashMap::new();
+    for (i, p) in files.iter().enumerate() {
+        by_stem.entry(file_stem(p).to_lowercase()).or_insert(i);
+    }
+
+    let per_file: Vec<Vec<usize>> = parallel_map(&files, |file| {
+        let dir = file.parent().unwrap_or(root);
+        outgoing_links(file)
+            .iter()
+            .filter_map(|t| resolve_link(dir, t, &by_path, &by_stem))
+            .collect()
+    });
+
+    let mut weights: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+    for (from, targets) in per_file.into_iter().enumerate() {
+        for to in targets.into_iter().filter(|&to| to != from) {
+            *weights.entry((from, to)).or_default() += 1;
+        }
+    }
+    let edges = weights.into_iter().map(|((f, t), w)| (f, t, w)).collect();
+    (files, edges)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphNode {
+    /// Path relative to the vault root.
+    pub id: String,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    /// Number of links from `source` to `target`.
+    pub weight: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LinkGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+pub fn build_link_graph(root: &Path) -> LinkGraph {
+    let root_norm = normalize(root);
+    let (files, edges) = link_graph(root);
+    let ids: Vec<String> = files
+        .iter()
+        .map(|p| {
+            let rel = p.strip_prefix(&root_norm).unwrap_or(p);
+            rel.to_string_lossy().replace('\\', "/")
+        })
+        .collect();
+    LinkGraph {
+        nodes: files
+            .iter()
+            .zip(&ids)
+            .map(|(p, id)| GraphNode { id: id.clone(), label: file_stem(p) })
+            .collect(),
+        edges: edges
+            .into_iter()
+            .map(|(f, t, weight)| GraphEdge { source: ids[f].clone(), target: ids[t].clone(), weight })
+            .collect(),
+    }
+}
+
+fn dot_quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+pub fn to_dot(graph: &LinkGraph) -> String {
+    let mut out = String::from("digraph links {\n");
+    for n in &graph.nodes {
+        out.push_str(&format!("  {} [label={}];\n", dot_quote(&n.id), dot_quote(&n.label)));
+    }
+    for e in &graph.edges {
+        out.push_str(&format!(
+            "  {} -> {} [weight={}];\n",
+            dot_quote(&e.source),
+            dot_quote(&e.target),
+            e.weight
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+// ── Wikilink resolution ───────────────────────────────────────────────────────
+
+/// Where a `[[wikilink]]` points. When several notes share the name,
+/// `ambiguous` is set and `path` is the one closest to the linking note.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WikiLinkMatch {
+    pub path: String,
+    pub ambiguous: bool,
+}
+
+/// Markdown files under a root, keyed by lowercased file stem.
+type StemIndex = HashMap<String, Vec<PathBuf>>;
+
+/// Stem indexes per root, kept for the session and rebuilt when a lookup
+/// misses or lands on a file that has since gone.
+fn stem_indexes() -> &'static Mutex<HashMap<PathBuf, StemIndex>> {
+    static INDEXES: OnceLock<Mutex<HashMap<PathBuf, StemIndex>>> = OnceLock::new();
+    INDEXES.get_or_init(Default::default)
+}
+
+pub fn build_stem_index(root: &Path) -> StemIndex {
+    let mut index = StemIndex::new();
+    for path in markdown_files(root) {
+        index.entry(file_stem(&path).to_lowercase()).or_default().push(normalize(&path));
+    }
+    index
+}
+
+/// Steps through the folder tree from `dir` to `path`.
+fn tree_distance(dir: &Path, path: &Path) -> usize {
+    let shared = dir.components().zip(path.components()).take_while(|(a, b)| a == b).count();
+    dir.components().count() + path.components().count() - 2 * shared
+}
+
+/// Resolve a wikilink target (`Note`, `folder/Note.md`, `Note#Heading|alias`)
+/// against `index`, case-insensitively. Ties go to the note nearest `from`'s
+/// folder, or nearest `root` when there is no linking note.
+pub fn resolve_wikilink_in(index: &StemIndex, root: &Path, target: &str, from: Option<&Path>) -> Option<WikiLinkMatch> {
+    let end = target.find(['|', '#']).unwrap_or(target.len());
+    let mut name = target[..end].trim().replace('\\', "/").to_lowercase();
+    if is_markdown(Path::new(&name)) {
+        name.truncate(name.rfind('.').unwrap_or(name.len()));
+    }
+    let stem = name.rsplit('/').next().unwrap_or_default();
+    let suffix = format!("/{}", name);
+    let candidates: Vec<&PathBuf> = index
+        .get(stem)?
+        .iter()
+        .filter(|p| {
+            let rel = p.strip_prefix(root).unwrap_or(p).with_extension("");
+            let rel = format!("/{}", rel.to_string_lossy().replace('\\', "/").to_lowercase());
+            rel.ends_with(&suffix)
+        })
+        .collect();
+    let base = from.and_then(Path::parent).unwrap_or(root);
+    let closest = candidates.iter().min_by_key(|p| (tree_distance(base, p), p.as_path()))?;
+    Some(WikiLinkMatch { path: closest.to_string_lossy().to_string(), ambiguous: candidates.len() > 1 })
+}
+
+/// Files sharing a title (compared case-insensitively).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DuplicateGroup {
+    /// The title as written in the first file of the group.
+    pub title: String,
+    pub paths: Vec<String>,
+}
+
+fn note_title(path: &Path) -> Option<String> {
+    let text = std::fs::read_to_string(path).ok()?;
+    let (raw, body, fm_lines) = analysis::split_front_matter(&text);
+    let fm = raw.map(analysis::parse_front_matter).unwrap_or_default();
+    analysis::title(&fm, &analysis::headings(body, fm_lines))
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// One row per markdown file under `root` with the requested front-matter
+/// fields (null when missing), for CSV/JSON export.
+#[tauri::command]
+pub fn collect_front_matter(root: String, fields: Vec<String>) -> Vec<FrontMatterRow> {
+    let files = markdown_files(Path::new(&root));
+    parallel_map(&files, |p| front_matter_row(p, &fields))
+}
+
+/// Rename a note and rewrite `[[wikilinks]]` and relative links to it in
+/// every markdown file under `root`. With `apply: false` nothing is written;
+/// the report says what would change. An applied rename is mirrored into the
+/// recent-files list.
+#[tauri::command]
+pub fn rename_with_link_updates(
+    app: tauri::AppHandle,
+    old_path: String,
+    new_name: String,
+    root: String,
+    apply: bool,
+) -> Result<RenameReport, String> {
+    let report = rename_with_links(Path::new(&old_path), &new_name, Path::new(&root), apply)?;
+    if report.applied {
+        crate::rename_in_recent(&app, &[(report.old_path.clone(), report.new_path.clone())]);
+    }
+    Ok(report)
+}
+
+/// Markdown files under `root` that no other note links to. The home note
+/// (`index` unless `home` names another) is never reported.
+#[tauri::command]
+pub fn find_orphans(root: String, home: Option<String>) -> Vec<String> {
+    let home = home.unwrap_or_else(|| DEFAULT_HOME_NOTE.to_string()).to_lowercase();
+    let (files, edges) = link_graph(Path::new(&root));
+    let mut linked = vec![false; files.len()];
+    for (_, to, _) in edges {
+        linked[to] = true;
+    }
+    files
+        .iter()
+        .zip(linked)
+        .filter(|(p, linked)| !linked && file_stem(p).to_lowercase() != home)
+        .map(|(p, _)| p.to_string_lossy().to_string())
+        .collect()
+}
+
+/// The note link graph under `root` as Graphviz DOT (`format: "dot"`) or
+/// `{ nodes, edges }` JSON (`format: "json"`).
+#[tauri::command]
+pub fn export_link_graph(root: String, format: String) -> Result<String, String> {
+    let graph = build_link_graph(Path::new(&root));
+    match format.to_lowercase().as_str() {
+        "dot" => Ok(to_dot(&graph)),
+        "json" => serde_json::to_string_pretty(&graph).map_err(|e| e.to_string()),
+        other => Err(format!("Unknown graph format '{}'; expected dot or json", other)),
+    }
+}
+
+/// Groups of notes under `root` whose front-matter title or first heading
+/// collide, ignoring case.
+#[tauri::command]
+pub fn duplicate_titles(root: String) -> Vec<DuplicateGroup> {
+    let files = markdown_files(Path::new(&root));
+    let titles = parallel_map(&files, |p| note_title(p));
+    let mut groups: BTreeMap<String, DuplicateGroup> = BTreeMap::new();
+    for (path, title) in files.iter().zip(titles) {
+        let Some(title) = title.filter(|t| !t.trim().is_empty()) else { continue };
+        groups
+            .entry(title.trim().to_lowercase())
+            .or_insert_with(|| DuplicateGroup { title: title.clone(), paths: vec![] })
+            .paths
+            .push(path.to_string_lossy().to_string());
+    }
+    groups.into_values().filter(|g| g.paths.len() > 1).collect()
+}
+
+/// The note under `root` that `[[target]]` refers to, for opening a clicked
+/// wikilink. Pass the linking note as `from` so ambiguous names pick the
+/// nearest match.
+#[tauri::command]
+pub fn resolve_wiki_link(root: String, target: String, from: Option<String>) -> Option<WikiLinkMatch> {
+    let root = normalize(Path::new(&root));
+    let from = from.map(|f| normalize(Path::new(&f)));
+    let cached = stem_indexes()
+        .lock_or_recover()
+        .get(&root)
+        .and_then(|index| resolve_wikilink_in(index, &root, &target, from.as_deref()))
+        .filter(|m| Path::new(&m.path).is_file());
+    if cached.is_some() {
+        return cached;
+    }
+    let index = build_stem_index(&root);
+    let found = resolve_wikilink_in(&index, &root, &target, from.as_deref());
+    stem_indexes().lock_or_recover().insert(root, index);
+    found
+}
+
+/// Regex rename of markdown file names under `root`, returning the old →
+/// new pairs. Only previews unless `opts.apply` is set; refuses collisions.
+/// Applied renames are mirrored into the recent-files list.
+#[tauri::command]
+pub fn batch_rename(
+    app: tauri::AppHandle,
+    root: String,
+    find: String,
+    replace: String,
+    opts: BatchRenameOpts,
+) -> Result<Vec<(String, String)>, String> {
+    let plan = plan_batch_rename(Path::new(&root), &find, &replace, &opts)?;
+    let pairs: Vec<(String, String)> = plan
+        .iter()
+        .map(|(o, n)| (o.to_string_lossy().to_string(), n.to_string_lossy().to_string()))
+        .collect();
+    if opts.apply {
+        apply_batch_rename(&plan)?;
+        crate::rename_in_recent(&app, &pairs);
+    }
+    Ok(pairs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_have_null_for_missing_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("a.md"), "---\ntitle: Alpha\nstatus: draft\n---\nbody").unwrap();
+        std::fs::create_dir(root.join("sub")).unwrap();
+        std::fs::write(root.join("sub/b.md"), "---\ntitle: Beta\ntags: [x, y]\n---\n").unwrap();
+        std::fs::write(root.join("c.md"), "no front matter").unwrap();
+        std::fs::create_dir(root.join(".hidden")).unwrap();
+        std::fs::write(root.join(".hidden/d.md"), "---\ntitle: Hidden\n---\n").unwrap();
+
+        let fields = vec!["title".to_string(), "status".to_string()];
+        let rows = collect_front_matter(root.to_string_lossy().to_string(), fields);
+        let json = serde_json::to_value(&rows).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(json[0]["title"], "Alpha");
+        assert_eq!(json[0]["status"], "draft");
+        assert_eq!(json[1]["title"], Value::Null);
+        assert_eq!(json[2]["title"], "Beta");
+        assert_eq!(json[2]["status"], Value::Null);
+        assert!(json[2]["path"].as_str().unwrap().ends_with("b.md"));
+    }
+
+    fn rename_fixture() -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        std::fs::write(root.join("old note.md"), "# Old\n[[old note#Old|top]]").unwrap();
+        std::fs::write(root.join("index.md"), "See [[Old Note]], [[old note#Intro|intro]] and [[Other]].").unwrap();
+        std::fs::create_dir(root.join("sub")).unwrap();
+        std::fs::write(root.join("sub/ref.md"), "[a](../old%20note.md) [b](<../old note.md#top> \"t\") [c](https://x.io/old%20note.md)").unwrap();
+        (dir, root)
+    }
+
+    #[test]
+    fn rewrites_wikilinks_and_relative_links() {
+        let (_dir, root) = rename_fixture();
+        let report = rename_with_links(&root.join("old note.md"), "new note", &root, true).unwrap();
+        assert_eq!(report.edited_files.len(), 3);
+        assert_eq!(std::fs::read_to_string(root.join("new note.md")).unwrap(), "# Old\n[[new note#Old|top]]");
+        assert!(!root.join("old note.md").exists());
+        assert_eq!(
+            std::fs::read_to_string(root.join("index.md")).unwrap(),
+            "See [[new note]], [[new note#Intro|intro]] and [[Other]]."
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.join("sub/ref.md")).unwrap(),
+            "[a](../new%20note.md) [b](<../new note.md#top> \"t\") [c](https://x.io/old%20note.md)"
+        );
+    }
+
+    #[test]
+    fn links_in_code_are_left_alone() {
+        let (_dir, root) = rename_fixture();
+        let text = "[[Old Note.MD]] `[[old note]]` [x](old%20note.md)\n\n```\n[[old note]] [x](old%20note.md)\n```\n";
+        std::fs::write(root.join("code.md"), text).unwrap();
+        rename_with_links(&root.join("old note.md"), "new note", &root, true).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(root.join("code.md")).unwrap(),
+            "[[new note]] `[[old note]]` [x](new%20note.md)\n\n```\n[[old note]] [x](old%20note.md)\n```\n"
+        );
+    }
+
+    #[test]
+    fn dry_run_leaves_disk_untouched() {
+        let (_dir, root) = rename_fixture();
+        let before = std::fs::read_to_string(root.join("index.md")).unwrap();
+        let report = rename_with_links(&root.join("old note.md"), "new note", &root, false).unwrap();
+        assert!(!report.applied);
+        assert!(report.edited_files.iter().any(|f| f.ends_with("index.md")));
+        assert!(root.join("old note.md").exists());
+        assert!(!root.join("new note.md").exists());
+        assert_eq!(std::fs::read_to_string(root.join("index.md")).unwrap(), before);
+    }
+
+    #[test]
+    fn wikilinks_resolve_by_name_and_prefer_the_nearest_note() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = normalize(dir.path());
+        for rel in ["Ideas.md", "work/Ideas.md", "work/Plan.md", "home/Plan.md"] {
+            std::fs::create_dir_all(root.join(rel).parent().unwrap()).unwrap();
+            std::fs::write(root.join(rel), "").unwrap();
+        }
+        let index = build_stem_index(&root);
+        let resolve = |target, from: Option<&str>| {
+            let from = from.map(|f| root.join(f));
+            resolve_wikilink_in(&index, &root, target, from.as_deref())
+                .map(|m| (m.path.strip_prefix(&*root.to_string_lossy()).unwrap().replace('\\', "/"), m.ambiguous))
+        };
+
+        assert_eq!(resolve("ideas#Top|my ideas", None), Some(("/Ideas.md".to_string(), true)));
+        assert_eq!(resolve("IDEAS", Some("work/Plan.md")), Some(("/work/Ideas.md".to_string(), true)));
+        assert_eq!(resolve("home/plan.md", Some("work/Plan.md")), Some(("/home/Plan.md".to_string(), false)));
+        assert_eq!(resolve("Missing", None), None);
+    }
+
+    #[test]
+    fn unlinked_note_is_an_orphan() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("index.md"), "[[Alpha]] and [beta](notes/beta.md)").unwrap();
+        std::fs::write(root.join("alpha.md"), "Back to [[index]], self [[alpha]]").unwrap();
+        std::fs::create_dir(root.join("notes")).unwrap();
+        std::fs::write(root.join("notes/beta.md"), "nothing").unwrap();
+        std::fs::write(root.join("lonely.md"), "links to [[alpha]] only").unwrap();
+
+        let orphans = find_orphans(root.to_string_lossy().to_string(), None);
+        assert_eq!(orphans.len(), 1);
+        assert!(orphans[0].ends_with("lonely.md"));
+    }
+
+    #[test]
+    fn graph_edges_reflect_links_with_weights() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("a.md"), "[[b]] twice: [b](b.md), and [[c]]").unwrap();
+        std::fs::write(root.join("b.md"), "[[a]]").unwrap();
+        std::fs::write(root.join("c.md"), "[[missing]]").unwrap();
+        let root_str = root.to_string_lossy().to_string();
+
+        let json: Value = serde_json::from_str(&export_link_graph(root_str.clone(), "json".into()).unwrap()).unwrap();
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 3);
+        assert_eq!(
+            json["edges"],
+            serde_json::json!([
+                { "source": "a.md", "target": "b.md", "weight": 2 },
+                { "source": "a.md", "target": "c.md", "weight": 1 },
+                { "source": "b.md", "target": "a.md", "weight": 1 },
+            ])
+        );
+
+        let dot = export_link_graph(root_str.clone(), "dot".into()).unwrap();
+        assert!(dot.starts_with("digraph links {"));
+        assert!(dot.contains("\"a.md\" -> \"b.md\" [weight=2];"));
+        assert!(export_link_graph(root_str, "svg".into()).is_err());
+    }
+
+    #[test]
+    fn colliding_titles_are_grouped() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("a.md"), "# Project Plan\n").unwrap();
+        std::fs::write(root.join("b.md"), "---\ntitle: project plan\n---\n# Other\n").unwrap();
+        std::fs::write(root.join("c.md"), "# Unique\n").unwrap();
+
+        let groups = duplicate_titles(root.to_string_lossy().to_string());
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].title, "Project Plan");
+        assert_eq!(groups[0].paths.len(), 2);
+        assert!(groups[0].paths.iter().all(|p| !p.ends_with("c.md")));
+    }
+
+    #[test]
+    fn batch_rename_preview_refuses_collisions() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("draft-a.md"), "").unwrap();
+        std::fs::write(root.join("final-a.md"), "").unwrap();
+        let opts = BatchRenameOpts::default();
+        let err = plan_batch_rename(root, "^draft-", "final-", &opts).unwrap_err();
+        assert!(err.contains("overwrite"));
+
+        std::fs::write(root.join("draft-b.md"), "").unwrap();
+        let err = plan_batch_rename(root, "^(draft|final)-.*", "same", &opts).unwrap_err();
+        assert!(err.contains("More than one"));
+    }
+
+    #[test]
+    fn batch_rename_applies_with_captures() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("2024-01-05 Notes.md"), "x").unwrap();
+        std::fs::write(root.join("Other.md"), "").unwrap();
+        let opts = BatchRenameOpts { apply: true, ..Default::default() };
+        let plan = plan_batch_rename(root, r"^(\d{4})-(\d{2})-(\d{2}) ", "$1$2$3-", &opts).unwrap();
+        assert_eq!(plan, vec![(root.join("2024-01-05 Notes.md"), root.join("20240105-Notes.md"))]);
+
+        apply_batch_rename(&plan).unwrap();
+        assert_eq!(std::fs::read_to_string(root.join("20240105-Notes.md")).unwrap(), "x");
+        assert!(!root.join("2024-01-05 Notes.md").exists());
+
+        let plan = vec![
+            (root.join("Other.md"), root.join("other.md")),
+            (root.join("20240105-Notes.md"), root.join("missing/Notes.md")),
+        ];
+        assert!(apply_batch_rename(&plan).is_err());
+        let mut names: Vec<_> = std::fs::read_dir(root).unwrap().map(|e| e.unwrap().file_name()).collect();
+        names.sort();
+        assert_eq!(names, ["20240105-Notes.md", "Other.md"]);
+    }
+}
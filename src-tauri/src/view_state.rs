@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+/// Where the user left off in a document.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ViewState {
+    /// Caret offset in the editor, in UTF-16 code units as the textarea
+    /// reports it.
+    pub cursor: usize,
+    /// Editor scroll position as a fraction of its scrollable height.
+    pub scroll: f64,
+}
+
+/// `view-state.json`: view state keyed by absolute file path.
+type ViewStates = BTreeMap<String, ViewState>;
+
+// ── Persistence ───────────────────────────────────────────────────────────────
+
+fn storage_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|d| d.join("view-state.json"))
+        .map_err(|e| format!("Cannot locate the app data directory: {}", e))
+}
+
+fn load(path: &Path) -> ViewStates {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save(path: &Path, states: &ViewStates) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let json = serde_json::to_string(states).map_err(|e| e.to_string())?;
+    crate::files::write_atomic(path, json.as_bytes()).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Keep only entries for `keep`; returns whether anything was dropped.
+pub fn retain_paths(states: &mut ViewStates, keep: &[String]) -> bool {
+    let before = states.len();
+    states.retain(|path, _| keep.contains(path));
+    states.len() != before
+}
+
+/// Forget files that have left the recent list, so the file stays small.
+pub fn prune(app: &tauri::AppHandle, recent: &[String]) {
+    let Ok(path) = storage_path(app) else { return };
+    let mut states = load(&path);
+    if retain_paths(&mut states, recent) {
+        let _ = save(&path, &states);
+    }
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn save_view_state(app: tauri::AppHandle, path: String, cursor: usize, scroll: f64) -> Result<(), String> {
+    let storage = storage_path(&app)?;
+    let mut states = load(&storage);
+    let scroll = if scroll.is_finite() { scroll.clamp(0.0, 1.0) } else { 0.0 };
+    states.insert(path, ViewState { cursor, scroll });
+    save(&storage, &states)
+}
+
+#[tauri::command]
+pub fn get_view_state(app: tauri::AppHandle, path: String) -> Result<Option<ViewState>, String> {
+    Ok(load(&storage_path(&app)?).get(&path).copied())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn states_round_trip_and_prune_to_the_recent_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("view-state.json");
+        let mut states = ViewStates::new();
+        states.insert("/a.md".into(), ViewState { cursor: 120, scroll: 0.5 });
+        states.insert("/b.md".into(), ViewState { cursor: 0, scroll: 0.0 });
+        save(&path, &states).unwrap();
+
+        let mut loaded = load(&path);
+        assert_eq!(loaded, states);
+        assert!(retain_paths(&mut loaded, &["/a.md".to_string()]));
+        assert_eq!(loaded.keys().collect::<Vec<_>>(), vec!["/a.md"]);
+        assert!(!retain_paths(&mut loaded, &["/a.md".to_string()]));
+    }
+}
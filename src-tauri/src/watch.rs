@@ -0,0 +1,180 @@
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, DebouncedEventKind, Debouncer};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tauri::{Emitter, Manager};
+
+use crate::lock::LockExt;
+
+/// A change fires only once the file has stopped changing for this long, so
+/// an editor's burst of writes becomes one event.
+pub const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// The stamp each of our own writes left, keyed by path, so the watchers
+/// can tell them from edits made elsewhere.
+static OWN_WRITES: Mutex<Option<HashMap<PathBuf, DiskStamp>>> = Mutex::new(None);
+
+/// A file's modification time (Unix milliseconds) and length. The frontend
+/// keeps the one it loaded or last saved and hands it back to auto-save.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiskStamp {
+    pub modified_ms: i64,
+    pub size: u64,
+}
+
+/// `None` if the file is missing.
+pub type Snapshot = Option<DiskStamp>;
+
+pub fn snapshot(path: &Path) -> Snapshot {
+    let meta = std::fs::metadata(path).ok()?;
+    let since_epoch = meta.modified().ok()?.duration_since(SystemTime::UNIX_EPOCH).ok()?;
+    Some(DiskStamp { modified_ms: i64::try_from(since_epoch.as_millis()).ok()?, size: meta.len() })
+}
+
+/// Remember the stamp `path` has right after we wrote it. Called by
+/// `write_document`.
+pub fn note_own_write(path: &Path) {
+    if let Some(stamp) = snapshot(path) {
+        OWN_WRITES.lock_or_recover().get_or_insert_with(HashMap::new).insert(path.to_path_buf(), stamp);
+    }
+}
+
+/// Whether `path` is still exactly as our last write left it.
+fn is_own_write(path: &Path) -> bool {
+    let stamp = snapshot(path);
+    stamp.is_some() && OWN_WRITES.lock_or_recover().as_ref().and_then(|w| w.get(path)) == stamp.as_ref()
+}
+
+/// Watches one file for OS change events and calls `on_change` once each
+/// burst of changes settles. Changes that are our own writes, and the file
+/// going away, are not reported. Stops when dropped.
+pub struct FileWatcher {
+    _debouncer: Debouncer<RecommendedWatcher>,
+}
+
+impl FileWatcher {
+    /// The parent folder is watched rather than the file itself, so editors
+    /// that save by writing a new file and renaming it over are still seen.
+    pub fn spawn<F>(path: PathBuf, debounce: Duration, on_change: F) -> Result<Self, String>
+    where
+        F: Fn(&Path) + Send + 'static,
+    {
+        let watched = path.clone();
+        let mut debouncer = new_debouncer(debounce, move |events: DebounceEventResult| {
+            // Only the folder's own entries are reported, so the name is enough
+            // (event paths may be spelled differently, e.g. resolved symlinks).
+            // `AnyContinuous` marks changes that are still going on.
+            let touched = events.is_ok_and(|events| {
+                events.iter().any(|e| e.kind == DebouncedEventKind::Any && e.path.file_name() == watched.file_name())
+            });
+            if touched && watched.exists() && !is_own_write(&watched) {
+                on_change(&watched);
+            }
+        })
+        .map_err(|e| format!("Failed to watch {}: {}", path.display(), e))?;
+        let folder = path.parent().ok_or_else(|| format!("{} has no parent folder", path.display()))?;
+        debouncer
+            .watcher()
+            .watch(folder, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch {}: {}", path.display(), e))?;
+        Ok(FileWatcher { _debouncer: debouncer })
+    }
+}
+
+/// The open document each window is watching, keyed by window label.
+#[derive(Default)]
+pub struct DocumentWatches(pub Mutex<HashMap<String, (String, FileWatcher)>>);
+
+/// Stop the watch held by window `label`, if any. Called when the window
+/// closes.
+pub fn stop_watching(app: &tauri::AppHandle, label: &str) {
+    let old = app.state::<DocumentWatches>().0.lock_or_recover().remove(label);
+    drop(old);
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// Emit `file-changed` with `path` to the calling window whenever the file
+/// changes on disk, other than through our own saves. Each window watches
+/// one file; this replaces its previous watch.
+#[tauri::command]
+pub fn watch_file(window: tauri::WebviewWindow, path: String) -> Result<(), String> {
+    let label = window.label().to_string();
+    let app = window.app_handle().clone();
+    let target = label.clone();
+    let handle = app.clone();
+    let watcher = FileWatcher::spawn(PathBuf::from(&path), DEBOUNCE, move |p| {
+        let _ = handle.emit_to(target.as_str(), "file-changed", p.to_string_lossy().to_string());
+    })?;
+    // Swap under the lock, but stop the old watcher after releasing it.
+    let old = app.state::<DocumentWatches>().0.lock_or_recover().insert(label, (path, watcher));
+    drop(old);
+    Ok(())
+}
+
+/// Stop watching `path` in the calling window. Ignored if the window has
+/// since moved on to another file.
+#[tauri::command]
+pub fn unwatch_file(window: tauri::WebviewWindow, path: String) {
+    let state = window.state::<DocumentWatches>();
+    let old = {
+        let mut watches = state.0.lock_or_recover();
+        match watches.get(window.label()) {
+            Some((watched, _)) if *watched == path => watches.remove(window.label()),
+            _ => None,
+        }
+    };
+    drop(old);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    /// Events (or their absence) take a moment to arrive.
+    const SETTLE: Duration = Duration::from_millis(500);
+
+    #[test]
+    fn burst_of_writes_fires_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("note.md");
+        std::fs::write(&path, "one").unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let watcher = FileWatcher::spawn(path.clone(), Duration::from_millis(100), move |p| {
+            let _ = tx.send(p.to_path_buf());
+        })
+        .unwrap();
+        for i in 0..3 {
+            std::fs::write(&path, format!("edit {i}")).unwrap();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(rx.recv_timeout(Duration::from_secs(2)).unwrap(), path);
+        assert!(rx.recv_timeout(SETTLE).is_err());
+        drop(watcher);
+    }
+
+    #[test]
+    fn own_writes_are_not_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("note.md");
+        std::fs::write(&path, "one").unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let watcher = FileWatcher::spawn(path.clone(), Duration::from_millis(100), move |p| {
+            let _ = tx.send(p.to_path_buf());
+        })
+        .unwrap();
+        crate::files::write_atomic(&path, b"saved by us").unwrap();
+        note_own_write(&path);
+        assert!(rx.recv_timeout(SETTLE).is_err());
+
+        std::fs::write(&path, "edited elsewhere").unwrap();
+        assert_eq!(rx.recv_timeout(Duration::from_secs(2)).unwrap(), path);
+        drop(watcher);
+    }
+}
@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::Manager;
+
+/// Moves and resizes arrive in bursts while the user drags; the state is
+/// written once they have stopped for this long.
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// How much of a restored window (in physical pixels, from its top-left)
+/// must land on some monitor for the saved position to be kept.
+const MIN_VISIBLE: f64 = 100.0;
+
+/// Bumped on every change; a pending save only writes if it is still current.
+static SAVE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Main window geometry in physical pixels (`window-state.json`): the
+/// content size and the frame's top-left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowState {
+    pub width: f64,
+    pub height: f64,
+    pub x: f64,
+    pub y: f64,
+    pub maximized: bool,
+}
+
+/// A monitor's work area in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Area {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+fn state_path(app: &tauri::AppHandle) -> Option<PathBuf> {
+    app.path().app_data_dir().ok().map(|d| d.join("window-state.json"))
+}
+
+pub fn load_state(path: &Path) -> Option<WindowState> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub fn save_state(path: &Path, state: &WindowState) {
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Ok(json) = serde_json::to_string(state) {
+        let _ = crate::files::write_atomic(path, json.as_bytes());
+    }
+}
+
+/// Keep a restored window reachable: if its top-left corner region is not
+/// on any monitor (say the one it was saved on is gone), move it to the
+/// first monitor and shrink it to fit.
+pub fn clamp_to_monitors(mut state: WindowState, monitors: &[Area]) -> WindowState {
+    let visible = monitors.iter().any(|m| {
+        state.x + MIN_VISIBLE.min(state.width) > m.x
+            && state.x < m.x + m.width - MIN_VISIBLE.min(m.width)
+            && state.y >= m.y
+            && state.y < m.y + m.height - MIN_VISIBLE.min(m.height)
+    });
+    if let (false, Some(m)) = (visible, monitors.first()) {
+        state.width = state.width.min(m.width);
+        state.height = state.height.min(m.height);
+        state.x = m.x + (m.width - state.width) / 2.0;
+        state.y = m.y + (m.height - state.height) / 2.0;
+    }
+    state
+}
+
+/// The window's geometry: its inner (content) size, which is what
+/// `set_size` restores, and its outer position, which `set_position` takes.
+fn current_state(window: &tauri::Window) -> Option<WindowState> {
+    let maximized = window.is_maximized().ok()?;
+    let size = window.inner_size().ok()?;
+    let pos = window.outer_position().ok()?;
+    Some(WindowState {
+        width: size.width as f64,
+        height: size.height as f64,
+        x: pos.x as f64,
+        y: pos.y as f64,
+        maximized,
+    })
+}
+
+fn monitor_areas(window: &tauri::WebviewWindow) -> Vec<Area> {
+    window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|m| {
+            let (pos, size) = (m.position(), m.size());
+            Area { x: pos.x as f64, y: pos.y as f64, width: size.width as f64, height: size.height as f64 }
+        })
+        .collect()
+}
+
+/// Apply the saved geometry to the main window, then show it.
+pub fn restore(app: &tauri::AppHandle) {
+    let Some(window) = app.get_webview_window(crate::windows::MAIN_WINDOW) else { return };
+    if let Some(state) = state_path(app).and_then(|p| load_state(&p)) {
+        let state = clamp_to_monitors(state, &monitor_areas(&window));
+        let _ = window.set_size(tauri::PhysicalSize::new(state.width as u32, state.height as u32));
+        let _ = window.set_position(tauri::PhysicalPosition::new(state.x as i32, state.y as i32));
+        if state.maximized {
+            let _ = window.maximize();
+        }
+    }
+    let _ = window.show();
+}
+
+/// Record the main window's geometry after a move or resize (debounced), or
+/// right away when it is closing. A maximized window keeps the last
+/// un-maximized size and position so un-maximizing after a restore works.
+pub fn window_changed(window: &tauri::Window, immediate: bool) {
+    if window.label() != crate::windows::MAIN_WINDOW {
+        return;
+    }
+    let app = window.app_handle().clone();
+    let Some(path) = state_path(&app) else { return };
+    let Some(mut state) = current_state(window) else { return };
+    if state.maximized {
+        if let Some(previous) = load_state(&path) {
+            state = WindowState { maximized: true, ..previous };
+        }
+    }
+    let generation = SAVE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    if immediate {
+        save_state(&path, &state);
+        return;
+    }
+    std::thread::spawn(move || {
+        std::thread::sleep(SAVE_DEBOUNCE);
+        if SAVE_GENERATION.load(Ordering::SeqCst) == generation {
+            save_state(&path, &state);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: Area = Area { x: 0.0, y: 0.0, width: 1920.0, height: 1080.0 };
+
+    #[test]
+    fn state_round_trips_and_on_screen_positions_are_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("window-state.json");
+        let state = WindowState { width: 1200.0, height: 800.0, x: 100.0, y: 50.0, maximized: false };
+        save_state(&path, &state);
+        assert_eq!(load_state(&path), Some(state));
+        assert_eq!(clamp_to_monitors(state, &[SCREEN]), state);
+    }
+
+    #[test]
+    fn window_from_a_missing_monitor_is_moved_on_screen() {
+        let state = WindowState { width: 2400.0, height: 800.0, x: 2500.0, y: 200.0, maximized: false };
+        let clamped = clamp_to_monitors(state, &[SCREEN]);
+        assert_eq!(clamped, WindowState { width: 1920.0, height: 800.0, x: 0.0, y: 140.0, maximized: false });
+
+        let second = Area { x: 1920.0, y: 0.0, width: 2560.0, height: 1440.0 };
+        assert_eq!(clamp_to_monitors(state, &[SCREEN, second]), state);
+    }
+}
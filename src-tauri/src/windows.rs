@@ -0,0 +1,449 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::documents::{DocState, Documents};
+use crate::files::OpenFilePayload;
+use crate::lock::LockExt;
+
+/// Label of the window created from `tauri.conf.json`.
+pub const MAIN_WINDOW: &str = "main";
+
+/// Extra document windows are labelled `doc-1`, `doc-2`, …
+const DOC_WINDOW_PREFIX: &str = "doc-";
+
+/// File each new window should open once its frontend asks via
+/// `get_opened_file`, keyed by window label.
+#[derive(Default)]
+pub struct WindowOpenQueue(pub Mutex<HashMap<String, String>>);
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// Take the files window `label` should open at startup: its own queued
+/// file first, then every app-wide pending launch file in arrival order.
+pub fn take_opened_files(queue: &mut HashMap<String, String>, pending: &mut Vec<String>, label: &str) -> Vec<String> {
+    queue.remove(label).into_iter().chain(pending.drain(..)).collect()
+}
+
+/// The first file `take_opened_files` would return, leaving the rest queued.
+pub fn take_opened_file(queue: &mut HashMap<String, String>, pending: &mut Vec<String>, label: &str) -> Option<String> {
+    queue.remove(label).or_else(|| (!pending.is_empty()).then(|| pending.remove(0)))
+}
+
+/// How many files `take_opened_files` would still hand out to `label`.
+pub fn opened_file_count(queue: &HashMap<String, String>, pending: &[String], label: &str) -> usize {
+    usize::from(queue.contains_key(label)) + pending.len()
+}
+
+/// Drop repeated paths from one batch of opened files, keeping the first
+/// occurrence of each.
+pub fn dedup_paths(paths: Vec<String>) -> Vec<String> {
+    let mut out: Vec<String> = Vec::with_capacity(paths.len());
+    for path in paths {
+        if !out.contains(&path) {
+            out.push(path);
+        }
+    }
+    out
+}
+
+/// Files to open from the process arguments. File associations launch us as
+/// `updown <path>...`: position 0 is the executable, every later argument
+/// that names an existing file with an openable extension is a document.
+/// Flags are ignored. Paths are made absolute without resolving symlinks
+/// (or, on Windows, turning into `\\?\` verbatim paths).
+pub fn launch_paths(args: impl IntoIterator<Item = String>) -> Vec<String> {
+    let paths = args
+        .into_iter()
+        .skip(1)
+        .filter(|a| !a.starts_with('-'))
+        .filter_map(|a| std::path::absolute(&a).ok())
+        .map(|p| crate::vault::normalize(&p))
+        .filter(|p| p.is_file())
+        .filter(|p| p.extension().is_some_and(|e| crate::settings::is_openable_extension(&e.to_string_lossy())))
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+    dedup_paths(paths)
+}
+
+/// What a drop onto a window should open: the openable documents, in drop
+/// order, or failing that the first dropped folder.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct DroppedPaths {
+    pub files: Vec<String>,
+    pub folder: Option<String>,
+}
+
+/// Sort dropped paths into documents to open and a folder for the panel.
+/// Files with other extensions are ignored.
+pub fn sort_dropped(paths: &[PathBuf]) -> DroppedPaths {
+    let files: Vec<String> = paths
+        .iter()
+        .filter(|p| p.is_file())
+        .filter(|p| p.extension().is_some_and(|e| crate::settings::is_openable_extension(&e.to_string_lossy())))
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+    let folder = if files.is_empty() {
+        paths.iter().find(|p| p.is_dir()).map(|p| p.to_string_lossy().to_string())
+    } else {
+        None
+    };
+    DroppedPaths { files: dedup_paths(files), folder }
+}
+
+/// Files dropped onto `window` from the OS. The first document opens in
+/// that window and any others get windows of their own; each is added to
+/// the recent list when its window opens it, as with every other open. A
+/// dropped folder is shown in the folder panel instead. Text dragged within
+/// the editor never gets here: the webview only reports drops from outside.
+pub fn files_dropped(window: &tauri::Window, paths: &[PathBuf]) {
+    let app = window.app_handle();
+    let dropped = sort_dropped(paths);
+    if let Some(folder) = dropped.folder {
+        if let Some(w) = app.get_webview_window(window.label()) {
+            crate::menu::send_action(&w, "openFolder", vec![folder.into()]);
+        }
+        return;
+    }
+    let Some((first, rest)) = dropped.files.split_first() else { return };
+    crate::open_file_in_window(app, window.label(), first, false);
+    for path in rest {
+        let _ = create_document_window(app, Some(path.clone()));
+    }
+}
+
+/// Document windows in cycling order: the main window first, then the rest
+/// sorted by label so the order doesn't depend on HashMap iteration.
+pub fn ordered_labels(mut labels: Vec<String>) -> Vec<String> {
+    labels.sort_by(|a, b| (a != MAIN_WINDOW, a).cmp(&(b != MAIN_WINDOW, b)));
+    labels
+}
+
+/// The window after (or before) `current` in cycling order, wrapping around.
+/// With no focused window the first (or last) one is picked.
+pub fn cycle_target(labels: &[String], current: Option<&str>, forward: bool) -> Option<String> {
+    if labels.is_empty() {
+        return None;
+    }
+    let len = labels.len();
+    let next = match current.and_then(|c| labels.iter().position(|l| l == c)) {
+        Some(i) if forward => (i + 1) % len,
+        Some(i) => (i + len - 1) % len,
+        None if forward => 0,
+        None => len - 1,
+    };
+    Some(labels[next].clone())
+}
+
+/// Label of the focused document window, if any. (`get_focused_window`
+/// needs Tauri's `unstable` feature.)
+pub fn focused_label(app: &tauri::AppHandle) -> Option<String> {
+    app.webview_windows()
+        .into_iter()
+        .find(|(_, w)| w.is_focused().unwrap_or(false))
+        .map(|(label, _)| label)
+}
+
+/// First `doc-N` label not already in use.
+pub fn next_window_label(existing: &[String]) -> String {
+    crate::files::first_free_name(|n| Some(format!("{DOC_WINDOW_PREFIX}{n}")).filter(|l| !existing.contains(l)))
+}
+
+/// Open a new document window, optionally queueing `path` for it to load;
+/// the window picks it up through `get_opened_file` once its frontend is
+/// ready. Returns the new window's label.
+pub fn create_document_window(app: &tauri::AppHandle, path: Option<String>) -> Result<String, String> {
+    let existing: Vec<String> = app.webview_windows().into_keys().collect();
+    let label = next_window_label(&existing);
+    if let Some(path) = &path {
+        app.state::<WindowOpenQueue>()
+            .0
+            .lock_or_recover()
+            .insert(label.clone(), path.clone());
+        app.state::<Documents>()
+            .0
+            .lock_or_recover()
+            .insert(label.clone(), DocState { path: Some(path.clone()), dirty: false });
+    }
+    // Same size and limits as the main window's config, but shown straight
+    // away (the main window waits for its saved geometry).
+    let mut config = app
+        .config()
+        .app
+        .windows
+        .first()
+        .cloned()
+        .ok_or("No window configuration to copy")?;
+    config.label = label.clone();
+    config.visible = true;
+    tauri::WebviewWindowBuilder::from_config(app, &config)
+        .and_then(|builder| builder.theme(crate::theme::load_theme(app).native()).build())
+        .map_err(|e| format!("Failed to create window: {}", e))?;
+    Ok(label)
+}
+
+fn raise(window: &tauri::WebviewWindow) {
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+}
+
+/// A window a file can be opened in. Abstracted so the focus decision in
+/// `deliver_open` can be tested without a real window.
+pub trait OpenTarget {
+    fn open_path(&self, file: &OpenFilePayload);
+    fn raise(&self);
+}
+
+/// Script handing `path` to the frontend's `__openFile`, with
+/// `{ readOnly: true }` for read-only opens. The path goes in as a JSON
+/// string literal, so quotes, backslashes, newlines and the like can't end
+/// the call early; the two line separators JSON leaves raw are escaped too.
+pub fn open_script(path: &str, read_only: bool) -> String {
+    let literal = serde_json::to_string(path)
+        .unwrap_or_else(|_| "\"\"".to_string())
+        .replace('\u{2028}', "\\u2028")
+        .replace('\u{2029}', "\\u2029");
+    let options = if read_only { ", { readOnly: true }" } else { "" };
+    format!("window.__openFile && window.__openFile({}{})", literal, options)
+}
+
+impl OpenTarget for tauri::WebviewWindow {
+    fn open_path(&self, file: &OpenFilePayload) {
+        let _ = self.eval(open_script(&file.path, file.read_only));
+    }
+
+    fn raise(&self) {
+        raise(self);
+    }
+}
+
+/// Open `file` in `target`, raising it only when `focus` is set so
+/// background opens don't steal focus.
+pub fn deliver_open<T: OpenTarget>(target: &T, file: &OpenFilePayload, focus: bool) {
+    target.open_path(file);
+    if focus {
+        target.raise();
+    }
+}
+
+/// Open `path` in `target` the way every normal open does: files we can't
+/// write go in read-only. Returns what was sent.
+pub fn open_path_in<T: OpenTarget>(target: &T, path: &str, focus: bool) -> OpenFilePayload {
+    let file = crate::files::open_payload(path.to_string(), false);
+    deliver_open(target, &file, focus);
+    file
+}
+
+/// Un-minimize and raise every window, leaving the focused one on top.
+pub fn bring_all_to_front(app: &tauri::AppHandle) {
+    let focused = focused_label(app);
+    let labels = ordered_labels(app.webview_windows().into_keys().collect());
+    for label in labels.iter().filter(|l| Some(*l) != focused.as_ref()) {
+        if let Some(w) = app.get_webview_window(label) {
+            raise(&w);
+        }
+    }
+    if let Some(w) = focused.and_then(|l| app.get_webview_window(l.as_str())) {
+        raise(&w);
+    }
+}
+
+// ── Tauri commands ────────────────────────────────────────────────────────────
+
+/// Move focus to the next (or previous) document window.
+#[tauri::command]
+pub fn cycle_windows(app: tauri::AppHandle, forward: bool) {
+    let labels = ordered_labels(app.webview_windows().into_keys().collect());
+    let current = focused_label(&app);
+    if let Some(w) = cycle_target(&labels, current.as_deref(), forward)
+        .and_then(|l| app.get_webview_window(l.as_str()))
+    {
+        raise(&w);
+    }
+}
+
+/// Open a document window, empty or showing `path`, and return its label.
+#[tauri::command]
+pub fn create_window(app: tauri::AppHandle, path: Option<String>) -> Result<String, String> {
+    create_document_window(&app, path)
+}
+
+/// Open the platform print dialog for the calling window. The page's print
+/// stylesheet hides everything but the rendered preview. Tauri maps this to
+/// WKWebView printing on macOS, WebView2 on Windows and WebKitGTK on Linux;
+/// where none of those is available it returns the error to show.
+#[tauri::command]
+pub fn print_preview(window: tauri::WebviewWindow) -> Result<(), String> {
+    window.print().map_err(|e| format!("Failed to print: {}", e))
+}
+
+/// Open the focused window's document in a second window.
+#[tauri::command]
+pub fn duplicate_window(app: tauri::AppHandle) -> Result<(), String> {
+    let label = focused_label(&app).unwrap_or_else(|| MAIN_WINDOW.to_string());
+    let path = app
+        .state::<Documents>()
+        .0
+        .lock_or_recover()
+        .get(&label)
+        .and_then(|d| d.path.clone())
+        .ok_or("No saved document is open in this window")?;
+    create_document_window(&app, Some(path)).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct FakeWindow(RefCell<Vec<String>>);
+
+    impl OpenTarget for FakeWindow {
+        fn open_path(&self, file: &OpenFilePayload) {
+            let mode = if file.read_only { " (read-only)" } else { "" };
+            self.0.borrow_mut().push(format!("open {}{mode}", file.path));
+        }
+
+        fn raise(&self) {
+            self.0.borrow_mut().push("raise".to_string());
+        }
+    }
+
+    fn labels(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn main_window_sorts_first() {
+        assert_eq!(
+            ordered_labels(labels(&["doc-2", "main", "doc-1"])),
+            labels(&["main", "doc-1", "doc-2"])
+        );
+    }
+
+    #[test]
+    fn new_window_gets_a_distinct_label() {
+        assert_eq!(next_window_label(&labels(&["main"])), "doc-1");
+        assert_eq!(next_window_label(&labels(&["main", "doc-1", "doc-3"])), "doc-2");
+    }
+
+    #[test]
+    fn cycling_wraps_in_both_directions() {
+        let order = labels(&["main", "doc-1", "doc-2"]);
+        assert_eq!(cycle_target(&order, Some("main"), true).as_deref(), Some("doc-1"));
+        assert_eq!(cycle_target(&order, Some("doc-2"), true).as_deref(), Some("main"));
+        assert_eq!(cycle_target(&order, Some("main"), false).as_deref(), Some("doc-2"));
+        assert_eq!(cycle_target(&order, None, true).as_deref(), Some("main"));
+        assert_eq!(cycle_target(&[], Some("main"), true), None);
+    }
+
+    #[test]
+    fn background_open_does_not_raise() {
+        let file = |read_only| OpenFilePayload { path: "/notes/a.md".into(), read_only };
+        let window = FakeWindow::default();
+        deliver_open(&window, &file(false), false);
+        assert_eq!(*window.0.borrow(), vec!["open /notes/a.md"]);
+
+        let window = FakeWindow::default();
+        deliver_open(&window, &file(true), true);
+        assert_eq!(*window.0.borrow(), vec!["open /notes/a.md (read-only)", "raise"]);
+    }
+
+    #[test]
+    fn unwritable_files_open_read_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("locked.md");
+        std::fs::write(&path, "# Locked").unwrap();
+        let path = path.to_string_lossy().to_string();
+        let window = FakeWindow::default();
+        assert!(!open_path_in(&window, &path, false).read_only);
+
+        let mut perms = std::fs::metadata(&path).unwrap().permissions();
+        perms.set_readonly(true);
+        std::fs::set_permissions(&path, perms).unwrap();
+        assert!(open_path_in(&window, &path, false).read_only);
+        assert_eq!(*window.0.borrow(), vec![format!("open {}", path), format!("open {} (read-only)", path)]);
+    }
+
+    #[test]
+    fn adversarial_paths_stay_inside_the_string_literal() {
+        let prefix = "window.__openFile && window.__openFile(";
+        for path in [
+            "/notes/it's \"quoted\".md",
+            "C:\\Users\\me\\notes.md",
+            "/notes/line\nbreak\r.md",
+            "/notes/</script><script>alert(1)</script>.md",
+            "/notes/'); alert(1); ('.md",
+            "/notes/naïve café 🎉\u{2028}.md",
+        ] {
+            let js = open_script(path, false);
+            assert_eq!(open_script(path, true), format!("{}, {{ readOnly: true }})", js.strip_suffix(')').unwrap()));
+            let literal = js.strip_prefix(prefix).and_then(|rest| rest.strip_suffix(')')).unwrap();
+            assert!(!literal.contains(['\n', '\r', '\u{2028}']));
+            assert_eq!(serde_json::from_str::<String>(literal).unwrap(), path);
+        }
+    }
+
+    #[test]
+    fn pending_count_drops_to_zero_after_draining() {
+        let mut queue = HashMap::from([("doc-1".to_string(), "/b.md".to_string())]);
+        let mut pending = vec!["/a.md".to_string(), "/c.md".to_string()];
+        assert_eq!(opened_file_count(&queue, &pending, "doc-1"), 3);
+        assert_eq!(opened_file_count(&queue, &pending, "main"), 2);
+
+        assert_eq!(take_opened_file(&mut queue, &mut pending, "doc-1").as_deref(), Some("/b.md"));
+        assert_eq!(take_opened_file(&mut queue, &mut pending, "doc-1").as_deref(), Some("/a.md"));
+        assert_eq!(opened_file_count(&queue, &pending, "doc-1"), 1);
+        assert_eq!(take_opened_file(&mut queue, &mut pending, "doc-1").as_deref(), Some("/c.md"));
+        assert_eq!(take_opened_file(&mut queue, &mut pending, "doc-1"), None);
+    }
+
+    #[test]
+    fn every_queued_launch_file_is_handed_out() {
+        let mut queue = HashMap::from([("main".to_string(), "/own.md".to_string())]);
+        let mut pending = vec!["/a.md".to_string(), "/b.md".to_string()];
+        assert_eq!(take_opened_files(&mut queue, &mut pending, "main"), vec!["/own.md", "/a.md", "/b.md"]);
+        assert_eq!(opened_file_count(&queue, &pending, "main"), 0);
+        assert!(take_opened_files(&mut queue, &mut pending, "main").is_empty());
+    }
+
+    #[test]
+    fn opened_batch_is_deduplicated_in_order() {
+        let paths = ["/b.md", "/a.md", "/b.md", "/c.md", "/a.md"].map(String::from).to_vec();
+        assert_eq!(dedup_paths(paths), vec!["/b.md", "/a.md", "/c.md"]);
+    }
+
+    #[test]
+    fn drops_open_documents_or_else_a_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let note = dir.path().join("note.md");
+        let image = dir.path().join("image.png");
+        std::fs::write(&note, "# Note").unwrap();
+        std::fs::write(&image, "").unwrap();
+        let note_str = note.to_string_lossy().to_string();
+        let dir_str = dir.path().to_string_lossy().to_string();
+
+        let dropped = sort_dropped(&[image.clone(), dir.path().to_path_buf(), note.clone(), note.clone()]);
+        assert_eq!(dropped, DroppedPaths { files: vec![note_str], folder: None });
+        let dropped = sort_dropped(&[image, dir.path().to_path_buf()]);
+        assert_eq!(dropped, DroppedPaths { files: vec![], folder: Some(dir_str) });
+    }
+
+    #[test]
+    fn launch_paths_skip_the_executable_flags_and_missing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let note = dir.path().join("note.md");
+        std::fs::write(&note, "# Note").unwrap();
+        let script = dir.path().join("run.sh");
+        std::fs::write(&script, "#!/bin/sh").unwrap();
+        let note = note.to_string_lossy().to_string();
+        let dotted = dir.path().join(".").join("note.md").to_string_lossy().to_string();
+        let script = script.to_string_lossy().to_string();
+        let args = [note.clone(), "--flag".into(), dotted, "/no/such/file.md".into(), script, note.clone()];
+        assert_eq!(launch_paths(args), vec![note]);
+    }
+}
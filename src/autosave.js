@@ -1,16 +1,25 @@
 /**
  * Autosave module for UpDown.
  * Debounced save on editor input when a file is already associated.
+ * In the desktop app the backend owns the debounce (and the interval
+ * setting); the browser build debounces here.
  */
-import { getCurrentFilePath, checkDirty, fileSave } from './file-ops.js';
+import { getCurrentFilePath, checkDirty, fileSave, markFileSaved } from './file-ops.js';
 import { debounce } from './utils.js';
+import { getStorageProvider } from './storage/provider.js';
 
 /**
  * Set up autosave: on editor input, mark dirty and debounce-save.
  * @param {HTMLTextAreaElement} editor
- * @param {number} [delayMs=1500] - autosave debounce delay in ms
+ * @param {number} [delayMs=1500] - autosave debounce delay in ms (browser only)
  */
 export function setupAutosave(editor, delayMs = 1500) {
+  const invoke = window.__TAURI__?.core?.invoke;
+  // Content last handed to the backend, per path, to mark clean once saved
+  const scheduled = new Map();
+  // Paths already warned about an auto-save conflict, until they save again
+  const conflicted = new Set();
+
   const debouncedSave = debounce(() => {
     if (getCurrentFilePath()) {
       fileSave(editor).catch(err => console.error('Autosave failed:', err));
@@ -19,11 +28,43 @@ export function setupAutosave(editor, delayMs = 1500) {
 
   function onInput() {
     checkDirty(editor.value);
-    if (getCurrentFilePath()) {
+    const path = getCurrentFilePath();
+    if (!path) return;
+    if (invoke) {
+      scheduled.set(path, editor.value);
+      const disk = getStorageProvider()?.diskStamp?.(path) ?? null;
+      invoke('schedule_autosave', { path, content: editor.value, disk }).catch(err => console.error('Autosave failed:', err));
+    } else {
       debouncedSave();
     }
   }
 
+  if (invoke) {
+    window.__TAURI__.event.listen('auto-saved', (event) => {
+      const { path, disk } = event.payload;
+      getStorageProvider()?.noteDiskStamp?.(path, disk);
+      conflicted.delete(path);
+      if (path === getCurrentFilePath() && scheduled.has(path)) {
+        markFileSaved(scheduled.get(path));
+        checkDirty(editor.value);
+      }
+      scheduled.delete(path);
+    });
+    // The file changed on disk since it was opened or saved: leave it alone
+    // and tell the user once, rather than on every keystroke.
+    window.__TAURI__.event.listen('auto-save-conflict', (event) => {
+      const path = event.payload;
+      scheduled.delete(path);
+      if (path !== getCurrentFilePath() || conflicted.has(path)) return;
+      conflicted.add(path);
+      const name = path.split(/[\\/]/).pop();
+      window.__TAURI__.dialog.message(
+        `${name} was changed by another program, so it was not auto-saved. Save to overwrite it, or reopen it to load the other changes.`,
+        { title: 'UpDown', kind: 'warning' }
+      ).catch(() => {});
+    });
+  }
+
   editor.addEventListener('input', onInput);
   editor.addEventListener('paste', onInput);
 }
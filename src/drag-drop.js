@@ -4,6 +4,7 @@
  *  - Tauri: uses native onDragDropEvent (receives file paths)
  *  - Web:   uses HTML5 File API (reads file content via FileReader)
  */
+import { isOpenablePath } from './utils.js';
 
 /**
  * Read a File object as text.
@@ -62,7 +63,7 @@ export function setupWebDragDrop(editor, refreshPreview, onAfterLoad) {
     overlay.classList.remove('visible');
 
     const files = Array.from(e.dataTransfer?.files || []);
-    const mdFile = files.find(f => /\.(md|markdown)$/i.test(f.name)) || files[0];
+    const mdFile = files.find(f => isOpenablePath(f.name)) || files[0];
     if (!mdFile) return;
 
     try {
@@ -77,14 +78,11 @@ export function setupWebDragDrop(editor, refreshPreview, onAfterLoad) {
 }
 
 /**
- * Set up drag-and-drop file opening (Tauri desktop).
- * Shows a visual overlay when hovering, opens the first .md file on drop.
- * @param {HTMLTextAreaElement} editor
- * @param {function} refreshPreview
- * @param {function} fileOpenPath - (path, editor, refreshPreview) => Promise
- * @param {function} [onAfterOpen] - optional callback after file opens, receives file path
+ * Set up the drop overlay for the Tauri desktop app. The backend opens
+ * dropped files itself (see `files_dropped` in windows.rs); this only shows
+ * the hover feedback.
  */
-export function setupDragDrop(editor, refreshPreview, fileOpenPath, onAfterOpen) {
+export function setupDragDrop() {
   if (!window.__TAURI__) return;
 
   const app = document.getElementById('app');
@@ -100,23 +98,6 @@ export function setupDragDrop(editor, refreshPreview, fileOpenPath, onAfterOpen)
   const webview = getCurrentWebview();
 
   webview.onDragDropEvent((event) => {
-    const { type } = event.payload;
-
-    if (type === 'over') {
-      overlay.classList.add('visible');
-    } else if (type === 'drop') {
-      overlay.classList.remove('visible');
-      const paths = event.payload.paths || [];
-      // Open the first markdown-like file (or just the first file)
-      const mdFile = paths.find(p => /\.(md|markdown)$/i.test(p)) || paths[0];
-      if (mdFile) {
-        fileOpenPath(mdFile, editor, refreshPreview).then(() => {
-          if (onAfterOpen) onAfterOpen(mdFile);
-        });
-      }
-    } else {
-      // cancelled
-      overlay.classList.remove('visible');
-    }
+    overlay.classList.toggle('visible', event.payload.type === 'enter' || event.payload.type === 'over');
   });
 }
@@ -51,12 +51,13 @@ export function setMdCommandHandler(handler) {
  * Execute a registered action by name.
  * Checks view action handlers first, then file action handlers.
  * @param {string} action - action identifier (e.g. 'new', 'open', 'save', 'saveAs', 'toggleFolder')
+ * @param {...*} args - passed through to the handler (e.g. the folder for 'openFolder')
  */
-export function onAction(action) {
+export function onAction(action, ...args) {
   if (viewActionHandlers[action]) {
-    viewActionHandlers[action]();
+    viewActionHandlers[action](...args);
   } else if (fileActionHandlers[action]) {
-    fileActionHandlers[action]();
+    fileActionHandlers[action](...args);
   }
 }
 
@@ -99,6 +100,36 @@ function applyZoom(doc) {
   if (zoomIn) zoomIn.disabled = previewZoom >= ZOOM_MAX;
 }
 
+/**
+ * Record the zoom so it survives restarts (Tauri only).
+ */
+function persistZoom() {
+  window.__TAURI__?.core?.invoke('set_zoom', { factor: previewZoom / 100 }).catch(() => {});
+}
+
+/**
+ * Zoom the preview in (`steps` > 0) or out by whole steps, within limits.
+ * @param {Document} doc
+ * @param {number} steps
+ */
+export function stepZoom(doc, steps) {
+  previewZoom = Math.min(ZOOM_MAX, Math.max(ZOOM_MIN, previewZoom + steps * ZOOM_STEP));
+  applyZoom(doc);
+  persistZoom();
+}
+
+/**
+ * Set the preview zoom to `percent` (100 = actual size).
+ * @param {Document} doc
+ * @param {number} percent
+ * @param {boolean} [persist=true] - false when restoring the saved value
+ */
+export function setZoom(doc, percent, persist = true) {
+  previewZoom = Math.min(ZOOM_MAX, Math.max(ZOOM_MIN, Math.round(percent)));
+  applyZoom(doc);
+  if (persist) persistZoom();
+}
+
 /**
  * Set up all toolbar button event listeners.
  */
@@ -114,11 +145,9 @@ export function setupToolbar(doc) {
       } else if (md) {
         if (mdCommandHandler) mdCommandHandler(md);
       } else if (action === 'zoomIn') {
-        previewZoom = Math.min(ZOOM_MAX, previewZoom + ZOOM_STEP);
-        applyZoom(doc);
+        stepZoom(doc, 1);
       } else if (action === 'zoomOut') {
-        previewZoom = Math.max(ZOOM_MIN, previewZoom - ZOOM_STEP);
-        applyZoom(doc);
+        stepZoom(doc, -1);
       } else if (action) {
         onAction(action);
       }
@@ -8,6 +8,7 @@ import { getStorageProvider, hasStorageCapability } from './storage/provider.js'
 let currentFilePath = null;
 let currentFileDisplayName = null; // human-readable name (set when Drive ID is used as path)
 let dirty = false;
+let readOnly = false; // the open file can't be saved in place
 let savedContent = '';
 
 export function getCurrentFilePath() {
@@ -39,14 +40,24 @@ export function isDirty() {
 function updateTitle() {
   const name = currentFileDisplayName || (currentFilePath ? basename(currentFilePath) : 'Untitled');
   const modifier = dirty ? ' *' : '';
-  const title = `${name}${modifier} — UpDown`;
+  const lock = readOnly ? ' (Read Only)' : '';
+  const title = `${name}${lock}${modifier} — UpDown`;
   document.title = title;
   // Update native Tauri window title via IPC
   if (window.__TAURI__?.core?.invoke) {
     window.__TAURI__.core.invoke('plugin:window|set_title', {
-      label: 'main',
+      label: window.__TAURI__.window?.getCurrentWindow().label ?? 'main',
       value: title,
     }).catch(() => {});
+    // Per-window document state for the quit prompt and document commands
+    window.__TAURI__.core.invoke('set_dirty', {
+      path: currentFilePath || null,
+      dirty,
+    }).catch(() => {});
+    // Save / Save As / Reveal are grayed out until there is a document
+    window.__TAURI__.core.invoke('set_document_open', {
+      open: Boolean(currentFilePath) || dirty,
+    }).catch(() => {});
   }
 }
 
@@ -91,6 +102,99 @@ export function checkDirty(content) {
   }
 }
 
+/**
+ * Point the backend's external-change watch at `path` (or nothing),
+ * dropping the watch on the previous file.
+ * @param {string|null} previous
+ * @param {string|null} path
+ */
+function watchFile(previous, path) {
+  const invoke = window.__TAURI__?.core?.invoke;
+  if (!invoke || previous === path) return;
+  if (previous) invoke('unwatch_file', { path: previous }).catch(() => {});
+  if (path) invoke('watch_file', { path }).catch(() => {});
+}
+
+/**
+ * Tell the backend `previous` is no longer shown, for "Reopen Last Closed
+ * File", unless the window is just reloading it.
+ * @param {string|null} previous
+ * @param {string|null} next
+ */
+function noteClosed(previous, next) {
+  const invoke = window.__TAURI__?.core?.invoke;
+  if (!invoke || !previous || previous === next) return;
+  invoke('note_closed', { path: previous }).catch(() => {});
+}
+
+/**
+ * The open file changed on disk (`file-changed` event). Reload it unless the
+ * buffer has unsaved edits or the disk already matches what we saved.
+ * @param {string} path
+ * @param {HTMLTextAreaElement} editor
+ * @param {function} refreshPreview
+ */
+export async function handleExternalChange(path, editor, refreshPreview) {
+  if (path !== currentFilePath || dirty) return;
+  const provider = getStorageProvider();
+  if (!provider?.readFile) return;
+  try {
+    const content = await provider.readFile(path);
+    if (content === savedContent) return;
+    editor.value = content;
+    markClean(content);
+    refreshPreview();
+  } catch (err) {
+    console.error('Failed to reload changed file:', path, err);
+  }
+}
+
+/**
+ * Remember the caret and scroll position in the current file (Tauri only).
+ * @param {HTMLTextAreaElement} editor
+ */
+export function saveViewState(editor) {
+  const invoke = window.__TAURI__?.core?.invoke;
+  if (!invoke || !currentFilePath || !editor) return;
+  const range = editor.scrollHeight - editor.clientHeight;
+  invoke('save_view_state', {
+    path: currentFilePath,
+    cursor: editor.selectionStart,
+    scroll: range > 0 ? editor.scrollTop / range : 0,
+  }).catch(() => {});
+}
+
+/**
+ * Put the caret and scroll position back where they were last time `path`
+ * was open.
+ * @param {string} path
+ * @param {HTMLTextAreaElement} editor
+ */
+async function restoreViewState(path, editor) {
+  const invoke = window.__TAURI__?.core?.invoke;
+  if (!invoke) return;
+  const state = await invoke('get_view_state', { path }).catch(() => null);
+  if (!state || path !== currentFilePath) return;
+  const cursor = Math.min(state.cursor, editor.value.length);
+  editor.setSelectionRange(cursor, cursor);
+  editor.scrollTop = state.scroll * (editor.scrollHeight - editor.clientHeight);
+}
+
+/**
+ * Title the window with the document's front-matter `title`, when it has
+ * one, instead of the file name (Tauri only).
+ * @param {string} path
+ */
+function showFrontMatterTitle(path) {
+  const invoke = window.__TAURI__?.core?.invoke;
+  if (!invoke) return;
+  invoke('read_file_with_meta', { path })
+    .then((meta) => {
+      if (path === currentFilePath) setCurrentFileName(meta.title);
+    })
+    .catch(() => {});
+}
+
 /**
  * Extract filename from a path or id.
  * @param {string} path
@@ -105,12 +209,18 @@ function basename(path) {
  * New file: clear editor, reset path and title.
  * @param {HTMLTextAreaElement} editor
  * @param {function} refreshPreview - re-render the preview
+ * @param {string} [content] - starting text (e.g. from a template); unsaved
  */
-export function fileNew(editor, refreshPreview) {
-  editor.value = '';
+export function fileNew(editor, refreshPreview, content = '') {
+  saveViewState(editor);
+  editor.value = content;
+  watchFile(currentFilePath, null);
+  noteClosed(currentFilePath, null);
   currentFilePath = null;
   currentFileDisplayName = null;
+  readOnly = false;
   markClean('');
+  if (content) markDirty();
   refreshPreview();
 }
 
@@ -145,17 +255,24 @@ export async function fileRefresh(editor, refreshPreview) {
  * @param {string} fileId
  * @param {HTMLTextAreaElement} editor
  * @param {function} refreshPreview
+ * @param {{ readOnly?: boolean }} [options] readOnly: open without allowing saves in place
  */
-export async function fileOpenPath(fileId, editor, refreshPreview) {
+export async function fileOpenPath(fileId, editor, refreshPreview, options = {}) {
   const provider = getStorageProvider();
   if (!provider?.readFile) return;
 
   try {
     const content = await provider.readFile(fileId);
+    saveViewState(editor);
     editor.value = content;
+    watchFile(currentFilePath, fileId);
+    noteClosed(currentFilePath, fileId);
     currentFilePath = fileId;
+    readOnly = Boolean(options.readOnly || provider.isReadOnly?.(fileId));
     markClean(content);
     refreshPreview();
+    await restoreViewState(fileId, editor);
+    showFrontMatterTitle(fileId);
 
     // Record in recent files (Tauri native "Open Recent" menu)
     if (window.__TAURI__?.core?.invoke) {
@@ -224,7 +341,9 @@ export async function fileSaveAs(editor) {
     } else {
       fileId = await provider.createFile(result.parentId, result.name, editor.value);
     }
+    watchFile(currentFilePath, fileId);
     currentFilePath = fileId;
+    readOnly = false;
     markClean(editor.value);
   } catch (err) {
     showError(`Failed to save file: ${err.message || err}`);
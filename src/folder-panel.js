@@ -12,6 +12,12 @@ const STORAGE_KEY = 'updown-last-folder';
 let currentFolder = null;
 let onFileSelect = null; // callback: (fileId) => void
 
+// Directories expanded in place under `expandedRoot`, saved per folder
+let expandedRoot = null;
+let expanded = new Set();
+// What the list currently shows, so toggling a directory can re-render it
+let listed = { entries: [], hasParent: false };
+
 /**
  * Get the last used folder from localStorage, default to root.
  * @returns {string}
@@ -83,15 +89,104 @@ function getDisplayPath(folderId) {
   return display || '/';
 }
 
+/**
+ * Load the saved expanded directories for `root` (Tauri only).
+ * @param {string} root
+ * @returns {Promise<Set<string>>}
+ */
+async function loadExpanded(root) {
+  const invoke = window.__TAURI__?.core?.invoke;
+  if (!invoke) return new Set();
+  const paths = await invoke('get_expanded', { root }).catch(() => []);
+  return new Set(paths);
+}
+
+/**
+ * Save the expanded directories for the current folder (Tauri only).
+ */
+function saveExpanded() {
+  window.__TAURI__?.core?.invoke('set_expanded', { root: expandedRoot, paths: [...expanded] }).catch(() => {});
+}
+
+/**
+ * Expand or collapse a directory in place and re-render the list.
+ * @param {string} dirId
+ * @param {HTMLElement} listEl
+ */
+async function toggleExpanded(dirId, listEl) {
+  if (expanded.has(dirId)) {
+    expanded.delete(dirId);
+  } else {
+    expanded.add(dirId);
+  }
+  saveExpanded();
+  await renderList(listEl, listed.entries, listed.hasParent);
+}
+
+/**
+ * Create the list item for one entry, indented by `depth`.
+ * @param {{id: string, name: string, isDirectory: boolean}} entry
+ * @param {HTMLElement} listEl
+ * @param {number} depth
+ * @returns {HTMLElement}
+ */
+function createEntryItem(entry, listEl, depth) {
+  const li = document.createElement('div');
+  li.className = 'folder-item ' + (entry.isDirectory ? 'folder-dir' : 'folder-file');
+  li.title = entry.id;
+  li.dataset.id = entry.id;
+  li.dataset.isDir = String(entry.isDirectory);
+  if (depth > 0) li.style.paddingLeft = `${0.6 + depth}rem`;
+
+  if (entry.isDirectory) {
+    const toggle = document.createElement('span');
+    toggle.className = 'folder-toggle';
+    toggle.textContent = expanded.has(entry.id) ? '▾' : '▸';
+    toggle.title = expanded.has(entry.id) ? 'Collapse' : 'Expand';
+    toggle.addEventListener('click', (e) => {
+      e.stopPropagation();
+      toggleExpanded(entry.id, listEl);
+    });
+    li.append(toggle, `📁 ${entry.name}`);
+    li.addEventListener('click', () => navigateTo(entry.id, listEl));
+  } else {
+    li.textContent = entry.name;
+    li.addEventListener('click', () => {
+      if (onFileSelect) onFileSelect(entry.id, entry.name);
+      listEl.querySelectorAll('.folder-item').forEach(el => el.classList.remove('selected'));
+      li.classList.add('selected');
+    });
+    li.addEventListener('contextmenu', (e) => showFileContextMenu(e, entry.id));
+  }
+  return li;
+}
+
+/**
+ * Append items for `entries` to `parent`, followed under each expanded
+ * directory by its own entries.
+ * @param {Node} parent
+ * @param {Array} entries
+ * @param {HTMLElement} listEl
+ * @param {number} depth
+ */
+async function appendEntries(parent, entries, listEl, depth) {
+  for (const entry of entries) {
+    parent.appendChild(createEntryItem(entry, listEl, depth));
+    if (entry.isDirectory && expanded.has(entry.id)) {
+      await appendEntries(parent, await readDirectory(entry.id), listEl, depth + 1);
+    }
+  }
+}
+
 /**
  * Render the folder list into the panel element.
  * @param {HTMLElement} listEl
  * @param {Array} entries
- * @param {string} folderId
  * @param {boolean} hasParent - whether to show ".." entry
  */
-function renderList(listEl, entries, folderId, hasParent) {
-  listEl.innerHTML = '';
+async function renderList(listEl, entries, hasParent) {
+  listed = { entries, hasParent };
+  const items = document.createDocumentFragment();
 
   // ".." entry (go to parent), unless at root
   if (hasParent) {
@@ -101,36 +196,40 @@ function renderList(listEl, entries, folderId, hasParent) {
     li.title = 'Go to parent folder';
     li.dataset.parent = 'true';
     li.addEventListener('click', () => navigateToParent(listEl));
-    listEl.appendChild(li);
+    items.appendChild(li);
   }
 
-  for (const entry of entries) {
-    const li = document.createElement('div');
-    li.className = 'folder-item ' + (entry.isDirectory ? 'folder-dir' : 'folder-file');
-    li.textContent = entry.isDirectory ? `📁 ${entry.name}` : entry.name;
-    li.title = entry.id;
-    li.dataset.id = entry.id;
-    li.dataset.isDir = String(entry.isDirectory);
-
-    if (entry.isDirectory) {
-      li.addEventListener('click', () => navigateTo(entry.id, listEl));
-    } else {
-      li.addEventListener('click', () => {
-        if (onFileSelect) onFileSelect(entry.id, entry.name);
-        listEl.querySelectorAll('.folder-item').forEach(el => el.classList.remove('selected'));
-        li.classList.add('selected');
-      });
-    }
-
-    listEl.appendChild(li);
-  }
+  await appendEntries(items, entries, listEl, 0);
 
   if (entries.length === 0 && !hasParent) {
     const empty = document.createElement('div');
     empty.className = 'folder-empty';
     empty.textContent = 'No markdown files';
-    listEl.appendChild(empty);
+    items.appendChild(empty);
   }
+  listEl.replaceChildren(items);
+}
+
+/**
+ * Native context menu for a file entry; offers "Remove from Recent" when the
+ * file is on the Open Recent list (Tauri only).
+ * @param {MouseEvent} event
+ * @param {string} fileId
+ */
+async function showFileContextMenu(event, fileId) {
+  const tauri = window.__TAURI__;
+  if (!tauri?.menu?.Menu) return;
+  event.preventDefault();
+  const recent = await tauri.core.invoke('get_recent_files').catch(() => []);
+  if (!recent.includes(fileId)) return;
+  const menu = await tauri.menu.Menu.new({
+    items: [{
+      id: 'remove_recent',
+      text: 'Remove from Recent',
+      action: () => tauri.core.invoke('remove_recent_file', { path: fileId }).catch(() => {}),
+    }],
+  });
+  await menu.popup();
 }
 
 /**
@@ -145,13 +244,16 @@ async function navigateToParent(listEl) {
 }
 
 /**
- * Navigate to a folder: read it, render list, save to storage.
+ * Navigate to a folder: read it, restore its expanded directories, render
+ * list, save to storage.
  * @param {string} folderId
  * @param {HTMLElement} listEl
  */
 async function navigateTo(folderId, listEl) {
   currentFolder = folderId;
   saveFolder(folderId);
+  window.__TAURI__?.core?.invoke('add_recent_folder', { path: folderId }).catch(() => {});
+  window.__TAURI__?.core?.invoke('set_last_folder', { path: folderId }).catch(() => {});
 
   // Update the folder path display — show a fallback immediately,
   // then resolve the human-readable name from the provider.
@@ -167,11 +269,16 @@ async function navigateTo(folderId, listEl) {
     }
   }
 
+  if (folderId !== expandedRoot) {
+    expandedRoot = folderId;
+    expanded = await loadExpanded(folderId);
+  }
+
   const entries = await readDirectory(folderId);
   const parentId = await getParentFolderId(folderId);
   const hasParent = parentId !== null && parentId !== undefined;
 
-  renderList(listEl, entries, folderId, hasParent);
+  await renderList(listEl, entries, hasParent);
 }
 
 /**
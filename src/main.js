@@ -4,19 +4,21 @@
  */
 import { setStorageProvider } from './storage/provider.js';
 import { createTauriProvider } from './storage/tauri-provider.js';
-import { setupToolbar, setViewMode, getViewMode, setFileActionHandlers, setViewActionHandlers, setMdCommandHandler, onAction } from './editor-ui.js';
+import { setupToolbar, setViewMode, getViewMode, setFileActionHandlers, setViewActionHandlers, setMdCommandHandler, onAction, stepZoom, setZoom } from './editor-ui.js';
 import { setupLivePreview } from './render.js';
-import { fileNew, fileOpen, fileOpenPath, fileRefresh, fileSave, fileSaveAs, getCurrentFilePath } from './file-ops.js';
+import { fileNew, fileOpen, fileOpenPath, fileRefresh, fileSave, fileSaveAs, getCurrentFilePath, handleExternalChange, isDirty, saveViewState } from './file-ops.js';
 import { setupDragDrop } from './drag-drop.js';
 import { setupAutosave } from './autosave.js';
-import { setupFolderPanel, setupPanelResize, toggleFolderPanel, syncToFile } from './folder-panel.js';
+import { setupFolderPanel, setupPanelResize, toggleFolderPanel, syncToFile, navigateToFolder, getCurrentFolder, refreshFolder } from './folder-panel.js';
 import { execMdCommand } from './md-commands.js';
+import { loadOpenableExtensions } from './utils.js';
 
 window.addEventListener('DOMContentLoaded', () => {
   // Set storage provider for Tauri (local file system)
   const tauriProvider = createTauriProvider();
   if (tauriProvider) {
     setStorageProvider(tauriProvider);
+    loadOpenableExtensions();
   }
   const editor = document.getElementById('editor');
   const preview = document.getElementById('preview');
@@ -27,8 +29,35 @@ window.addEventListener('DOMContentLoaded', () => {
   }
 
   // Wire file actions to file-ops module
+  const newFile = async () => {
+    if (isDirty() && window.__TAURI__?.dialog?.confirm) {
+      const discard = await window.__TAURI__.dialog.confirm(
+        'Discard unsaved changes and start a new document?',
+        { title: 'UpDown', kind: 'warning', okLabel: 'Discard', cancelLabel: 'Cancel' }
+      );
+      if (!discard) return;
+    }
+    fileNew(editor, refreshPreview);
+  };
+
+  // File › New from Template: the backend fills in the template's placeholders
+  const newFromTemplate = async (templateId) => {
+    try {
+      const content = await window.__TAURI__.core.invoke('new_from_template', { templateId });
+      if (isDirty() && !await window.__TAURI__.dialog.confirm(
+        'Discard unsaved changes and start a new document?',
+        { title: 'UpDown', kind: 'warning', okLabel: 'Discard', cancelLabel: 'Cancel' }
+      )) return;
+      fileNew(editor, refreshPreview, content);
+    } catch (err) {
+      await window.__TAURI__.dialog.message('Could not use template:\n' + err, { title: 'UpDown', kind: 'error' });
+    }
+  };
+
   setFileActionHandlers({
-    new: () => fileNew(editor, refreshPreview),
+    new: newFile,
+    newFile,
+    newFromTemplate,
     open: async () => {
       await fileOpen(editor, refreshPreview);
       syncToFile(getCurrentFilePath());
@@ -50,11 +79,26 @@ window.addEventListener('DOMContentLoaded', () => {
   // Wire view actions
   setViewActionHandlers({
     toggleFolder: toggleFolderPanel,
+    openFolder: (folderPath) => navigateToFolder(folderPath),
     viewSource: () => setViewMode(document, 'source'),
     viewPreview: () => setViewMode(document, 'preview'),
     viewSplit: () => setViewMode(document, 'split'),
     installQuickLook: installQuickLookPlugin,
+    copyAsImage: copyPreviewAsImage,
+    revealInFinder: revealCurrentFile,
+    openInDefaultApp,
+    moveToTrash: () => moveCurrentFileToTrash(editor, refreshPreview),
+    copyFilePath: () => copyCurrentFilePath(false),
+    copyRelativePath: () => copyCurrentFilePath(true),
+    exportHtml: () => exportHtml(editor),
+    exportPdf,
+    print: printPreview,
+    zoomIn: () => stepZoom(document, 1),
+    zoomOut: () => stepZoom(document, -1),
+    zoomReset: () => setZoom(document, 100),
     about: showAboutDialog,
+    setDefaultHandler: makeDefaultMarkdownApp,
+    checkForUpdates,
   });
 
   // Wire markdown formatting commands
@@ -63,13 +107,17 @@ window.addEventListener('DOMContentLoaded', () => {
   // Set up toolbar after handlers are registered
   setupToolbar(document);
   setViewMode(document, getViewMode() || 'split');
+  window.__TAURI__?.core?.invoke('get_zoom')
+    .then((factor) => setZoom(document, factor * 100, false))
+    .catch(() => {});
 
-  // Drag-and-drop to open files (sync folder panel after open)
-  setupDragDrop(editor, refreshPreview, fileOpenPath, syncToFile);
+  // Drop overlay; the backend opens dropped files through __openFile
+  setupDragDrop();
 
-  // Autosave on edit (1.5s debounce)
+  // Autosave on edit (debounced; the interval is a backend setting)
   if (editor) {
     setupAutosave(editor);
+    editor.addEventListener('paste', (event) => pasteImage(event, editor));
   }
 
   // Folder panel
@@ -80,15 +128,22 @@ window.addEventListener('DOMContentLoaded', () => {
   setupFolderPanel(openFromPanel);
   setupPanelResize();
 
-  // Handle native menu actions — delegates to the same registered handlers
-  window.__menuAction = (action) => {
-    onAction(action);
+  // Handle native menu actions — delegates to the same registered handlers.
+  // The backend emits `menu-action`; once we listen for it, its eval
+  // fallback through __menuAction stands down.
+  window.__menuAction = (action, ...args) => {
+    onAction(action, ...args);
   };
+  window.__TAURI__?.event.listen('menu-action', (event) => {
+    onAction(event.payload.action, ...event.payload.args);
+  }).then(() => {
+    window.__menuActionEvents = true;
+  }).catch(() => {});
 
   // Handle files opened via macOS "Open With" / Finder file associations.
   // Called from Rust (lib.rs) via eval when the app is already running.
-  window.__openFile = (filePath) => {
-    fileOpenPath(filePath, editor, refreshPreview).then(() => {
+  window.__openFile = (filePath, options) => {
+    fileOpenPath(filePath, editor, refreshPreview, options).then(() => {
       syncToFile(getCurrentFilePath());
     });
   };
@@ -96,12 +151,67 @@ window.addEventListener('DOMContentLoaded', () => {
   // Check if the app was launched by opening a .md file (e.g. double-click in Finder).
   // The Rust backend stores the path in managed state; we retrieve it via a Tauri command.
   if (window.__TAURI__) {
-    window.__TAURI__.core.invoke('get_opened_file').then((filePath) => {
-      if (filePath) {
-        window.__openFile(filePath);
+    // Several files may have queued up; the first opens here, the rest in
+    // windows of their own.
+    window.__TAURI__.core.invoke('get_opened_file').then(([first, ...rest]) => {
+      if (first) {
+        window.__openFile(first);
+      }
+      for (const path of rest) {
+        window.__TAURI__.core.invoke('create_window', { path }).catch(() => {});
       }
     }).catch(() => {});
 
+    // The backend asks for a PNG of the preview after `copy_as_image`
+    window.__TAURI__.event.listen('render-image', async (event) => {
+      try {
+        const png = await renderPreviewPng(preview, event.payload);
+        await window.__TAURI__.core.invoke('write_clipboard_image', { png });
+      } catch (err) {
+        await window.__TAURI__.dialog.message('Could not copy as image:\n' + err, { title: 'Copy as Image', kind: 'error' });
+      }
+    });
+
+    // Follow the saved theme; the backend sends `theme-changed` when it or
+    // (for the System theme) the OS appearance changes
+    window.__TAURI__.core.invoke('get_theme').then((theme) => {
+      const dark = theme === 'Dark'
+        || (theme === 'System' && window.matchMedia?.('(prefers-color-scheme: dark)').matches);
+      document.documentElement.dataset.theme = dark ? 'dark' : 'light';
+    }).catch(() => {});
+    window.__TAURI__.event.listen('theme-changed', (event) => {
+      document.documentElement.dataset.theme = event.payload;
+    });
+
+    // Editor preferences are stored by the backend; every window gets
+    // `editor-settings-changed` when one of them changes them
+    window.__TAURI__.core.invoke('get_editor_settings')
+      .then((settings) => applyEditorSettings(editor, settings))
+      .catch(() => {});
+    window.__TAURI__.event.listen('editor-settings-changed', (event) => {
+      applyEditorSettings(editor, event.payload);
+    });
+
+    // Reopen the folder the panel showed last session (the backend only
+    // sends it if the folder still exists)
+    window.__TAURI__.event.listen('restore-folder', (event) => {
+      navigateToFolder(event.payload);
+    });
+
+    // Quit or window close with unsaved changes: ask before discarding them
+    window.__TAURI__.event.listen('confirm-quit', (event) => {
+      confirmQuit(event.payload, editor);
+    });
+
+    // Remember the caret and scroll position when leaving the window
+    window.addEventListener('blur', () => saveViewState(editor));
+    window.addEventListener('beforeunload', () => saveViewState(editor));
+
+    // Reload the open file when another app changes it
+    window.__TAURI__.event.listen('file-changed', (event) => {
+      handleExternalChange(event.payload, editor, refreshPreview);
+    });
+
     // First-run: offer to install the Quick Look plugin for Markdown
     offerQuickLookInstall();
   }
@@ -115,6 +225,15 @@ function showAboutDialog() {
   if (!overlay) return;
   overlay.classList.remove('hidden');
 
+  // Replace the built-in version line with the running build's details
+  window.__TAURI__?.core.invoke('app_version_info').then((info) => {
+    const versionEl = overlay.querySelector('.about-version');
+    if (!versionEl) return;
+    const build = [info.commit, info.commit_date].filter(Boolean).join(', ');
+    versionEl.textContent = `Version ${info.version}${build ? ` (${build})` : ''}`;
+    versionEl.title = `Tauri ${info.tauri_version} · ${info.os} ${info.arch}`;
+  }).catch(() => {});
+
   // Close on clicking the overlay background (not the dialog itself)
   function onOverlayClick(e) {
     if (e.target === overlay) {
@@ -150,6 +269,304 @@ document.addEventListener('click', (e) => {
   }
 });
 
+/**
+ * Answer the backend's `confirm-quit` prompt. A single dirty document (this
+ * window's) can be saved first; with several, the user can only discard
+ * them all or cancel.
+ * @param {string[]} paths - dirty documents, "Untitled" for unsaved buffers
+ * @param {HTMLTextAreaElement} editor
+ */
+async function confirmQuit(paths, editor) {
+  const { invoke } = window.__TAURI__.core;
+  const { message } = window.__TAURI__.dialog;
+  const names = paths.map((p) => p.replace(/.*[\\/]/, '')).join(', ');
+  let proceed;
+  if (paths.length === 1 && isDirty()) {
+    const answer = await message(`Do you want to save the changes to ${names}?`, {
+      title: 'UpDown',
+      kind: 'warning',
+      buttons: { yes: 'Save', no: "Don't Save", cancel: 'Cancel' },
+    });
+    if (answer === 'Save' || answer === 'Yes') {
+      await fileSave(editor);
+      proceed = !isDirty();
+    } else {
+      proceed = answer === "Don't Save" || answer === 'No';
+    }
+  } else {
+    const answer = await message(`These documents have unsaved changes: ${names}`, {
+      title: 'UpDown',
+      kind: 'warning',
+      buttons: { ok: 'Discard Changes', cancel: 'Cancel' },
+    });
+    proceed = answer === 'Discard Changes' || answer === 'Ok';
+  }
+  await invoke('confirm_quit', { proceed });
+}
+
+/**
+ * File › Print…: print the rendered preview (the print stylesheet hides the
+ * editor). The backend opens the native dialog; `window.print` is a no-op
+ * in some webviews.
+ */
+async function printPreview() {
+  if (!window.__TAURI__) {
+    window.print();
+    return;
+  }
+  try {
+    await window.__TAURI__.core.invoke('print_preview');
+  } catch (err) {
+    await window.__TAURI__.dialog.message(String(err), { title: 'Print', kind: 'error' });
+  }
+}
+
+/**
+ * File › Export › HTML…: ask where to save, then have the backend render a
+ * standalone page with the preview styles inlined.
+ * @param {HTMLTextAreaElement} editor
+ */
+async function exportHtml(editor) {
+  if (!window.__TAURI__) return;
+  const current = getCurrentFilePath();
+  const name = current ? current.replace(/.*[\\/]/, '').replace(/\.[^.]*$/, '') : 'Untitled';
+  try {
+    const dest = await window.__TAURI__.dialog.save({
+      title: 'Export as HTML',
+      defaultPath: `${name}.html`,
+      filters: [{ name: 'HTML', extensions: ['html', 'htm'] }],
+    });
+    if (!dest) return;
+    await window.__TAURI__.core.invoke('export_html', { markdown: editor.value, dest, embedCss: true });
+  } catch (err) {
+    await window.__TAURI__.dialog.message('Could not export HTML:\n' + err, { title: 'Export', kind: 'error' });
+  }
+}
+
+/**
+ * UpDown › Make Default Markdown App…: claim `.md` files for UpDown and
+ * report what happened.
+ */
+async function makeDefaultMarkdownApp() {
+  if (!window.__TAURI__) return;
+  try {
+    const message = await window.__TAURI__.core.invoke('set_as_default_markdown_handler');
+    await window.__TAURI__.dialog.message(message, { title: 'UpDown', kind: 'info' });
+  } catch (err) {
+    await window.__TAURI__.dialog.message('Could not make UpDown the default Markdown app:\n' + err, { title: 'UpDown', kind: 'error' });
+  }
+}
+
+/**
+ * UpDown › Check for Updates…: compare with the latest release and offer
+ * to open its download page.
+ */
+async function checkForUpdates() {
+  if (!window.__TAURI__) return;
+  let info;
+  try {
+    info = await window.__TAURI__.core.invoke('check_for_updates');
+  } catch (err) {
+    await window.__TAURI__.dialog.message(String(err), { title: 'Check for Updates', kind: 'error' });
+    return;
+  }
+  if (!info.update_available) {
+    await window.__TAURI__.dialog.message(`You have the latest version of UpDown (${info.latest_version}).`, { title: 'Check for Updates', kind: 'info' });
+    return;
+  }
+  const url = info.download_url || info.notes_url;
+  const download = await window.__TAURI__.dialog.confirm(
+    `UpDown ${info.latest_version} is available.` + (url ? '\n\nDownload it now?' : ''),
+    { title: 'Check for Updates', kind: 'info', okLabel: url ? 'Download' : 'OK', cancelLabel: 'Not Now' }
+  );
+  if (download && url) {
+    window.__TAURI__.opener.openUrl(url);
+  }
+}
+
+/**
+ * Apply the saved editor preferences to the textarea. Line numbers are
+ * stored but the plain textarea has none to show.
+ * @param {HTMLTextAreaElement} editor
+ * @param {{ word_wrap: boolean, font_size: number, tab_width: number }} settings
+ */
+function applyEditorSettings(editor, settings) {
+  if (!editor || !settings) return;
+  editor.wrap = settings.word_wrap ? 'soft' : 'off';
+  editor.style.fontSize = `${settings.font_size}px`;
+  editor.style.tabSize = String(settings.tab_width);
+}
+
+/**
+ * Pasting an image: store it in `assets/` beside the note and insert a
+ * relative link instead of the image data.
+ * @param {ClipboardEvent} event
+ * @param {HTMLTextAreaElement} editor
+ */
+async function pasteImage(event, editor) {
+  if (!window.__TAURI__) return;
+  const file = [...(event.clipboardData?.files || [])].find((f) => f.type.startsWith('image/'));
+  if (!file) return;
+  event.preventDefault();
+  try {
+    const link = await window.__TAURI__.core.invoke('save_pasted_image', {
+      notePath: getCurrentFilePath() || '',
+      imageBytes: [...new Uint8Array(await file.arrayBuffer())],
+      suggestedExt: file.type.replace(/^image\//, '').replace(/\+.*$/, ''),
+    });
+    editor.setRangeText(`![](${link})`, editor.selectionStart, editor.selectionEnd, 'end');
+    editor.dispatchEvent(new Event('input'));
+  } catch (err) {
+    await window.__TAURI__.dialog.message('Could not paste image:\n' + err, { title: 'UpDown', kind: 'error' });
+  }
+}
+
+/**
+ * File › Export › PDF…: ask where to save, then have the backend print the
+ * preview to that file. The page takes its print layout meanwhile.
+ */
+async function exportPdf() {
+  if (!window.__TAURI__) return;
+  const current = getCurrentFilePath();
+  const name = current ? current.replace(/.*[\\/]/, '').replace(/\.[^.]*$/, '') : 'Untitled';
+  try {
+    const dest = await window.__TAURI__.dialog.save({
+      title: 'Export as PDF',
+      defaultPath: `${name}.pdf`,
+      filters: [{ name: 'PDF', extensions: ['pdf'] }],
+    });
+    if (!dest) return;
+    document.body.classList.add('pdf-export');
+    await window.__TAURI__.core.invoke('export_pdf', { dest, options: { pageSize: 'A4', marginMm: 20 } });
+  } catch (err) {
+    await window.__TAURI__.dialog.message('Could not export PDF:\n' + err, { title: 'Export', kind: 'error' });
+  } finally {
+    document.body.classList.remove('pdf-export');
+  }
+}
+
+/**
+ * Show the open file in the OS file manager.
+ */
+async function revealCurrentFile() {
+  if (!window.__TAURI__) return;
+  try {
+    await window.__TAURI__.core.invoke('reveal_in_file_manager', { path: getCurrentFilePath() || '' });
+  } catch (err) {
+    await window.__TAURI__.dialog.message(String(err), { title: 'UpDown', kind: 'error' });
+  }
+}
+
+/**
+ * Move the current file to the OS trash and start an empty document.
+ * Unsaved edits would go with it, so those need confirming first.
+ * @param {HTMLTextAreaElement} editor
+ * @param {function} refreshPreview
+ */
+async function moveCurrentFileToTrash(editor, refreshPreview) {
+  if (!window.__TAURI__) return;
+  const path = getCurrentFilePath();
+  if (isDirty()) {
+    const discard = await window.__TAURI__.dialog.confirm(
+      'Move this file to the Trash and discard its unsaved changes?',
+      { title: 'UpDown', kind: 'warning', okLabel: 'Move to Trash', cancelLabel: 'Cancel' }
+    );
+    if (!discard) return;
+  }
+  try {
+    await window.__TAURI__.core.invoke('trash_file', { path: path || '' });
+    fileNew(editor, refreshPreview);
+    refreshFolder();
+  } catch (err) {
+    await window.__TAURI__.dialog.message(String(err), { title: 'UpDown', kind: 'error' });
+  }
+}
+
+/**
+ * Open the current file in the OS default app for its type.
+ */
+async function openInDefaultApp() {
+  if (!window.__TAURI__) return;
+  try {
+    await window.__TAURI__.core.invoke('open_in_default_app', { path: getCurrentFilePath() || '' });
+  } catch (err) {
+    await window.__TAURI__.dialog.message(String(err), { title: 'UpDown', kind: 'error' });
+  }
+}
+
+/**
+ * Copy the open file's path, optionally relative to the folder panel's folder.
+ * @param {boolean} relative
+ */
+async function copyCurrentFilePath(relative) {
+  if (!window.__TAURI__) return;
+  try {
+    await window.__TAURI__.core.invoke('copy_file_path', {
+      path: getCurrentFilePath() || '',
+      relativeTo: relative ? getCurrentFolder() : null,
+    });
+  } catch (err) {
+    await window.__TAURI__.dialog.message(String(err), { title: 'UpDown', kind: 'error' });
+  }
+}
+
+/**
+ * Copy the rendered preview to the clipboard as an image. The backend checks
+ * that a document is open, then replies with a `render-image` event.
+ */
+async function copyPreviewAsImage() {
+  if (!window.__TAURI__) return;
+  const preview = document.getElementById('preview');
+  try {
+    await window.__TAURI__.core.invoke('copy_as_image', {
+      width: Math.round(preview?.clientWidth || 800),
+      scale: Math.min(Math.max(window.devicePixelRatio || 1, 0.5), 4),
+    });
+  } catch (err) {
+    await window.__TAURI__.dialog.message('Could not copy as image:\n' + err, { title: 'Copy as Image', kind: 'error' });
+  }
+}
+
+/**
+ * Render the preview element to PNG bytes by drawing it through an SVG
+ * foreignObject, with the page's stylesheets inlined.
+ * @param {HTMLElement} preview
+ * @param {{ width: number, scale: number, pixelWidth: number }} size
+ * @returns {Promise<number[]>}
+ */
+async function renderPreviewPng(preview, { width, scale, pixelWidth }) {
+  const css = [...document.styleSheets].map((sheet) => {
+    try {
+      return [...sheet.cssRules].map((rule) => rule.cssText).join('\n');
+    } catch {
+      return '';
+    }
+  }).join('\n').replace(/&/g, '&amp;').replace(/</g, '&lt;');
+  const clone = preview.cloneNode(true);
+  clone.style.width = `${width}px`;
+  clone.style.height = 'auto';
+  clone.style.overflow = 'visible';
+  const height = Math.ceil(preview.scrollHeight * (width / Math.max(preview.clientWidth, 1)));
+  const body = new XMLSerializer().serializeToString(clone);
+  const svg = `<svg xmlns="http://www.w3.org/2000/svg" width="${width}" height="${height}">` +
+    `<foreignObject width="100%" height="100%"><div xmlns="http://www.w3.org/1999/xhtml">` +
+    `<style>${css}</style>${body}</div></foreignObject></svg>`;
+
+  const img = new Image();
+  img.src = 'data:image/svg+xml;charset=utf-8,' + encodeURIComponent(svg);
+  await img.decode();
+  const canvas = document.createElement('canvas');
+  canvas.width = pixelWidth;
+  canvas.height = Math.ceil(height * scale);
+  const ctx = canvas.getContext('2d');
+  ctx.fillStyle = getComputedStyle(preview).backgroundColor || '#fff';
+  ctx.fillRect(0, 0, canvas.width, canvas.height);
+  ctx.scale(scale, scale);
+  ctx.drawImage(img, 0, 0);
+  const blob = await new Promise((resolve) => canvas.toBlob(resolve, 'image/png'));
+  return Array.from(new Uint8Array(await blob.arrayBuffer()));
+}
+
 /**
  * Install the Quick Look plugin for Markdown preview in Finder (Space key).
  * Called from the menu action or the first-run prompt.
@@ -168,22 +585,39 @@ async function installQuickLookPlugin() {
 }
 
 /**
- * On first run, offer to install the Quick Look plugin.
+ * On first run, offer to install the Quick Look plugin; later, offer once
+ * per release to update an installed plugin that is out of date.
  * Uses localStorage to remember that the offer was made.
  */
 async function offerQuickLookInstall() {
   if (!window.__TAURI__) return;
-  if (localStorage.getItem('ql_plugin_offered')) return;
+
+  let status;
+  try {
+    status = await window.__TAURI__.core.invoke('quicklook_status');
+  } catch {
+    return; // Not macOS, or no bundled plugin
+  }
+  if (status.installed && status.installed_version === status.bundled_version) return;
+
+  const key = status.installed ? `ql_plugin_update_offered_${status.bundled_version}` : 'ql_plugin_offered';
+  if (localStorage.getItem(key)) return;
 
   // Mark as offered so we only ask once
-  localStorage.setItem('ql_plugin_offered', '1');
+  localStorage.setItem(key, '1');
 
   try {
-    const yes = await window.__TAURI__.dialog.confirm(
-      'Would you like to install the Quick Look plugin?\n\n' +
-      'This lets you preview Markdown files by pressing Space in Finder.',
-      { title: 'Quick Look for Markdown', kind: 'info', okLabel: 'Install', cancelLabel: 'Not Now' }
-    );
+    const yes = status.installed
+      ? await window.__TAURI__.dialog.confirm(
+        `An older Quick Look plugin (${status.installed_version || 'unknown version'}) is installed.\n\n` +
+        `Update it to ${status.bundled_version}?`,
+        { title: 'Quick Look for Markdown', kind: 'info', okLabel: 'Update', cancelLabel: 'Not Now' }
+      )
+      : await window.__TAURI__.dialog.confirm(
+        'Would you like to install the Quick Look plugin?\n\n' +
+        'This lets you preview Markdown files by pressing Space in Finder.',
+        { title: 'Quick Look for Markdown', kind: 'info', okLabel: 'Install', cancelLabel: 'Not Now' }
+      );
     if (yes) {
       await installQuickLookPlugin();
     }
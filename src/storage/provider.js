@@ -12,6 +12,7 @@
  * - showOpenDialog?() -> Promise<fileId|null>
  * - showSaveDialog?(defaultName) -> Promise<{parentId, name}|null>
  * - getRootFolderId?() -> Promise<folderId>
+ * - isReadOnly?(fileId) -> boolean (as of the last readFile)
  */
 
 let storageProvider = null;
@@ -3,6 +3,7 @@
  * Wraps Tauri FS and dialog APIs for local file system access.
  * Uses path strings as ids (e.g. "/Users/name/folder/file.md").
  */
+import { isOpenablePath } from '../utils.js';
 
 /**
  * Create the Tauri storage provider.
@@ -12,8 +13,14 @@ export function createTauriProvider() {
   const tauri = window.__TAURI__;
   if (!tauri?.fs) return null;
 
-  const { readTextFile, writeTextFile, readDir } = tauri.fs;
+  const { writeTextFile, readDir } = tauri.fs;
   const { open, save } = tauri.dialog;
+  // Paths whose last read found them unwritable; see isReadOnly
+  const readOnlyFiles = new Set();
+  // Paths already warned about being re-encoded to UTF-8 on save
+  const encodingNoticed = new Set();
+  // Each path's modification time and size as last read or saved; see diskStamp
+  const diskStamps = new Map();
 
   return {
     async listDirectory(folderId) {
@@ -31,7 +38,7 @@ export function createTauriProvider() {
           })
           .filter((entry) => {
             if (entry.isDirectory) return !entry.name.startsWith('.');
-            return /\.(md|markdown)$/i.test(entry.name);
+            return isOpenablePath(entry.name);
           })
           .sort((a, b) => {
             if (a.isDirectory && !b.isDirectory) return -1;
@@ -45,11 +52,43 @@ export function createTauriProvider() {
     },
 
     async readFile(fileId) {
-      return readTextFile(fileId);
+      const { content, encoding, writable, disk } = await tauri.core.invoke('read_file', { path: fileId });
+      diskStamps.set(fileId, disk);
+      if (encoding !== 'UTF-8' && !encodingNoticed.has(fileId)) {
+        encodingNoticed.add(fileId);
+        // Not awaited: the file opens behind the notice
+        const name = fileId.split(/[\\/]/).pop();
+        tauri.dialog.message(
+          `${name} is encoded as ${encoding}. Saving it will convert it to UTF-8.`,
+          { title: 'UpDown', kind: 'info' }
+        ).catch(() => {});
+      }
+      if (writable) {
+        readOnlyFiles.delete(fileId);
+      } else {
+        readOnlyFiles.add(fileId);
+      }
+      return content;
+    },
+
+    /** Whether the last read of `fileId` found it locked or on a read-only volume. */
+    isReadOnly(fileId) {
+      return readOnlyFiles.has(fileId);
+    },
+
+    /** The file as last read or saved, for auto-save to detect changes made elsewhere. */
+    diskStamp(fileId) {
+      return diskStamps.get(fileId) ?? null;
+    },
+
+    /** Record the stamp of a write made by the backend (auto-save). */
+    noteDiskStamp(fileId, disk) {
+      diskStamps.set(fileId, disk);
     },
 
     async writeFile(fileId, content) {
-      await writeTextFile(fileId, content);
+      const disk = await tauri.core.invoke('save_file', { path: fileId, content });
+      diskStamps.set(fileId, disk);
     },
 
     async createFile(parentId, name, content) {
@@ -15,3 +15,29 @@ export function debounce(fn, delayMs) {
     timer = setTimeout(() => fn.apply(this, args), delayMs);
   };
 }
+
+/** File extensions opened as markdown; replaced by the user's setting in Tauri. */
+let openableExtensions = ['md', 'markdown'];
+
+/**
+ * Load the openable-extension allowlist from the backend settings.
+ * @returns {Promise<void>}
+ */
+export async function loadOpenableExtensions() {
+  if (!window.__TAURI__?.core?.invoke) return;
+  try {
+    openableExtensions = await window.__TAURI__.core.invoke('get_openable_extensions');
+  } catch {
+    // Keep the defaults
+  }
+}
+
+/**
+ * Whether a file name or path has an openable markdown extension.
+ * @param {string} name
+ * @returns {boolean}
+ */
+export function isOpenablePath(name) {
+  const dot = name.lastIndexOf('.');
+  return dot >= 0 && openableExtensions.includes(name.slice(dot + 1).toLowerCase());
+}
@@ -5,6 +5,7 @@ vi.mock('../src/file-ops.js', () => ({
   getCurrentFilePath: vi.fn(),
   checkDirty: vi.fn(),
   fileSave: vi.fn().mockResolvedValue(undefined),
+  markFileSaved: vi.fn(),
 }));
 
 const { getCurrentFilePath, checkDirty, fileSave } = await import('../src/file-ops.js');
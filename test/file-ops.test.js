@@ -51,6 +51,13 @@ describe('file-ops', () => {
       fileNew(editor, refreshPreview);
       expect(refreshPreview).toHaveBeenCalledTimes(1);
     });
+
+    it('starts from template content as an unsaved document', () => {
+      fileNew(editor, refreshPreview, '# Meeting\n');
+      expect(editor.value).toBe('# Meeting\n');
+      expect(getCurrentFilePath()).toBeNull();
+      expect(isDirty()).toBe(true);
+    });
   });
 
   describe('fileOpenPath', () => {